@@ -0,0 +1,20 @@
+create table guilds (
+    id serial constraint guilds_pk primary key,
+    server_id integer not null,
+    name varchar not null,
+    notice_title varchar default '' not null,
+    notice varchar default '' not null,
+    created timestamp with time zone default now() not null
+);
+
+create unique index guilds_server_id_name_uindex on guilds (server_id, lower(name));
+
+create table guild_members (
+    character_id integer not null constraint guild_members_pk primary key
+        constraint guild_members_characters_id_fk references characters on delete cascade,
+    guild_id integer not null constraint guild_members_guilds_id_fk references guilds on delete cascade,
+    rank smallint not null,
+    joined timestamp with time zone default now() not null
+);
+
+create index guild_members_guild_id_index on guild_members (guild_id);
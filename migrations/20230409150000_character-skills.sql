@@ -0,0 +1,5 @@
+create table character_skills (
+    character_id integer not null constraint character_skills_characters_id_fk references characters on delete cascade,
+    skill_id integer not null,
+    constraint character_skills_pk primary key (character_id, skill_id)
+);
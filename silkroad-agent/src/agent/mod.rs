@@ -1,8 +1,10 @@
 use crate::agent::event::{ActionFinished, MovementFinished};
-use crate::agent::states::{action, dead, movement, pickup, turning, update_target_location};
+use crate::agent::states::{
+    action, dead, movement, pickup, recover, turning, update_target_location, KnockedDown, Stunned,
+};
 use crate::agent::system::{
-    movement_input, transition_from_attacking, transition_from_idle, transition_from_moving, transition_from_sitting,
-    transition_to_idle,
+    movement_input, transition_from_attacking, transition_from_idle, transition_from_incapacitated,
+    transition_from_moving, transition_from_sitting, transition_to_idle,
 };
 use bevy_app::{App, Plugin, PostUpdate, PreUpdate, Update};
 use bevy_ecs::prelude::*;
@@ -40,6 +42,8 @@ impl Plugin for AgentPlugin {
                     transition_from_moving,
                     transition_from_sitting,
                     transition_from_attacking,
+                    transition_from_incapacitated::<Stunned>,
+                    transition_from_incapacitated::<KnockedDown>,
                 )
                     .in_set(AgentSet::Transition),
             )
@@ -52,6 +56,7 @@ impl Plugin for AgentPlugin {
                     action,
                     turning,
                     dead,
+                    recover,
                 )
                     .in_set(AgentSet::Execute),
             );
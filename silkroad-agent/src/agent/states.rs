@@ -3,6 +3,7 @@ mod dead;
 mod idle;
 mod movement;
 mod sitting;
+mod stunned;
 
 pub(crate) use action::*;
 use bevy_ecs::prelude::*;
@@ -12,6 +13,7 @@ pub(crate) use idle::*;
 pub(crate) use movement::*;
 pub(crate) use sitting::*;
 use std::collections::VecDeque;
+pub(crate) use stunned::*;
 
 pub(crate) trait State {
     const ORDER: usize;
@@ -25,6 +27,8 @@ pub(crate) enum StateChange {
     Action(Action),
     Pickup(Pickup),
     Dead(Dead),
+    Stunned(Stunned),
+    KnockedDown(KnockedDown),
 }
 
 impl StateChange {
@@ -43,6 +47,8 @@ impl StateChange {
             StateChange::Action(inner) => entity_cmd.insert(inner),
             StateChange::Dead(inner) => entity_cmd.insert(inner),
             StateChange::Pickup(inner) => entity_cmd.insert(inner),
+            StateChange::Stunned(inner) => entity_cmd.insert(inner),
+            StateChange::KnockedDown(inner) => entity_cmd.insert(inner),
         };
     }
 }
@@ -66,7 +72,9 @@ impl_state!(Moving, 1, true);
 impl_state!(Action, 2, false);
 impl_state!(Sitting, 1, true);
 impl_state!(Pickup, 2, false);
-impl_state!(Dead, 3, false);
+impl_state!(Stunned, 3, false);
+impl_state!(KnockedDown, 3, false);
+impl_state!(Dead, 4, false);
 
 pub(crate) struct StateTransition {
     data: StateChange,
@@ -1,15 +1,28 @@
-use crate::agent::states::Idle;
+use crate::agent::states::{Dead, Idle};
+use crate::comp::cooldown::SkillCooldowns;
+use crate::comp::drop::LootSharing;
 use crate::comp::inventory::PlayerInventory;
+use crate::comp::monster::Monster;
 use crate::comp::net::Client;
-use crate::comp::{drop, EntityReference, GameEntity};
-use crate::event::{AttackDefinition, DamageReceiveEvent};
-use crate::game::attack::AttackInstanceCounter;
+use crate::comp::player::Player;
+use crate::comp::pos::Position;
+use crate::comp::visibility::Visibility;
+use crate::comp::{drop, EntityReference, GameEntity, Health, Mana};
+use crate::event::{AttackDefinition, BuffApplyEvent, DamageReceiveEvent};
+use crate::game::attack::{pay_for_skill, AttackInstanceCounter, Caster};
+use crate::game::damage::CombatStats;
+use crate::world::WorldData;
 use bevy_ecs::prelude::*;
-use bevy_ecs::query::QueryEntityError;
+use bevy_ecs::query::{Has, QueryEntityError, WorldQuery};
+use bevy_ecs::system::SystemParam;
 use bevy_time::{Time, Timer, TimerMode};
-use silkroad_data::skilldata::{RefSkillData, SkillParam};
+use cgmath::{Deg, InnerSpace, Quaternion, Rotation3, Vector3};
+use silkroad_data::skilldata::RefSkillData;
 use silkroad_data::DataEntry;
-use silkroad_game_base::{GlobalLocation, ItemTypeData};
+use silkroad_game_base::{
+    buff_duration, calculate_damage, hit_count, AreaOfEffect, AreaOrigin, DamageOutcome, GlobalLocation, ItemTypeData,
+    SkillDamage, Vector3Ext,
+};
 use silkroad_protocol::combat::{DoActionResponseCode, PerformActionError, PerformActionResponse};
 use silkroad_protocol::inventory::{InventoryItemContentData, InventoryOperationError, InventoryOperationResult};
 use silkroad_protocol::world::CharacterPointsUpdate;
@@ -31,6 +44,21 @@ pub(crate) struct Action {
     target: ActionTarget,
     state: ActionProgressState,
     progress: Timer,
+    hits: Option<PendingHits>,
+}
+
+/// The hits of an attack which have not been dealt yet. The hits of a skill are spread evenly over its execution,
+/// instead of all landing at once. The damage of every hit is already decided when the attack starts, such that it can
+/// be reported to the client with the first hit.
+#[derive(Clone)]
+struct PendingHits {
+    skill: &'static RefSkillData,
+    instance: u32,
+    /// The entities hit by the attack, together with the damage each of the hits deals to them.
+    targets: Vec<(Entity, Vec<DamageOutcome>)>,
+    dealt: u32,
+    remaining: u32,
+    interval: Timer,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -80,6 +108,7 @@ impl From<ActionDescription> for Action {
                 Duration::from_millis(value.0.timings.preparation_time.into()),
                 TimerMode::Once,
             ),
+            hits: None,
         }
     }
 }
@@ -90,6 +119,7 @@ pub(crate) struct Pickup(pub Entity, pub Option<Timer>);
 
 pub(crate) fn pickup(
     mut query: Query<(Entity, &Client, &mut Pickup, &mut PlayerInventory)>,
+    picker_query: Query<(&GameEntity, Option<&LootSharing>)>,
     time: Res<Time>,
     target_query: Query<&drop::Drop>,
     mut cmd: Commands,
@@ -116,6 +146,17 @@ pub(crate) fn pickup(
                 },
             };
 
+            let may_pick_up = picker_query
+                .get(entity)
+                .map(|(game_entity, sharing)| drop.may_be_picked_up_by(game_entity.unique_id, sharing))
+                .unwrap_or(false);
+            if !may_pick_up {
+                client.send(InventoryOperationResult::Error(InventoryOperationError::CannotBePicked));
+                client.send(PerformActionResponse::Stop(PerformActionError::InvalidTarget));
+                cmd.entity(entity).remove::<Pickup>();
+                continue;
+            }
+
             cmd.entity(pickup.0).despawn();
             pickup.1 = Some(Timer::from_seconds(1.0, TimerMode::Once));
 
@@ -147,47 +188,306 @@ pub(crate) fn pickup(
     }
 }
 
-pub(crate) fn action(
-    mut query: Query<(Entity, &GameEntity, &mut Action)>,
-    target_query: Query<&GameEntity>,
-    time: Res<Time>,
-    mut attack_instance_counter: ResMut<AttackInstanceCounter>,
-    mut cmd: Commands,
-    mut damage_event: EventWriter<DamageReceiveEvent>,
-) {
+/// An entity that may get hit by an attack.
+#[derive(WorldQuery)]
+pub(crate) struct AttackTarget {
+    position: &'static Position,
+    player: Has<Player>,
+    monster: Has<Monster>,
+    health: Option<&'static Health>,
+}
+
+impl AttackTargetItem<'_> {
+    /// An entity may already have died from an earlier hit, even if it didn't transition to being dead yet.
+    fn is_alive(&self) -> bool {
+        !self.health.is_some_and(|health| health.is_dead())
+    }
+
+    /// Players only attack monsters and monsters only attack players.
+    fn is_hostile_to(&self, attacker_is_monster: bool) -> bool {
+        if attacker_is_monster {
+            self.player
+        } else {
+            self.monster
+        }
+    }
+}
+
+/// The entity performing an action.
+#[derive(WorldQuery)]
+#[world_query(mutable)]
+pub(crate) struct Actor {
+    entity: Entity,
+    game_entity: &'static GameEntity,
+    action: &'static mut Action,
+    position: &'static Position,
+    visibility: Option<&'static Visibility>,
+    monster: Has<Monster>,
+    client: Option<&'static Client>,
+    inventory: Option<&'static PlayerInventory>,
+    mana: Option<&'static mut Mana>,
+    cooldowns: Option<&'static mut SkillCooldowns>,
+}
+
+/// Everything needed to execute the effect of a skill, like finding the entities it hits and dealing damage to them.
+#[derive(SystemParam)]
+pub(crate) struct SkillExecution<'w, 's> {
+    targets: Query<'w, 's, AttackTarget, Without<Dead>>,
+    stats: Query<'w, 's, CombatStats>,
+    attack_instance_counter: ResMut<'w, AttackInstanceCounter>,
+    damage_event: EventWriter<'w, DamageReceiveEvent>,
+    buff_event: EventWriter<'w, BuffApplyEvent>,
+}
+
+impl SkillExecution<'_, '_> {
+    /// Provides all entities hit by the action, starting with the targeted entity. For skills covering an area, this
+    /// also includes the closest hostile entities within that area. The source never hits itself, even when it is
+    /// the target of the action.
+    fn find(
+        &self,
+        source: Entity,
+        action: &Action,
+        position: &Position,
+        visibility: Option<&Visibility>,
+        attacker_is_monster: bool,
+    ) -> Vec<Entity> {
+        let primary = match action.target {
+            ActionTarget::Entity(target) if target != source => Some(target).filter(|target| {
+                self.targets
+                    .get(*target)
+                    .is_ok_and(|target| target.is_hostile_to(attacker_is_monster))
+            }),
+            _ => None,
+        };
+        let mut targets: Vec<Entity> = primary.into_iter().collect();
+
+        let (Some(area), Some(visibility)) = (AreaOfEffect::from_skill(action.skill), visibility) else {
+            return targets;
+        };
+
+        let own_location = position.location();
+        let target_location = match action.target {
+            ActionTarget::Entity(target) => self.targets.get(target).ok().map(|target| target.position.location()),
+            ActionTarget::Location(location) => Some(location),
+            ActionTarget::None | ActionTarget::Own => None,
+        };
+        let center = match (area.origin, target_location) {
+            (AreaOrigin::Target, Some(location)) => location,
+            _ => own_location,
+        };
+        let facing = match target_location {
+            Some(location) if location.0 != own_location.0 => (location.0 - own_location.0).normalize(),
+            _ => (Quaternion::from_angle_y(Deg(position.rotation().0)) * Vector3::unit_x()).to_flat_vec2(),
+        };
+
+        let candidates = visibility
+            .entities_in_radius
+            .iter()
+            .filter(|reference| reference.0 != source && Some(reference.0) != primary)
+            .filter_map(|reference| {
+                self.targets
+                    .get(reference.0)
+                    .ok()
+                    .filter(|target| target.is_hostile_to(attacker_is_monster))
+                    .map(|target| (reference.0, target.position.location()))
+            });
+        targets.extend(area.select_targets(center, facing, candidates));
+        targets.truncate(area.max_targets);
+        targets
+    }
+
+    /// Provides the next skill in the chain of the currently executed skill, as long as there is still something to
+    /// use it on and the caster is still able to use it. Each part of a chain is a skill of its own, so it has to be
+    /// paid for just like the skill starting the chain.
+    fn next_in_chain(&self, action: &Action, caster: Caster) -> Option<&'static RefSkillData> {
+        let next = action.skill.next_in_chain?;
+        if let ActionTarget::Entity(target) = action.target {
+            self.targets.get(target).ok()?;
+        }
+        let next = WorldData::skills().find_id(next.get())?;
+        caster.check(next).ok()?;
+        Some(next)
+    }
+
+    /// Applies the effect of the skill, by either starting to deal damage to everything it hits or applying its buff.
+    /// The first hit lands right away, while further hits follow over the execution of the skill.
+    fn execute(
+        &mut self,
+        source: EntityReference,
+        action: &mut Action,
+        position: &Position,
+        visibility: Option<&Visibility>,
+        attacker_is_monster: bool,
+    ) {
+        if let Some(damage) = SkillDamage::from_skill(action.skill) {
+            let hits = hit_count(action.skill);
+            let duration = ActionProgressState::Execution.get_time_for(action.skill).unwrap_or(0) as u64;
+            let targets = self.find(source.0, action, position, visibility, attacker_is_monster);
+            let mut pending = PendingHits {
+                skill: action.skill,
+                // All entities hit by this action share the same instance, so they are reported together.
+                instance: self.attack_instance_counter.next(),
+                targets: self.roll_hits(source.0, &damage, targets, hits),
+                dealt: 0,
+                remaining: hits,
+                // Skills without a duration still need a non-zero interval, their hits all land once they finish.
+                interval: Timer::new(
+                    Duration::from_millis(duration / u64::from(hits)).max(Duration::from_millis(1)),
+                    TimerMode::Repeating,
+                ),
+            };
+            self.hit(source, &mut pending);
+            action.hits = Some(pending);
+        } else if let Some(duration) = buff_duration(action.skill) {
+            let target = match action.target {
+                ActionTarget::Own => source.0,
+                ActionTarget::Entity(target) => target,
+                ActionTarget::None | ActionTarget::Location(_) => return,
+            };
+            self.buff_event.send(BuffApplyEvent {
+                target,
+                skill: action.skill,
+                duration,
+            });
+        }
+    }
+
+    /// Rolls the damage of each of the hits the attacker deals to the given targets.
+    fn roll_hits(
+        &self,
+        source: Entity,
+        damage: &SkillDamage,
+        targets: Vec<Entity>,
+        hits: u32,
+    ) -> Vec<(Entity, Vec<DamageOutcome>)> {
+        let Ok(attacker) = self.stats.get(source) else {
+            return Vec::new();
+        };
+        let offense = attacker.offense();
+        let mut rng = rand::thread_rng();
+        targets
+            .into_iter()
+            .filter_map(|target| {
+                let defense = self.stats.get(target).ok()?.defense();
+                let rolls = (0..hits)
+                    .map(|_| calculate_damage(&mut rng, damage, &offense, &defense))
+                    .collect();
+                Some((target, rolls))
+            })
+            .collect()
+    }
+
+    /// Deals the hits of the action which became due in the elapsed time.
+    fn continue_hits(&mut self, source: EntityReference, action: &mut Action, delta: Duration) {
+        let Some(pending) = action.hits.as_mut() else {
+            return;
+        };
+        let due = pending.interval.tick(delta).times_finished_this_tick();
+        for _ in 0..due.min(pending.remaining) {
+            self.hit(source, pending);
+        }
+    }
+
+    /// Deals all remaining hits of the action, as its execution is over.
+    fn finish_hits(&mut self, source: EntityReference, action: &mut Action) {
+        let Some(mut pending) = action.hits.take() else {
+            return;
+        };
+        while pending.remaining > 0 {
+            self.hit(source, &mut pending);
+        }
+    }
+
+    /// Deals a single hit to each of the targets that are still alive.
+    fn hit(&mut self, source: EntityReference, pending: &mut PendingHits) {
+        pending.remaining = pending.remaining.saturating_sub(1);
+        let hit = pending.dealt as usize;
+        pending.dealt += 1;
+
+        for (target, damage) in pending.targets.iter() {
+            // A target killed by an earlier hit has already been reported as such, so there's nothing left to deal.
+            if !self.targets.get(*target).is_ok_and(|target| target.is_alive()) {
+                continue;
+            }
+            let (Ok(defender), Some(outcome)) = (self.stats.get(*target), damage.get(hit)) else {
+                continue;
+            };
+            let later_hits = if hit == 0 { damage[1..].to_vec() } else { Vec::new() };
+            self.damage_event.send(DamageReceiveEvent {
+                source,
+                target: EntityReference(*target, *defender.entity),
+                attack: AttackDefinition {
+                    skill: pending.skill,
+                    instance: Some(pending.instance),
+                    first_hit: hit == 0,
+                },
+                damage: *outcome,
+                later_hits,
+            });
+        }
+    }
+}
+
+pub(crate) fn action(mut query: Query<Actor>, mut execution: SkillExecution, time: Res<Time>, mut cmd: Commands) {
     let delta = time.delta();
-    for (entity, game_entity, mut action) in query.iter_mut() {
-        if action.progress.tick(delta).just_finished() {
-            if let Some(next) = action.state.next() {
+    for ActorItem {
+        entity,
+        game_entity,
+        mut action,
+        position,
+        visibility,
+        monster,
+        client,
+        inventory,
+        mut mana,
+        mut cooldowns,
+    } in query.iter_mut()
+    {
+        if action.is_added() {
+            if let Err(error) = Caster::new(inventory, mana.as_deref(), cooldowns.as_deref()).check(action.skill) {
+                if let Some(client) = client {
+                    client.send(PerformActionResponse::Stop(error.as_protocol()));
+                }
+                cmd.entity(entity).remove::<Action>();
+                continue;
+            }
+            pay_for_skill(action.skill, mana.as_deref_mut(), cooldowns.as_deref_mut());
+        }
+
+        let source = EntityReference(entity, *game_entity);
+        let finished = action.progress.tick(delta).just_finished();
+        if action.state == ActionProgressState::Execution {
+            if finished {
+                execution.finish_hits(source, &mut action);
+            } else {
+                execution.continue_hits(source, &mut action, delta);
+            }
+        }
+
+        if finished {
+            let chained = if action.state == ActionProgressState::Execution {
+                execution.next_in_chain(&action, Caster::new(inventory, mana.as_deref(), cooldowns.as_deref()))
+            } else {
+                None
+            };
+
+            if let Some(chained) = chained {
+                // The next skill of a chain follows right away, without going through preparation and casting again.
+                let time = ActionProgressState::Execution.get_time_for(chained).unwrap_or(0);
+                pay_for_skill(chained, mana.as_deref_mut(), cooldowns.as_deref_mut());
+                action.skill = chained;
+                action.progress = Timer::new(Duration::from_millis(time as u64), TimerMode::Once);
+                if let Some(client) = client {
+                    client.send(PerformActionResponse::Do(DoActionResponseCode::Success));
+                }
+                execution.execute(source, &mut action, position, visibility, monster);
+            } else if let Some(next) = action.state.next() {
                 let time = next.get_time_for(action.skill).unwrap_or(0);
                 action.state = next;
                 action.progress = Timer::new(Duration::from_millis(time as u64), TimerMode::Once);
 
                 if next == ActionProgressState::Execution {
-                    let attack = action
-                        .skill
-                        .params
-                        .iter()
-                        .find(|param| matches!(param, SkillParam::Attack { .. }))
-                        .unwrap();
-                    match attack {
-                        SkillParam::Attack { .. } => {
-                            let ActionTarget::Entity(target) = action.target else {
-                                panic!();
-                            };
-                            let target_ = target_query.get(target).unwrap();
-                            damage_event.send(DamageReceiveEvent {
-                                source: EntityReference(entity, *game_entity),
-                                target: EntityReference(target, *target_),
-                                attack: AttackDefinition {
-                                    skill: action.skill,
-                                    instance: attack_instance_counter.next(),
-                                },
-                                amount: 10,
-                            });
-                        },
-                        _ => {},
-                    }
+                    execution.execute(source, &mut action, position, visibility, monster);
                 }
             } else {
                 cmd.entity(entity).remove::<Action>();
@@ -195,3 +495,144 @@ pub(crate) fn action(
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::event::{BuffApplyEvent, DamageReceiveEvent};
+    use bevy_ecs::system::SystemState;
+    use once_cell::sync::Lazy;
+    use silkroad_data::skilldata::SkillParam;
+    use silkroad_game_base::{GlobalPosition, Heading};
+
+    static AREA_SKILL: Lazy<RefSkillData> = Lazy::new(|| {
+        let mut skill = RefSkillData::test_skill(1);
+        skill.requires_target = false;
+        skill.params = vec![SkillParam::AOE {
+            origin: 0,
+            area_type: 0,
+            area_size: 50,
+            unknown_type: 0,
+            val1: 0,
+            val2: 0,
+        }];
+        skill
+    });
+
+    fn spawn_at(world: &mut World, unique_id: u32, x: f32, monster: bool) -> EntityReference {
+        let game_entity = GameEntity { unique_id, ref_id: 1 };
+        let position = Position::new(GlobalPosition(Vector3::new(x, 0.0, 0.0)), Heading(0.0));
+        let mut entity = world.spawn((game_entity, position));
+        if monster {
+            entity.insert(Monster {
+                target: None,
+                rarity: Default::default(),
+            });
+        }
+        EntityReference(entity.id(), game_entity)
+    }
+
+    #[test]
+    fn test_targetless_area_skips_caster() {
+        let mut world = World::new();
+        world.init_resource::<AttackInstanceCounter>();
+        world.init_resource::<Events<DamageReceiveEvent>>();
+        world.init_resource::<Events<BuffApplyEvent>>();
+
+        let caster = spawn_at(&mut world, 1, 0.0, false);
+        let close = spawn_at(&mut world, 2, 20.0, true);
+        let far = spawn_at(&mut world, 3, 200.0, true);
+        let mut visibility = Visibility::with_radius(500.0);
+        visibility.entities_in_radius.extend([caster, close, far]);
+
+        let position = world.get::<Position>(caster.0).unwrap().clone();
+        let mut state: SystemState<SkillExecution> = SystemState::new(&mut world);
+        let execution = state.get_mut(&mut world);
+        for target in [ActionTarget::Own, ActionTarget::None, ActionTarget::Entity(caster.0)] {
+            let action = Action::from(ActionDescription(&AREA_SKILL, target));
+            let hit = execution.find(caster.0, &action, &position, Some(&visibility), false);
+            assert_eq!(vec![close.0], hit);
+        }
+    }
+
+    #[test]
+    fn test_hits_spread_over_execution() {
+        let mut world = World::new();
+        world.init_resource::<AttackInstanceCounter>();
+        world.init_resource::<Events<DamageReceiveEvent>>();
+        world.init_resource::<Events<BuffApplyEvent>>();
+
+        let source = EntityReference(
+            world.spawn_empty().id(),
+            GameEntity {
+                unique_id: 1,
+                ref_id: 1,
+            },
+        );
+        let mut action = Action::from(ActionDescription(&AREA_SKILL, ActionTarget::Own));
+        action.hits = Some(PendingHits {
+            skill: &AREA_SKILL,
+            instance: 0,
+            targets: Vec::new(),
+            dealt: 1,
+            remaining: 2,
+            interval: Timer::new(Duration::from_millis(100), TimerMode::Repeating),
+        });
+
+        let mut state: SystemState<SkillExecution> = SystemState::new(&mut world);
+        let mut execution = state.get_mut(&mut world);
+        execution.continue_hits(source, &mut action, Duration::from_millis(50));
+        assert_eq!(Some(2), action.hits.as_ref().map(|pending| pending.remaining));
+        execution.continue_hits(source, &mut action, Duration::from_millis(50));
+        assert_eq!(Some(1), action.hits.as_ref().map(|pending| pending.remaining));
+        execution.finish_hits(source, &mut action);
+        assert!(action.hits.is_none());
+    }
+
+    /// Hits against a target that already died would be answered with an error update, which cancels the skill on the
+    /// client, so the remaining hits must not be dealt at all.
+    #[test]
+    fn test_no_hits_after_target_died() {
+        let mut world = World::new();
+        world.init_resource::<AttackInstanceCounter>();
+        world.init_resource::<Events<DamageReceiveEvent>>();
+        world.init_resource::<Events<BuffApplyEvent>>();
+
+        let source = spawn_at(&mut world, 1, 0.0, false);
+        let target = spawn_at(&mut world, 2, 20.0, true);
+        world.entity_mut(target.0).insert(Health::new(100));
+        let mut action = Action::from(ActionDescription(&AREA_SKILL, ActionTarget::Entity(target.0)));
+        action.hits = Some(PendingHits {
+            skill: &AREA_SKILL,
+            instance: 0,
+            targets: vec![(target.0, vec![DamageOutcome::Normal(150); 3])],
+            dealt: 0,
+            remaining: 3,
+            interval: Timer::new(Duration::from_millis(100), TimerMode::Repeating),
+        });
+
+        let mut state: SystemState<SkillExecution> = SystemState::new(&mut world);
+        let mut execution = state.get_mut(&mut world);
+        execution.continue_hits(source, &mut action, Duration::from_millis(100));
+        {
+            let events = world.resource::<Events<DamageReceiveEvent>>();
+            let hits: Vec<&DamageReceiveEvent> = events.iter_current_update_events().collect();
+            assert_eq!(1, hits.len());
+            assert!(hits[0].attack.first_hit);
+            assert_eq!(2, hits[0].later_hits.len());
+        }
+
+        // The first hit is lethal, which the target only notices once the damage has been handled.
+        world.get_mut::<Health>(target.0).unwrap().reduce(150);
+        let mut execution = state.get_mut(&mut world);
+        execution.finish_hits(source, &mut action);
+        assert!(action.hits.is_none());
+        assert_eq!(
+            1,
+            world
+                .resource::<Events<DamageReceiveEvent>>()
+                .iter_current_update_events()
+                .count()
+        );
+    }
+}
@@ -11,6 +11,8 @@ use bevy_time::Time;
 use cgmath::num_traits::Pow;
 use cgmath::{Array, Deg, InnerSpace, Quaternion, Rotation3, Vector3, Zero};
 use silkroad_game_base::{GlobalLocation, GlobalPosition, Heading, Vector3Ext};
+use silkroad_navmesh::pathfinding::PathfindingError;
+use std::collections::VecDeque;
 use std::ops::Deref;
 use tracing::debug;
 
@@ -20,6 +22,35 @@ pub(crate) enum MovementGoal {
     Location(GlobalPosition),
     Direction(Heading),
     Entity(Entity, GlobalPosition, f32),
+    /// Walk along the given waypoints, where the first element is the position we're currently walking
+    /// towards and the last element is the final destination.
+    Path(VecDeque<GlobalPosition>),
+}
+
+impl MovementGoal {
+    /// Creates a goal to walk to the given position, following the navmesh around any obstacles. If the
+    /// navmesh is not available for the area, we fall back to walking in a straight line. If the target
+    /// cannot be reached at all, [None] is returned.
+    pub(crate) fn path_to(navmesh: &Navmesh, from: GlobalPosition, to: GlobalPosition) -> Option<MovementGoal> {
+        match navmesh.find_path(from, to) {
+            Ok(path) if path.len() > 1 => Some(MovementGoal::Path(path.into())),
+            Ok(_) => Some(MovementGoal::Location(to)),
+            Err(PathfindingError::Unreachable) => None,
+            Err(e) => {
+                debug!("Could not find path, walking in a straight line: {}", e);
+                Some(MovementGoal::Location(to))
+            },
+        }
+    }
+
+    /// The position we're currently walking towards, if there is one.
+    pub(crate) fn next_position(&self) -> Option<GlobalPosition> {
+        match self {
+            MovementGoal::Location(location) | MovementGoal::Entity(_, location, _) => Some(*location),
+            MovementGoal::Path(path) => path.front().copied(),
+            MovementGoal::Direction(_) => None,
+        }
+    }
 }
 
 #[derive(Component)]
@@ -69,29 +100,51 @@ pub(crate) fn turning(mut query: Query<(&mut Position, &PlayerInput), With<Idle>
 }
 
 pub(crate) fn movement(
-    mut query: Query<(Entity, &mut Position, &Agent, &Moving, &MovementState)>,
+    mut query: Query<(Entity, &mut Position, &Agent, &mut Moving, &MovementState)>,
     time: Res<Time>,
     mut cmd: Commands,
     navmesh: Res<Navmesh>,
     mut finish_movement: EventWriter<MovementFinished>,
 ) {
     let delta = time.delta_seconds_f64() as f32;
-    for (entity, mut pos, agent, movement, speed_state) in query.iter_mut() {
+    for (entity, mut pos, agent, mut movement, speed_state) in query.iter_mut() {
         let speed = agent.get_speed_value(*speed_state.deref());
-        let (next_location, heading, finished) = match movement.0 {
+        let mut reached_waypoint = false;
+        let (next_location, heading, finished) = match &movement.0 {
             MovementGoal::Location(location) => get_next_step(delta, pos.location(), speed, location.to_location()),
             MovementGoal::Direction(direction) => {
                 let current_location_2d = pos.location().0;
                 let direction_vec = Quaternion::from_angle_y(Deg(direction.0)) * Vector3::unit_x();
                 let direction_vec = direction_vec.to_flat_vec2().normalize();
                 let movement = direction_vec * (speed * delta);
-                (GlobalLocation(current_location_2d + movement), direction, false)
+                let next_location = GlobalLocation(current_location_2d + movement);
+                match navmesh.raycast(pos.location(), next_location) {
+                    Some(obstacle) => (obstacle, *direction, true),
+                    None => (next_location, *direction, false),
+                }
             },
             MovementGoal::Entity(_, location, _) => get_next_step(delta, pos.location(), speed, location.to_location()),
+            MovementGoal::Path(path) => match path.front() {
+                Some(waypoint) => {
+                    let (next_location, heading, reached) =
+                        get_next_step(delta, pos.location(), speed, waypoint.to_location());
+                    reached_waypoint = reached && path.len() > 1;
+                    (next_location, heading, reached && path.len() == 1)
+                },
+                None => (pos.location(), pos.rotation(), true),
+            },
         };
 
         move_with_step(&navmesh, &mut pos, next_location, heading);
 
+        if reached_waypoint {
+            // We only touch the goal when reaching a waypoint, such that the change to the next part of
+            // the path gets synchronized to clients.
+            if let MovementGoal::Path(path) = &mut movement.0 {
+                path.pop_front();
+            }
+        }
+
         if finished {
             cmd.entity(entity).remove::<Moving>().insert(Idle);
             finish_movement.send(MovementFinished(entity));
@@ -0,0 +1,46 @@
+use bevy_ecs::prelude::*;
+use bevy_time::{Time, Timer, TimerMode};
+use std::time::Duration;
+
+/// The entity is incapacitated, e.g. by being stunned, frozen or asleep, and can neither move nor act until it
+/// recovers.
+#[derive(Component)]
+#[component(storage = "SparseSet")]
+pub(crate) struct Stunned(Timer);
+
+impl Stunned {
+    pub(crate) fn new(duration: Duration) -> Self {
+        Stunned(Timer::new(duration, TimerMode::Once))
+    }
+}
+
+/// The entity was knocked to the ground and needs to get back up before it can do anything else.
+#[derive(Component)]
+#[component(storage = "SparseSet")]
+pub(crate) struct KnockedDown(Timer);
+
+impl KnockedDown {
+    pub(crate) fn new(duration: Duration) -> Self {
+        KnockedDown(Timer::new(duration, TimerMode::Once))
+    }
+}
+
+pub(crate) fn recover(
+    mut stunned: Query<(Entity, &mut Stunned)>,
+    mut knocked_down: Query<(Entity, &mut KnockedDown)>,
+    time: Res<Time>,
+    mut cmd: Commands,
+) {
+    let delta = time.delta();
+    for (entity, mut stunned) in stunned.iter_mut() {
+        if stunned.0.tick(delta).just_finished() {
+            cmd.entity(entity).remove::<Stunned>();
+        }
+    }
+
+    for (entity, mut knocked_down) in knocked_down.iter_mut() {
+        if knocked_down.0.tick(delta).just_finished() {
+            cmd.entity(entity).remove::<KnockedDown>();
+        }
+    }
+}
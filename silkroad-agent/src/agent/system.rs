@@ -1,16 +1,33 @@
 use crate::agent::event::{ActionFinished, MovementFinished};
-use crate::agent::states::{Action, Idle, MovementGoal, Moving, Sitting, StateTransitionQueue};
+use crate::agent::states::{
+    Action, Dead, Idle, KnockedDown, MovementGoal, Moving, Sitting, State, StateTransitionQueue, Stunned,
+};
 use crate::comp::net::Client;
 use crate::comp::pos::Position;
+use crate::ext::Navmesh;
 use crate::input::PlayerInput;
 use bevy_ecs::prelude::*;
-use cgmath::Vector3;
+use cgmath::{MetricSpace, Vector3};
 use silkroad_game_base::{Heading, LocalPosition};
 use silkroad_protocol::world::MovementTarget;
 use tracing::debug;
 
+/// The minimum (squared) distance we need to be able to walk, for a movement to be considered.
+const MIN_MOVEMENT_DISTANCE: f32 = 1.0;
+
 pub(crate) fn transition_to_idle(
-    mut query: Query<Entity, (Without<Idle>, Without<Moving>, Without<Action>, Without<Sitting>)>,
+    mut query: Query<
+        Entity,
+        (
+            Without<Idle>,
+            Without<Moving>,
+            Without<Action>,
+            Without<Sitting>,
+            Without<Dead>,
+            Without<Stunned>,
+            Without<KnockedDown>,
+        ),
+    >,
     mut cmd: Commands,
 ) {
     for entity in query.iter_mut() {
@@ -62,7 +79,21 @@ pub(crate) fn transition_from_attacking(
     }
 }
 
-pub(crate) fn movement_input(mut query: Query<(&Client, &PlayerInput, &mut StateTransitionQueue, &Position)>) {
+/// Entities that are incapacitated ignore anything but states that are more important, like dying. Once they recover,
+/// they'll become idle again.
+pub(crate) fn transition_from_incapacitated<T: Component + State>(
+    mut query: Query<(Entity, &mut StateTransitionQueue), With<T>>,
+    mut cmd: Commands,
+) {
+    for (entity, mut transitions) in query.iter_mut() {
+        transitions.transition_to_higher_state::<T>(entity, &mut cmd);
+    }
+}
+
+pub(crate) fn movement_input(
+    mut query: Query<(&Client, &PlayerInput, &mut StateTransitionQueue, &Position), Without<Dead>>,
+    navmesh: Res<Navmesh>,
+) {
     for (client, input, mut agent, position) in query.iter_mut() {
         if let Some(kind) = input.movement {
             match kind {
@@ -70,7 +101,19 @@ pub(crate) fn movement_input(mut query: Query<(&Client, &PlayerInput, &mut State
                     let local_position = position.position().to_local();
                     let target_pos = LocalPosition(region.into(), Vector3::new(x.into(), y.into(), z.into()));
                     debug!(id = ?client.id(), "Movement: {} -> {}", local_position, target_pos);
-                    agent.request_transition(Moving(MovementGoal::Location(target_pos.to_global())));
+                    let target_pos = target_pos.to_global();
+                    let destination = match navmesh.raycast(position.location(), target_pos.to_location()) {
+                        Some(reachable) => {
+                            // Something is in the way, so we only walk up to it.
+                            if reachable.distance2(position.location().0) < MIN_MOVEMENT_DISTANCE {
+                                continue;
+                            }
+                            let height = navmesh.height_for(reachable).unwrap_or(target_pos.y);
+                            reachable.with_y(height)
+                        },
+                        None => target_pos,
+                    };
+                    agent.request_transition(Moving(MovementGoal::Location(destination)));
                 },
                 MovementTarget::Direction { unknown, angle } => {
                     let direction = Heading::from(angle);
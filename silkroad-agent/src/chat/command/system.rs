@@ -6,6 +6,7 @@ use crate::comp::{EntityReference, GameEntity};
 use crate::event::{PlayerCommandEvent, PlayerTeleportEvent};
 use crate::ext::Navmesh;
 use crate::game::exp::ReceiveExperienceEvent;
+use crate::game::stats::ResetStatsEvent;
 use crate::game::target::Target;
 use crate::world::WorldData;
 use bevy_ecs::event::EventReader;
@@ -42,6 +43,7 @@ pub(crate) fn handle_command(
     target_query: Query<&Position>,
     mut teleport_events: EventWriter<PlayerTeleportEvent>,
     mut exp_events: EventWriter<ReceiveExperienceEvent>,
+    mut reset_events: EventWriter<ResetStatsEvent>,
 ) {
     for event in command_events.iter() {
         if let Ok((e, client, entity, pos, target, mut agent, player)) = query.get_mut(event.0) {
@@ -134,6 +136,8 @@ pub(crate) fn handle_command(
                 };
 
                 teleport_events.send(PlayerTeleportEvent(event.0, pos));
+            } else if event.1.name == "resetstats" {
+                reset_events.send(ResetStatsEvent(e));
             }
         }
     }
@@ -2,6 +2,7 @@ use crate::agent::states::StateTransitionQueue;
 use crate::agent::{Agent, MovementState};
 use crate::chat::command::Command;
 use crate::comp::damage::{DamageReceiver, Invincible};
+use crate::comp::drop::DropOrigin;
 use crate::comp::monster::{Monster, MonsterBundle, RandomStroll, SpawnedBy};
 use crate::comp::net::Client;
 use crate::comp::player::Player;
@@ -12,7 +13,10 @@ use crate::event::PlayerCommandEvent;
 use crate::ext::EntityIdPool;
 use crate::game::drop::SpawnDrop;
 use crate::game::mind::Mind;
+use crate::guild::component::GuildMembership;
 use crate::input::PlayerInput;
+use crate::party::component::{Parties, PartyMembership};
+use crate::status::component::StatusEffects;
 use crate::world::{EntityLookup, WorldData};
 use bevy_ecs::change_detection::ResMut;
 use bevy_ecs::entity::Entity;
@@ -40,6 +44,9 @@ pub(crate) fn handle_chat(
     mut query: Query<(Entity, &Client, &GameEntity, &PlayerInput, &Visibility, &Player)>,
     lookup: Res<EntityLookup>,
     others: Query<(&Client, &Player)>,
+    memberships: Query<&PartyMembership>,
+    parties: Res<Parties>,
+    guild_members: Query<(Entity, &Client, &GuildMembership)>,
     mut command_events: EventWriter<PlayerCommandEvent>,
 ) {
     for (entity, client, game_entity, input, visibility, player) in query.iter_mut() {
@@ -129,6 +136,62 @@ pub(crate) fn handle_chat(
                         },
                     }
                 },
+                ChatTarget::Party => {
+                    let Some(party) = memberships
+                        .get(entity)
+                        .ok()
+                        .and_then(|membership| parties.get(membership.0))
+                    else {
+                        client.send(ChatMessageResponse::new(
+                            ChatMessageResult::error(ChatErrorCode::InvalidTarget),
+                            message.target,
+                            message.index,
+                        ));
+                        continue;
+                    };
+
+                    party
+                        .others(game_entity.unique_id)
+                        .filter_map(|member| others.get(member.entity).ok())
+                        .for_each(|(client, _)| {
+                            client.send(ChatUpdate::new(
+                                ChatSource::party(player.character.name.clone()),
+                                message.message.clone(),
+                            ));
+                        });
+                    client.send(ChatMessageResponse::new(
+                        ChatMessageResult::Success,
+                        message.target,
+                        message.index,
+                    ));
+                },
+                ChatTarget::Guild => {
+                    let Ok((_, _, membership)) = guild_members.get(entity) else {
+                        client.send(ChatMessageResponse::new(
+                            ChatMessageResult::error(ChatErrorCode::InvalidTarget),
+                            message.target,
+                            message.index,
+                        ));
+                        continue;
+                    };
+
+                    guild_members
+                        .iter()
+                        .filter(|(other, _, other_membership)| {
+                            *other != entity && other_membership.guild_id == membership.guild_id
+                        })
+                        .for_each(|(_, client, _)| {
+                            client.send(ChatUpdate::new(
+                                ChatSource::guild(player.character.name.clone()),
+                                message.message.clone(),
+                            ));
+                        });
+                    client.send(ChatMessageResponse::new(
+                        ChatMessageResult::Success,
+                        message.target,
+                        message.index,
+                    ));
+                },
                 _ => {},
             }
         }
@@ -167,6 +230,7 @@ pub(crate) fn handle_gm_commands(
                             movement_state: MovementState::default_monster(),
                             damage_receiver: DamageReceiver::default(),
                             mind: Mind::default(),
+                            status: StatusEffects::default(),
                         };
                         commands.spawn(bundle);
                     }
@@ -200,6 +264,7 @@ pub(crate) fn handle_gm_commands(
                         },
                         position.location(),
                         None,
+                        DropOrigin::None,
                     ));
                     client.send(GmResponse::success_message(format!("Dropped 1 of {}", item.common.id)));
                 },
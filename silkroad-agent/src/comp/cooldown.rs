@@ -0,0 +1,154 @@
+use bevy_ecs_macros::Component;
+use bevy_time::{Timer, TimerMode};
+use silkroad_data::skilldata::RefSkillData;
+use silkroad_protocol::world::CooldownInfo;
+use std::collections::HashMap;
+use std::time::Duration;
+
+fn remaining_millis(timer: &Timer) -> u32 {
+    timer.remaining().as_millis().try_into().unwrap_or(u32::MAX)
+}
+
+/// The cooldowns of item groups that are currently active, keyed by the type of the item. Items of the same type,
+/// e.g. all HP potions, share a single cooldown.
+#[derive(Component, Default)]
+pub(crate) struct ItemCooldowns(HashMap<(u8, u8), (u32, Timer)>);
+
+impl ItemCooldowns {
+    pub(crate) fn is_active(&self, group: (u8, u8)) -> bool {
+        self.0.contains_key(&group)
+    }
+
+    /// Starts the cooldown for the group, remembering the item that caused it.
+    pub(crate) fn start(&mut self, group: (u8, u8), ref_id: u32, duration: Duration) {
+        self.0.insert(group, (ref_id, Timer::new(duration, TimerMode::Once)));
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn tick(&mut self, delta: Duration) {
+        self.0.retain(|_, (_, timer)| !timer.tick(delta).finished());
+    }
+
+    pub(crate) fn as_protocol(&self) -> Vec<CooldownInfo> {
+        self.0
+            .values()
+            .map(|(ref_id, timer)| CooldownInfo {
+                ref_id: *ref_id,
+                cooldown: remaining_millis(timer),
+            })
+            .collect()
+    }
+}
+
+/// The cooldowns of skills that are currently active. A skill that has been used cannot be used again until its
+/// cooldown finished, which also applies to all other levels of the same skill.
+#[derive(Component, Default)]
+pub(crate) struct SkillCooldowns {
+    skills: HashMap<u32, Timer>,
+    groups: HashMap<u32, Timer>,
+}
+
+impl SkillCooldowns {
+    pub(crate) fn is_active(&self, skill: &RefSkillData) -> bool {
+        self.skills.contains_key(&skill.ref_id) || self.groups.contains_key(&skill.group)
+    }
+
+    pub(crate) fn start(&mut self, skill: &RefSkillData) {
+        if skill.timings.cooldown == 0 {
+            return;
+        }
+
+        let duration = Duration::from_millis(skill.timings.cooldown.into());
+        self.skills.insert(skill.ref_id, Timer::new(duration, TimerMode::Once));
+        self.groups.insert(skill.group, Timer::new(duration, TimerMode::Once));
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.skills.is_empty() && self.groups.is_empty()
+    }
+
+    pub(crate) fn tick(&mut self, delta: Duration) {
+        self.skills.retain(|_, timer| !timer.tick(delta).finished());
+        self.groups.retain(|_, timer| !timer.tick(delta).finished());
+    }
+
+    /// Provides the remaining cooldown of each of the given learned skills. Besides the skills that have been used
+    /// themselves, this includes skills which are only on cooldown because of their group, e.g. because a different
+    /// level of the same skill has been used before learning the current one.
+    pub(crate) fn as_protocol<'a>(&self, learned: impl IntoIterator<Item = &'a RefSkillData>) -> Vec<CooldownInfo> {
+        learned
+            .into_iter()
+            .filter_map(|skill| {
+                let remaining = [self.skills.get(&skill.ref_id), self.groups.get(&skill.group)]
+                    .into_iter()
+                    .flatten()
+                    .map(remaining_millis)
+                    .max()?;
+                Some(CooldownInfo {
+                    ref_id: skill.ref_id,
+                    cooldown: remaining,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn skill(ref_id: u32, group: u32, cooldown: u32) -> RefSkillData {
+        let mut skill = RefSkillData::test_skill(ref_id);
+        skill.group = group;
+        skill.timings.cooldown = cooldown;
+        skill
+    }
+
+    #[test]
+    fn test_skill_cooldown_expires() {
+        let used = skill(1, 1, 1000);
+        let next_level = skill(2, 1, 1000);
+        let other = skill(3, 2, 1000);
+        let mut cooldowns = SkillCooldowns::default();
+        cooldowns.start(&used);
+
+        assert!(cooldowns.is_active(&used));
+        assert!(cooldowns.is_active(&next_level));
+        assert!(!cooldowns.is_active(&other));
+
+        cooldowns.tick(Duration::from_millis(600));
+        assert!(cooldowns.is_active(&used));
+        cooldowns.tick(Duration::from_millis(600));
+        assert!(!cooldowns.is_active(&used));
+        assert!(!cooldowns.is_active(&next_level));
+        assert!(cooldowns.is_empty());
+    }
+
+    #[test]
+    fn test_skill_without_cooldown() {
+        let instant = skill(1, 1, 0);
+        let mut cooldowns = SkillCooldowns::default();
+        cooldowns.start(&instant);
+
+        assert!(!cooldowns.is_active(&instant));
+        assert!(cooldowns.is_empty());
+    }
+
+    #[test]
+    fn test_skill_cooldowns_as_protocol() {
+        let used = skill(1, 1, 1000);
+        let next_level = skill(2, 1, 1000);
+        let other = skill(3, 2, 1000);
+        let mut cooldowns = SkillCooldowns::default();
+        cooldowns.start(&used);
+        cooldowns.tick(Duration::from_millis(400));
+
+        let infos = cooldowns.as_protocol([&next_level, &other]);
+        assert_eq!(1, infos.len());
+        assert_eq!(2, infos[0].ref_id);
+        assert_eq!(600, infos[0].cooldown);
+    }
+}
@@ -1,6 +1,8 @@
 use bevy_ecs_macros::Component;
+use bevy_time::{Timer, TimerMode};
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::time::Duration;
 
 #[derive(Component, Default)]
 pub(crate) struct DamageReceiver {
@@ -23,6 +25,18 @@ impl DamageReceiver {
         self.damage_counts.get(&source).copied().unwrap_or(0)
     }
 
+    pub(crate) fn total_damage(&self) -> u64 {
+        self.damage_counts.values().sum()
+    }
+
+    /// Provides the attacker that dealt the most damage overall.
+    pub(crate) fn top_attacker(&self) -> Option<u32> {
+        self.damage_counts
+            .iter()
+            .max_by_key(|(_, damage)| **damage)
+            .map(|(attacker, _)| *attacker)
+    }
+
     pub(crate) fn all_attackers(&self) -> impl Iterator<Item = u32> + '_ {
         self.damage_counts.keys().copied()
     }
@@ -38,3 +52,14 @@ impl Invincible {
         Invincible { by_command: true }
     }
 }
+
+/// Marks an entity that recently attacked or got attacked, which prevents it from regenerating.
+#[derive(Component)]
+#[component(storage = "SparseSet")]
+pub(crate) struct InCombat(pub(crate) Timer);
+
+impl InCombat {
+    pub(crate) fn for_duration(duration: Duration) -> Self {
+        InCombat(Timer::new(duration, TimerMode::Once))
+    }
+}
@@ -1,12 +1,59 @@
 use crate::comp::pos::Position;
 use crate::comp::{Despawn, EntityReference, GameEntity};
 use bevy_ecs::prelude::*;
+use bevy_time::Timer;
 use silkroad_game_base::Item;
+use silkroad_protocol::world::DroppedItemSource;
+
+/// Who caused an item to be dropped.
+#[derive(Copy, Clone)]
+pub(crate) enum DropOrigin {
+    None,
+    Monster(u32),
+    Player(u32),
+}
+
+impl DropOrigin {
+    pub(crate) fn as_protocol(&self) -> (DroppedItemSource, u32) {
+        match self {
+            DropOrigin::None => (DroppedItemSource::None, 0),
+            DropOrigin::Monster(id) => (DroppedItemSource::Monster, *id),
+            DropOrigin::Player(id) => (DroppedItemSource::Player, *id),
+        }
+    }
+}
 
 #[derive(Component)]
 pub(crate) struct Drop {
     pub owner: Option<EntityReference>,
     pub item: Item,
+    pub source: DropOrigin,
+    /// Time until the drop is no longer reserved for its owner and anyone may pick it up.
+    pub protection: Option<Timer>,
+}
+
+impl Drop {
+    /// Checks if the entity with the given unique id may pick up this drop. While the drop is protected, only its
+    /// owner or someone the owner shares their loot with may pick it up.
+    pub(crate) fn may_be_picked_up_by(&self, unique_id: u32, sharing: Option<&LootSharing>) -> bool {
+        let Some(owner) = self.owner else {
+            return true;
+        };
+        owner.1.unique_id == unique_id || sharing.is_some_and(|sharing| sharing.includes(owner.1.unique_id))
+    }
+}
+
+/// The entities whose protected drops this entity may pick up as if they were its own, e.g. the other members of
+/// its party.
+#[derive(Component, Default)]
+pub(crate) struct LootSharing {
+    pub(crate) members: Vec<u32>,
+}
+
+impl LootSharing {
+    pub(crate) fn includes(&self, unique_id: u32) -> bool {
+        self.members.contains(&unique_id)
+    }
 }
 
 #[derive(Bundle)]
@@ -17,6 +17,7 @@ pub(crate) struct Experienced {
     experience: u64,
     sp_exp: u64,
     experience_received: Vec<ExperienceGained>,
+    experience_lost: u64,
 }
 
 impl Default for Experienced {
@@ -25,6 +26,7 @@ impl Default for Experienced {
             experience: 0,
             sp_exp: 0,
             experience_received: vec![],
+            experience_lost: 0,
         }
     }
 }
@@ -32,6 +34,7 @@ impl Default for Experienced {
 impl Reset for Experienced {
     fn reset(&mut self) {
         self.experience_received = Vec::new();
+        self.experience_lost = 0;
     }
 }
 
@@ -41,6 +44,7 @@ impl Experienced {
             experience,
             sp_exp,
             experience_received: Vec::new(),
+            experience_lost: 0,
         }
     }
 
@@ -59,6 +63,18 @@ impl Experienced {
         self.experience
     }
 
+    pub(crate) fn sp_experience(&self) -> u64 {
+        self.sp_exp
+    }
+
+    /// Removes up to the given amount of experience, without losing a level. Returns the amount actually lost.
+    pub(crate) fn lose(&mut self, exp: u64) -> u64 {
+        let lost = exp.min(self.experience);
+        self.experience -= lost;
+        self.experience_lost += lost;
+        lost
+    }
+
     pub(crate) fn try_level_up(&mut self, required: u64) -> bool {
         if self.experience >= required {
             self.experience -= required;
@@ -80,6 +96,10 @@ impl Experienced {
     pub(crate) fn experience_gains(&self) -> &[ExperienceGained] {
         &self.experience_received
     }
+
+    pub(crate) fn experience_loss(&self) -> u64 {
+        self.experience_lost
+    }
 }
 
 #[derive(Component, Constructor)]
@@ -101,6 +121,18 @@ impl SP {
     pub(crate) fn current(&self) -> u32 {
         self.sp
     }
+
+    /// Tries to spend the given amount of SP. Returns `false`, without changing the current SP, if there isn't
+    /// enough SP available.
+    pub(crate) fn spend(&mut self, amount: u32) -> bool {
+        match self.sp.checked_sub(amount) {
+            Some(remaining) => {
+                self.sp = remaining;
+                true
+            },
+            None => false,
+        }
+    }
 }
 
 #[derive(Component)]
@@ -4,12 +4,18 @@ use bevy_ecs::prelude::*;
 use silkroad_data::itemdata::RefItemData;
 use silkroad_definitions::type_id::{ObjectItem, ObjectType};
 use silkroad_game_base::{Inventory, Item, ItemTypeData};
+use std::collections::VecDeque;
 use std::ops::{Deref, DerefMut};
 
+/// The amount of sold items that are kept around to be bought back.
+const MAX_BUYBACK_ITEMS: usize = 5;
+
 #[derive(Component)]
 pub(crate) struct PlayerInventory {
     inventory: Inventory,
     pub gold: u64,
+    /// Items sold to NPCs during this session, which can be bought back until logging out.
+    buyback: VecDeque<Item>,
 }
 
 impl Deref for PlayerInventory {
@@ -65,8 +71,33 @@ impl PlayerInventory {
         }
     }
 
+    pub(crate) fn new(inventory: Inventory, gold: u64) -> Self {
+        PlayerInventory {
+            inventory,
+            gold,
+            buyback: VecDeque::new(),
+        }
+    }
+
     pub(crate) fn from_db(items: &[CharacterItem], size: usize, gold: u64) -> Self {
-        let inventory = Self::from_db_inventory(items, size);
-        PlayerInventory { inventory, gold }
+        Self::new(Self::from_db_inventory(items, size), gold)
+    }
+
+    /// Remembers the sold item for buying it back later, dropping the oldest one if the list is full. Returns the slot
+    /// of the item in the buyback list.
+    pub(crate) fn add_buyback(&mut self, item: Item) -> u8 {
+        if self.buyback.len() >= MAX_BUYBACK_ITEMS {
+            self.buyback.pop_front();
+        }
+        self.buyback.push_back(item);
+        (self.buyback.len() - 1) as u8
+    }
+
+    pub(crate) fn buyback_item(&self, slot: u8) -> Option<&Item> {
+        self.buyback.get(slot as usize)
+    }
+
+    pub(crate) fn take_buyback(&mut self, slot: u8) -> Option<Item> {
+        self.buyback.remove(slot as usize)
     }
 }
@@ -1,3 +1,4 @@
+pub(crate) mod cooldown;
 pub(crate) mod damage;
 pub(crate) mod drop;
 pub(crate) mod exp;
@@ -56,8 +57,10 @@ impl Health {
 
     pub fn regenerate(&mut self, amount: u32) {
         let before = self.current_health;
-        self.current_health = self.current_health.saturating_add(amount);
-        self.add_change(before as i32)
+        self.current_health = self.current_health.saturating_add(amount).min(self.max_health);
+        if self.current_health != before {
+            self.add_change((self.current_health - before) as i32)
+        }
     }
 
     fn add_change(&mut self, amount: i32) {
@@ -67,10 +70,20 @@ impl Health {
         }
     }
 
+    pub fn refill(&mut self) {
+        let missing = self.max_health - self.current_health;
+        self.current_health = self.max_health;
+        self.add_change(missing as i32)
+    }
+
     pub fn is_dead(&self) -> bool {
         self.current_health == 0
     }
 
+    pub fn is_full(&self) -> bool {
+        self.current_health >= self.max_health
+    }
+
     pub fn upgrade(&mut self, new_max: u32) {
         let diff = new_max - self.current_health;
         self.max_health = new_max;
@@ -112,6 +125,32 @@ impl Mana {
         }
     }
 
+    pub fn reduce(&mut self, amount: u32) {
+        let before = self.current_mana;
+        self.current_mana = self.current_mana.saturating_sub(amount);
+        if self.current_mana != before {
+            self.add_change(-((before - self.current_mana) as i32))
+        }
+    }
+
+    pub fn regenerate(&mut self, amount: u32) {
+        let before = self.current_mana;
+        self.current_mana = self.current_mana.saturating_add(amount).min(self.max_mana);
+        if self.current_mana != before {
+            self.add_change((self.current_mana - before) as i32)
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.current_mana >= self.max_mana
+    }
+
+    pub fn refill(&mut self) {
+        let missing = self.max_mana - self.current_mana;
+        self.current_mana = self.max_mana;
+        self.add_change(missing as i32)
+    }
+
     pub fn upgrade(&mut self, new_max: u32) {
         let diff = new_max - self.current_mana;
         self.max_mana = new_max;
@@ -1,5 +1,6 @@
 use crate::agent::states::StateTransitionQueue;
 use crate::agent::{Agent, MovementState};
+use crate::comp::cooldown::{ItemCooldowns, SkillCooldowns};
 use crate::comp::damage::DamageReceiver;
 use crate::comp::exp::{Experienced, Leveled, SP};
 use crate::comp::inventory::PlayerInventory;
@@ -10,8 +11,14 @@ use crate::db::character::CharacterData;
 use crate::db::user::ServerUser;
 use crate::game::mind::Mind;
 use crate::input::PlayerInput;
+use crate::status::component::StatusEffects;
+use crate::sync::Reset;
 use bevy_ecs::prelude::*;
-use silkroad_game_base::{Character, Race, SpawningState, Stats};
+use bevy_time::{Timer, TimerMode};
+use silkroad_data::skilldata::RefSkillData;
+use silkroad_game_base::{buffs_interfere, BuffEffect, Character, Race, SpawningState, Stats};
+use std::mem::take;
+use std::time::Duration;
 
 #[derive(Component)]
 pub(crate) struct Player {
@@ -20,11 +27,11 @@ pub(crate) struct Player {
 }
 
 impl Player {
-    fn from_db_character(data: &CharacterData) -> Character {
+    fn from_db_character(data: &CharacterData, race: Race) -> Character {
         Character {
             id: data.id as u32,
             name: data.charname.clone(),
-            race: Race::Chinese,
+            race,
             scale: data.scale as u8,
             level: data.level as u8,
             max_level: data.max_level as u8,
@@ -45,15 +52,116 @@ impl Player {
         }
     }
 
-    pub fn from_db_data(user: ServerUser, character: &CharacterData) -> Self {
-        let char = Self::from_db_character(character);
+    pub fn from_db_data(user: ServerUser, character: &CharacterData, race: Race) -> Self {
+        let char = Self::from_db_character(character, race);
         Player { user, character: char }
     }
 }
 
-#[derive(Component)]
+pub(crate) struct Buff {
+    pub(crate) skill: &'static RefSkillData,
+    pub(crate) token: u32,
+    pub(crate) remaining: Timer,
+}
+
+#[derive(Copy, Clone)]
+pub(crate) enum BuffChange {
+    Added(&'static RefSkillData, u32),
+    Removed(&'static RefSkillData, u32),
+}
+
+impl BuffChange {
+    pub(crate) fn skill(&self) -> &'static RefSkillData {
+        match *self {
+            BuffChange::Added(skill, _) | BuffChange::Removed(skill, _) => skill,
+        }
+    }
+}
+
+#[derive(Component, Default)]
 pub(crate) struct Buffed {
-    // pub buffs: Vec<Buff>
+    pub(crate) buffs: Vec<Buff>,
+    changes: Vec<BuffChange>,
+}
+
+impl Reset for Buffed {
+    fn reset(&mut self) {
+        self.changes.clear();
+    }
+}
+
+impl Buffed {
+    /// Adds the buff created by the given skill, replacing all active buffs it interferes with. The buff is rejected
+    /// if a higher level of the same skill is already active.
+    pub(crate) fn add(&mut self, skill: &'static RefSkillData, token: u32, duration: Duration) -> bool {
+        if self
+            .buffs
+            .iter()
+            .any(|buff| buff.skill.group == skill.group && buff.skill.level > skill.level)
+        {
+            return false;
+        }
+
+        let (replaced, kept) = take(&mut self.buffs)
+            .into_iter()
+            .partition::<Vec<_>, _>(|buff| buffs_interfere(buff.skill, skill));
+        self.buffs = kept;
+        self.remove_all(replaced);
+
+        self.buffs.push(Buff {
+            skill,
+            token,
+            remaining: Timer::new(duration, TimerMode::Once),
+        });
+        self.changes.push(BuffChange::Added(skill, token));
+        true
+    }
+
+    /// Removes the buff created by the skill with the given id, if it is active.
+    pub(crate) fn remove_skill(&mut self, skill_id: u32) -> bool {
+        let (removed, kept) = take(&mut self.buffs)
+            .into_iter()
+            .partition::<Vec<_>, _>(|buff| buff.skill.ref_id == skill_id);
+        self.buffs = kept;
+        let any_removed = !removed.is_empty();
+        self.remove_all(removed);
+        any_removed
+    }
+
+    pub(crate) fn clear(&mut self) {
+        let removed = take(&mut self.buffs);
+        self.remove_all(removed);
+    }
+
+    /// Advances the timers of all buffs and removes those that expired.
+    pub(crate) fn tick(&mut self, delta: Duration) {
+        for buff in self.buffs.iter_mut() {
+            buff.remaining.tick(delta);
+        }
+        let (expired, kept) = take(&mut self.buffs)
+            .into_iter()
+            .partition::<Vec<_>, _>(|buff| buff.remaining.finished());
+        self.buffs = kept;
+        self.remove_all(expired);
+    }
+
+    fn remove_all(&mut self, buffs: Vec<Buff>) {
+        self.changes.extend(
+            buffs
+                .into_iter()
+                .map(|buff| BuffChange::Removed(buff.skill, buff.token)),
+        );
+    }
+
+    /// The combined effect of all currently active buffs.
+    pub(crate) fn effect(&self) -> BuffEffect {
+        self.buffs.iter().map(|buff| BuffEffect::from_skill(buff.skill)).sum()
+    }
+
+    /// The buffs that have been added or removed since the last synchronization.
+    pub(crate) fn changes(&self) -> &[BuffChange] {
+        &self.changes
+    }
 }
 
 #[derive(Bundle)]
@@ -64,6 +172,7 @@ pub(crate) struct PlayerBundle {
     agent: Agent,
     pos: Position,
     buff: Buffed,
+    status: StatusEffects,
     visibility: Visibility,
     input: PlayerInput,
     state_queue: StateTransitionQueue,
@@ -75,6 +184,8 @@ pub(crate) struct PlayerBundle {
     sp: SP,
     exp: Experienced,
     mind: Mind,
+    cooldowns: ItemCooldowns,
+    skill_cooldowns: SkillCooldowns,
 }
 
 impl PlayerBundle {
@@ -98,7 +209,8 @@ impl PlayerBundle {
             inventory,
             agent,
             pos,
-            buff: Buffed {},
+            buff: Buffed::default(),
+            status: StatusEffects::default(),
             visibility,
             input: Default::default(),
             state_queue: Default::default(),
@@ -110,6 +222,8 @@ impl PlayerBundle {
             level: Leveled::new(level),
             exp: Experienced::new(exp, sp_exp as u64),
             mind: Mind::default(),
+            cooldowns: ItemCooldowns::default(),
+            skill_cooldowns: SkillCooldowns::default(),
         }
     }
 }
@@ -1,8 +1,10 @@
 use bevy_ecs_macros::Resource;
+use cgmath::{MetricSpace, Vector3};
 use config::{ConfigError, FileFormat};
 use log::LevelFilter;
 use once_cell::sync::Lazy;
 use serde::Deserialize;
+use silkroad_game_base::{DropRolls, GlobalLocation, GlobalPosition, LocalPosition, Race, DEFAULT_SUCCESS_CHANCES};
 use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
 use sqlx::{ConnectOptions, PgPool};
 use tracing::debug;
@@ -50,6 +52,154 @@ pub(crate) struct GameConfig {
     pub(crate) spawner: SpawnOptions,
     pub(crate) max_follow_distance: f32,
     pub(crate) masteries: MasteryConfig,
+    /// Interval, in seconds, in which the state of all players is saved. `0` disables autosaving.
+    pub(crate) autosave_interval: u64,
+    /// Time, in seconds, for which a drop can only be picked up by its owner.
+    pub(crate) drop_protection: u64,
+    #[serde(default)]
+    pub(crate) rates: RateConfig,
+    #[serde(default)]
+    pub(crate) drop_rolls: DropRollConfig,
+    pub(crate) starting: StartingConfig,
+    /// Stat points granted for each level a character reaches for the first time.
+    pub(crate) stat_points_per_level: u16,
+    /// Share of the experience required for the current level a character loses when dying.
+    pub(crate) death_exp_penalty: f32,
+    /// Towns players may return to, e.g. when resurrecting after dying.
+    pub(crate) towns: Vec<TownConfig>,
+    #[serde(default)]
+    pub(crate) regeneration: RegenerationConfig,
+    #[serde(default)]
+    pub(crate) item_cooldowns: ItemCooldownConfig,
+    /// Chance for reinforcing an item to succeed, indexed by the upgrade level the item currently has. Items can't be
+    /// reinforced past the last configured level. This is a stopgap until the chances are loaded from the server's
+    /// reference data, which the client data doesn't include.
+    #[serde(default = "default_reinforce_chances")]
+    pub(crate) reinforce_chances: Vec<f32>,
+}
+
+fn default_reinforce_chances() -> Vec<f32> {
+    DEFAULT_SUCCESS_CHANCES.to_vec()
+}
+
+impl GameConfig {
+    /// Finds the return point of the town closest to the given location.
+    pub(crate) fn closest_return_point(&self, location: GlobalLocation) -> Option<GlobalPosition> {
+        self.towns.iter().map(TownConfig::return_point).min_by(|a, b| {
+            let distance_a = a.to_location().0.distance2(location.0);
+            let distance_b = b.to_location().0.distance2(location.0);
+            distance_a.total_cmp(&distance_b)
+        })
+    }
+}
+
+/// Server-wide multipliers for the experience, SP experience and items gained from killing monsters.
+#[derive(Deserialize, Clone)]
+pub(crate) struct RateConfig {
+    pub(crate) exp: f32,
+    pub(crate) sp: f32,
+    pub(crate) drop: f32,
+}
+
+impl Default for RateConfig {
+    fn default() -> Self {
+        RateConfig {
+            exp: 1.0,
+            sp: 1.0,
+            drop: 1.0,
+        }
+    }
+}
+
+/// How many times monsters roll their drops, depending on their rarity.
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct DropRollConfig {
+    pub(crate) normal: usize,
+    pub(crate) champion: usize,
+    pub(crate) elite: usize,
+    pub(crate) giant: usize,
+    pub(crate) titan: usize,
+    pub(crate) unique: usize,
+    pub(crate) party_multiplier: usize,
+}
+
+impl Default for DropRollConfig {
+    fn default() -> Self {
+        DropRolls::default().into()
+    }
+}
+
+impl From<DropRolls> for DropRollConfig {
+    fn from(rolls: DropRolls) -> Self {
+        DropRollConfig {
+            normal: rolls.normal,
+            champion: rolls.champion,
+            elite: rolls.elite,
+            giant: rolls.giant,
+            titan: rolls.titan,
+            unique: rolls.unique,
+            party_multiplier: rolls.party_multiplier,
+        }
+    }
+}
+
+impl From<&DropRollConfig> for DropRolls {
+    fn from(config: &DropRollConfig) -> Self {
+        DropRolls {
+            normal: config.normal,
+            champion: config.champion,
+            elite: config.elite,
+            giant: config.giant,
+            titan: config.titan,
+            unique: config.unique,
+            party_multiplier: config.party_multiplier,
+        }
+    }
+}
+
+/// How fast entities recover their HP and MP while not being in combat.
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct RegenerationConfig {
+    /// Interval, in seconds, in which HP and MP are restored.
+    pub(crate) interval: u64,
+    /// Share of the maximum HP and MP restored each interval.
+    pub(crate) share: f32,
+    /// Multiplier for the restored amount while sitting.
+    pub(crate) sitting_multiplier: f32,
+    /// Time, in seconds, after the last attack during which an entity is still considered to be in combat.
+    pub(crate) combat_duration: u64,
+}
+
+impl Default for RegenerationConfig {
+    fn default() -> Self {
+        RegenerationConfig {
+            interval: 3,
+            share: 0.02,
+            sitting_multiplier: 3.0,
+            combat_duration: 10,
+        }
+    }
+}
+
+/// Cooldowns, in milliseconds, after using a consumable before another one of the same kind can be used.
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct ItemCooldownConfig {
+    /// Cooldown of HP, MP and vigor potions.
+    pub(crate) potion: u64,
+    /// Cooldown of pills curing abnormal states.
+    pub(crate) cure: u64,
+}
+
+impl Default for ItemCooldownConfig {
+    fn default() -> Self {
+        ItemCooldownConfig {
+            potion: 1000,
+            cure: 1000,
+        }
+    }
 }
 
 #[derive(Deserialize, Default, Clone)]
@@ -65,6 +215,58 @@ pub(crate) struct MasteryConfig {
     pub(crate) chinese_per_level: usize,
 }
 
+/// The setup newly created characters start out with, depending on their race.
+#[derive(Deserialize, Default, Clone)]
+pub(crate) struct StartingConfig {
+    pub(crate) chinese: StartingSetup,
+    pub(crate) european: StartingSetup,
+}
+
+impl StartingConfig {
+    pub(crate) fn for_race(&self, race: Race) -> &StartingSetup {
+        match race {
+            Race::Chinese => &self.chinese,
+            Race::European => &self.european,
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct StartingSetup {
+    pub(crate) gold: u64,
+    pub(crate) region: u16,
+    pub(crate) x: f32,
+    pub(crate) y: f32,
+    pub(crate) z: f32,
+    /// Masteries the character already knows, at level 0.
+    pub(crate) masteries: Vec<u16>,
+    /// Items, besides the chosen equipment, the character receives in their inventory.
+    pub(crate) items: Vec<StartingItem>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct TownConfig {
+    pub(crate) region: u16,
+    pub(crate) x: f32,
+    pub(crate) y: f32,
+    pub(crate) z: f32,
+}
+
+impl TownConfig {
+    pub(crate) fn return_point(&self) -> GlobalPosition {
+        LocalPosition(self.region.into(), Vector3::new(self.x, self.y, self.z)).to_global()
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct StartingItem {
+    pub(crate) ref_id: u32,
+    pub(crate) amount: u16,
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub(crate) struct GameServerConfig {
@@ -124,3 +124,25 @@ impl CharacterMastery {
         Ok(masteries)
     }
 }
+
+/// A skill a character has learned. Only the highest learned level of each skill group is stored.
+#[derive(sqlx::FromRow, Copy, Clone)]
+pub struct CharacterSkill {
+    pub character_id: i32,
+    pub skill_id: i32,
+}
+
+impl CharacterSkill {
+    pub async fn fetch_for_characters<T: Borrow<PgPool>>(
+        character_ids: &[i32],
+        pool: T,
+    ) -> Result<Vec<CharacterSkill>, Error> {
+        sqlx::query_as!(
+            CharacterSkill,
+            "SELECT character_id, skill_id FROM character_skills WHERE character_id in (SELECT * FROM UNNEST($1::INTEGER[]))",
+            character_ids
+        )
+        .fetch_all(pool.borrow())
+        .await
+    }
+}
@@ -0,0 +1,123 @@
+use itertools::Itertools;
+use sqlx::{Error, PgPool};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+
+#[derive(sqlx::Type, Copy, Clone, Eq, PartialEq, Debug)]
+#[sqlx(type_name = "\"FriendStatus\"", rename_all = "UPPERCASE")]
+pub enum FriendStatus {
+    Requested,
+    Accepted,
+    Declined,
+}
+
+#[derive(sqlx::FromRow, Clone)]
+pub struct FriendGroup {
+    pub id: i32,
+    pub character_id: i32,
+    pub name: String,
+}
+
+impl FriendGroup {
+    pub async fn fetch_for_characters<T: Borrow<PgPool>>(
+        character_ids: &[i32],
+        pool: T,
+    ) -> Result<HashMap<i32, Vec<FriendGroup>>, Error> {
+        let groups = sqlx::query_as!(
+            FriendGroup,
+            "SELECT id, character_id, name FROM friends_groups WHERE character_id in (SELECT * FROM UNNEST($1::INTEGER[])) ORDER BY id ASC",
+            character_ids
+        )
+        .fetch_all(pool.borrow())
+        .await?;
+        Ok(groups.into_iter().into_group_map_by(|group| group.character_id))
+    }
+
+    pub async fn create<T: Borrow<PgPool>>(character_id: u32, name: String, pool: T) -> Result<i32, Error> {
+        let result = sqlx::query!(
+            "INSERT INTO friends_groups(character_id, name) VALUES($1, $2) RETURNING id",
+            character_id as i32,
+            name
+        )
+        .fetch_one(pool.borrow())
+        .await?;
+        Ok(result.id)
+    }
+}
+
+/// A friend of a character, including the information of the befriended character needed to display them.
+#[derive(sqlx::FromRow, Clone)]
+pub struct Friend {
+    pub character_id: i32,
+    pub friend_id: i32,
+    pub group_id: Option<i32>,
+    pub name: String,
+    pub character_type: i32,
+}
+
+impl Friend {
+    pub async fn fetch_for_characters<T: Borrow<PgPool>>(
+        character_ids: &[i32],
+        pool: T,
+    ) -> Result<HashMap<i32, Vec<Friend>>, Error> {
+        let friends = sqlx::query_as!(
+            Friend,
+            "SELECT friends.character_id, friends.friend_id, friends.group_id, characters.charname as name, characters.character_type FROM friends JOIN characters ON characters.id = friends.friend_id WHERE friends.character_id in (SELECT * FROM UNNEST($1::INTEGER[])) AND friends.status = $2",
+            character_ids,
+            FriendStatus::Accepted as FriendStatus
+        )
+        .fetch_all(pool.borrow())
+        .await?;
+        Ok(friends.into_iter().into_group_map_by(|friend| friend.character_id))
+    }
+
+    pub async fn request<T: Borrow<PgPool>>(character_id: u32, friend_id: u32, pool: T) -> Result<(), Error> {
+        sqlx::query!(
+            "INSERT INTO friends(character_id, friend_id, status) VALUES($1, $2, $3) ON CONFLICT (character_id, friend_id) DO UPDATE SET status = EXCLUDED.status",
+            character_id as i32,
+            friend_id as i32,
+            FriendStatus::Requested as FriendStatus
+        )
+        .execute(pool.borrow())
+        .await?;
+        Ok(())
+    }
+
+    /// Declines the request of `character_id` to befriend `friend_id`. This also applies to requests that can no
+    /// longer be answered, as requests are only kept while the requested character is online.
+    pub async fn decline<T: Borrow<PgPool>>(character_id: u32, friend_id: u32, pool: T) -> Result<(), Error> {
+        sqlx::query!(
+            "UPDATE friends SET status = $3 WHERE character_id = $1 AND friend_id = $2",
+            character_id as i32,
+            friend_id as i32,
+            FriendStatus::Declined as FriendStatus
+        )
+        .execute(pool.borrow())
+        .await?;
+        Ok(())
+    }
+
+    /// Accepts the request of `character_id` to befriend `friend_id`, making both characters friends of each other.
+    pub async fn accept<T: Borrow<PgPool>>(character_id: u32, friend_id: u32, pool: T) -> Result<(), Error> {
+        sqlx::query!(
+            "INSERT INTO friends(character_id, friend_id, status) VALUES($1, $2, $3), ($2, $1, $3) ON CONFLICT (character_id, friend_id) DO UPDATE SET status = EXCLUDED.status",
+            character_id as i32,
+            friend_id as i32,
+            FriendStatus::Accepted as FriendStatus
+        )
+        .execute(pool.borrow())
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete<T: Borrow<PgPool>>(character_id: u32, friend_id: u32, pool: T) -> Result<(), Error> {
+        sqlx::query!(
+            "DELETE FROM friends WHERE (character_id = $1 AND friend_id = $2) OR (character_id = $2 AND friend_id = $1)",
+            character_id as i32,
+            friend_id as i32
+        )
+        .execute(pool.borrow())
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+use itertools::Itertools;
+use sqlx::{Error, PgPool};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+
+#[derive(sqlx::FromRow, Clone)]
+pub struct GuildData {
+    pub id: i32,
+    pub name: String,
+    pub notice_title: String,
+    pub notice: String,
+}
+
+#[derive(sqlx::FromRow, Clone)]
+pub struct GuildMemberData {
+    pub guild_id: i32,
+    pub character_id: i32,
+    pub name: String,
+    pub level: i16,
+    pub rank: i16,
+}
+
+/// A guild together with all of its members.
+#[derive(Clone)]
+pub struct DbGuild {
+    pub guild: GuildData,
+    pub members: Vec<GuildMemberData>,
+}
+
+impl DbGuild {
+    /// Loads the guilds of the given characters, keyed by the id of the character being a member of the guild.
+    pub async fn fetch_for_characters<T: Borrow<PgPool>>(
+        character_ids: &[i32],
+        pool: T,
+    ) -> Result<HashMap<i32, DbGuild>, Error> {
+        let guilds = sqlx::query_as!(
+            GuildData,
+            "SELECT id, name, notice_title, notice FROM guilds WHERE id in (SELECT guild_id FROM guild_members WHERE character_id in (SELECT * FROM UNNEST($1::INTEGER[])))",
+            character_ids
+        )
+        .fetch_all(pool.borrow())
+        .await?;
+        let guild_ids = guilds.iter().map(|guild| guild.id).collect::<Vec<_>>();
+        let mut members = sqlx::query_as!(
+            GuildMemberData,
+            "SELECT guild_members.guild_id, guild_members.character_id, characters.charname as name, characters.level, guild_members.rank FROM guild_members JOIN characters ON characters.id = guild_members.character_id WHERE guild_members.guild_id in (SELECT * FROM UNNEST($1::INTEGER[])) ORDER BY guild_members.joined ASC",
+            &guild_ids
+        )
+        .fetch_all(pool.borrow())
+        .await?
+        .into_iter()
+        .into_group_map_by(|member| member.guild_id);
+
+        let mut result = HashMap::new();
+        for guild in guilds {
+            let members = members.remove(&guild.id).unwrap_or_default();
+            for character_id in character_ids {
+                if members.iter().any(|member| member.character_id == *character_id) {
+                    result.insert(
+                        *character_id,
+                        DbGuild {
+                            guild: guild.clone(),
+                            members: members.clone(),
+                        },
+                    );
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Creates a new guild with the given character as its master, returning the id of the guild.
+    pub async fn create<T: Borrow<PgPool>>(
+        server_id: u16,
+        name: String,
+        master: u32,
+        master_rank: i16,
+        pool: T,
+    ) -> Result<i32, Error> {
+        let mut transaction = pool.borrow().begin().await?;
+        let guild = sqlx::query!(
+            "INSERT INTO guilds(server_id, name) VALUES($1, $2) RETURNING id",
+            server_id as i32,
+            name
+        )
+        .fetch_one(&mut *transaction)
+        .await?;
+        sqlx::query!(
+            "INSERT INTO guild_members(character_id, guild_id, rank) VALUES($1, $2, $3)",
+            master as i32,
+            guild.id,
+            master_rank
+        )
+        .execute(&mut *transaction)
+        .await?;
+        transaction.commit().await?;
+        Ok(guild.id)
+    }
+
+    pub async fn disband<T: Borrow<PgPool>>(guild_id: u32, pool: T) -> Result<(), Error> {
+        sqlx::query!("DELETE FROM guilds WHERE id = $1", guild_id as i32)
+            .execute(pool.borrow())
+            .await?;
+        Ok(())
+    }
+
+    pub async fn add_member<T: Borrow<PgPool>>(
+        guild_id: u32,
+        character_id: u32,
+        rank: i16,
+        pool: T,
+    ) -> Result<(), Error> {
+        sqlx::query!(
+            "INSERT INTO guild_members(character_id, guild_id, rank) VALUES($1, $2, $3)",
+            character_id as i32,
+            guild_id as i32,
+            rank
+        )
+        .execute(pool.borrow())
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove_member<T: Borrow<PgPool>>(character_id: u32, pool: T) -> Result<(), Error> {
+        sqlx::query!("DELETE FROM guild_members WHERE character_id = $1", character_id as i32)
+            .execute(pool.borrow())
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_rank<T: Borrow<PgPool>>(character_id: u32, rank: i16, pool: T) -> Result<(), Error> {
+        sqlx::query!(
+            "UPDATE guild_members SET rank = $2 WHERE character_id = $1",
+            character_id as i32,
+            rank
+        )
+        .execute(pool.borrow())
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_notice<T: Borrow<PgPool>>(
+        guild_id: u32,
+        title: String,
+        notice: String,
+        pool: T,
+    ) -> Result<(), Error> {
+        sqlx::query!(
+            "UPDATE guilds SET notice_title = $2, notice = $3 WHERE id = $1",
+            guild_id as i32,
+            title,
+            notice
+        )
+        .execute(pool.borrow())
+        .await?;
+        Ok(())
+    }
+}
@@ -1,3 +1,6 @@
 pub(crate) mod character;
+pub(crate) mod friend;
+pub(crate) mod guild;
+pub(crate) mod persistence;
 pub(crate) mod server;
 pub(crate) mod user;
@@ -0,0 +1,133 @@
+use sqlx::{Error, PgPool};
+use std::borrow::Borrow;
+
+/// A copy of everything about a character that needs to survive a restart, taken from the ECS at a single point in
+/// time. This allows the actual write to happen asynchronously without needing to hold on to any components.
+#[derive(Clone)]
+pub(crate) struct CharacterSnapshot {
+    pub(crate) id: u32,
+    pub(crate) level: u8,
+    pub(crate) max_level: u8,
+    pub(crate) exp: u64,
+    pub(crate) sp: u32,
+    pub(crate) sp_exp: u32,
+    pub(crate) strength: u16,
+    pub(crate) intelligence: u16,
+    pub(crate) stat_points: u16,
+    pub(crate) current_hp: u32,
+    pub(crate) current_mp: u32,
+    pub(crate) berserk_points: u8,
+    pub(crate) gold: u64,
+    pub(crate) beginner_mark: bool,
+    pub(crate) region: u16,
+    pub(crate) x: f32,
+    pub(crate) y: f32,
+    pub(crate) z: f32,
+    pub(crate) rotation: u16,
+    pub(crate) items: Vec<ItemSnapshot>,
+    pub(crate) masteries: Vec<(u32, u8)>,
+    pub(crate) skills: Vec<u32>,
+}
+
+#[derive(Copy, Clone)]
+pub(crate) struct ItemSnapshot {
+    pub(crate) slot: u8,
+    pub(crate) ref_id: u32,
+    pub(crate) upgrade_level: u8,
+    pub(crate) variance: Option<u64>,
+    pub(crate) amount: u16,
+}
+
+impl CharacterSnapshot {
+    /// Writes the snapshot to the database, replacing the existing state of the character. Everything is written in a
+    /// single transaction such that we never end up with a partially saved character, e.g. with the gold of a trade
+    /// but without the item being removed.
+    pub(crate) async fn save<T: Borrow<PgPool>>(&self, pool: T) -> Result<(), Error> {
+        let mut transaction = pool.borrow().begin().await?;
+        let character_id = self.id as i32;
+
+        sqlx::query!(
+            "UPDATE characters SET level = $2, max_level = $3, exp = $4, sp = $5, sp_exp = $6, strength = $7, intelligence = $8, stat_points = $9, current_hp = $10, current_mp = $11, berserk_points = $12, gold = $13, beginner_mark = $14, region = $15, x = $16, y = $17, z = $18, rotation = $19 WHERE id = $1",
+            character_id,
+            self.level as i16,
+            self.max_level as i16,
+            self.exp as i64,
+            self.sp as i32,
+            self.sp_exp as i32,
+            self.strength as i16,
+            self.intelligence as i16,
+            self.stat_points as i16,
+            self.current_hp as i32,
+            self.current_mp as i32,
+            self.berserk_points as i16,
+            self.gold as i64,
+            self.beginner_mark,
+            self.region as i16,
+            self.x,
+            self.y,
+            self.z,
+            self.rotation as i16,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!("DELETE FROM character_items WHERE character_id = $1", character_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        let slots = self.items.iter().map(|item| item.slot as i16).collect::<Vec<_>>();
+        let ref_ids = self.items.iter().map(|item| item.ref_id as i32).collect::<Vec<_>>();
+        let upgrade_levels = self
+            .items
+            .iter()
+            .map(|item| item.upgrade_level as i16)
+            .collect::<Vec<_>>();
+        let variances = self
+            .items
+            .iter()
+            .map(|item| item.variance.map(|variance| variance as i64))
+            .collect::<Vec<_>>();
+        let amounts = self.items.iter().map(|item| item.amount as i16).collect::<Vec<_>>();
+        sqlx::query!(
+            "INSERT INTO character_items(character_id, slot, item_obj_id, upgrade_level, variance, amount) SELECT $1, * FROM UNNEST($2::SMALLINT[], $3::INTEGER[], $4::SMALLINT[], $5::BIGINT[], $6::SMALLINT[])",
+            character_id,
+            &slots,
+            &ref_ids,
+            &upgrade_levels,
+            &variances as &[Option<i64>],
+            &amounts,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        let mastery_ids = self.masteries.iter().map(|(id, _)| *id as i32).collect::<Vec<_>>();
+        let mastery_levels = self
+            .masteries
+            .iter()
+            .map(|(_, level)| *level as i16)
+            .collect::<Vec<_>>();
+        sqlx::query!(
+            "INSERT INTO character_masteries(character_id, mastery_id, level) SELECT $1, * FROM UNNEST($2::INTEGER[], $3::SMALLINT[]) ON CONFLICT (character_id, mastery_id) DO UPDATE SET level = EXCLUDED.level",
+            character_id,
+            &mastery_ids,
+            &mastery_levels,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!("DELETE FROM character_skills WHERE character_id = $1", character_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        let skill_ids = self.skills.iter().map(|id| *id as i32).collect::<Vec<_>>();
+        sqlx::query!(
+            "INSERT INTO character_skills(character_id, skill_id) SELECT $1, * FROM UNNEST($2::INTEGER[])",
+            character_id,
+            &skill_ids,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await
+    }
+}
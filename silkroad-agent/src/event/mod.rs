@@ -2,7 +2,8 @@ use crate::chat::command::Command;
 use crate::comp::{EntityReference, GameEntity};
 use bevy_ecs::prelude::*;
 use silkroad_data::skilldata::RefSkillData;
-use silkroad_game_base::GlobalPosition;
+use silkroad_game_base::{DamageOutcome, GlobalPosition};
+use std::time::Duration;
 
 #[derive(Event)]
 pub(crate) struct ClientConnectedEvent(pub Entity);
@@ -24,7 +25,11 @@ pub(crate) struct UniqueKilledEvent {
 
 pub(crate) struct AttackDefinition {
     pub skill: &'static RefSkillData,
-    pub instance: u32,
+    /// The instance of the attack this damage belongs to, or `None` for damage that is dealt over time.
+    pub instance: Option<u32>,
+    /// Whether this is the first hit of the attack instance. Skills hitting multiple times only cause their status
+    /// effects once per instance.
+    pub first_hit: bool,
 }
 
 #[derive(Event)]
@@ -32,7 +37,10 @@ pub(crate) struct DamageReceiveEvent {
     pub source: EntityReference,
     pub target: EntityReference,
     pub attack: AttackDefinition,
-    pub amount: u32,
+    pub damage: DamageOutcome,
+    /// The damage the remaining hits of the attack will deal to the target. This is only provided with the first hit,
+    /// as the client expects all hits of an attack to be reported at once, while they are only dealt over time.
+    pub later_hits: Vec<DamageOutcome>,
 }
 
 #[derive(Event)]
@@ -46,3 +54,10 @@ pub(crate) struct EntityDeath {
     pub died: EntityReference,
     pub killer: Option<EntityReference>,
 }
+
+#[derive(Event)]
+pub(crate) struct BuffApplyEvent {
+    pub target: Entity,
+    pub skill: &'static RefSkillData,
+    pub duration: Duration,
+}
@@ -0,0 +1,113 @@
+use bevy_ecs::prelude::*;
+use bevy_time::{Timer, TimerMode};
+use silkroad_game_base::Item;
+use std::collections::HashMap;
+
+pub(crate) const MAX_EXCHANGE_ITEMS: usize = 12;
+const INVITATION_TIMEOUT_SECONDS: f32 = 10.0;
+
+/// What one side of an exchange is willing to give to the other side.
+#[derive(Default)]
+pub(crate) struct ExchangeOffer {
+    pub(crate) gold: u64,
+    /// The offered items together with the inventory slot they are in. The item itself is kept to be able to verify
+    /// that the slot still contains the same item once the exchange is completed.
+    pub(crate) items: Vec<(u8, Item)>,
+}
+
+pub(crate) struct ExchangeParticipant {
+    pub(crate) entity: Entity,
+    pub(crate) unique_id: u32,
+    pub(crate) offer: ExchangeOffer,
+    /// Whether the participant is done with their offer, which prevents any further changes to it.
+    pub(crate) confirmed: bool,
+    /// Whether the participant agreed to the final state of both offers.
+    pub(crate) approved: bool,
+}
+
+impl ExchangeParticipant {
+    pub(crate) fn new(entity: Entity, unique_id: u32) -> Self {
+        ExchangeParticipant {
+            entity,
+            unique_id,
+            offer: ExchangeOffer::default(),
+            confirmed: false,
+            approved: false,
+        }
+    }
+}
+
+pub(crate) struct Exchange {
+    pub(crate) participants: [ExchangeParticipant; 2],
+}
+
+impl Exchange {
+    /// Provides the participant for the given entity first and their partner second.
+    pub(crate) fn sides_mut(&mut self, entity: Entity) -> Option<(&mut ExchangeParticipant, &mut ExchangeParticipant)> {
+        let [first, second] = &mut self.participants;
+        if first.entity == entity {
+            Some((first, second))
+        } else if second.entity == entity {
+            Some((second, first))
+        } else {
+            None
+        }
+    }
+}
+
+/// All exchanges currently in progress on this server.
+#[derive(Resource, Default)]
+pub(crate) struct Exchanges {
+    next_id: u32,
+    exchanges: HashMap<u32, Exchange>,
+}
+
+impl Exchanges {
+    pub(crate) fn start(&mut self, first: ExchangeParticipant, second: ExchangeParticipant) -> u32 {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.exchanges.insert(
+            id,
+            Exchange {
+                participants: [first, second],
+            },
+        );
+        id
+    }
+
+    pub(crate) fn get_mut(&mut self, id: u32) -> Option<&mut Exchange> {
+        self.exchanges.get_mut(&id)
+    }
+
+    pub(crate) fn remove(&mut self, id: u32) -> Option<Exchange> {
+        self.exchanges.remove(&id)
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&u32, &Exchange)> {
+        self.exchanges.iter()
+    }
+
+    /// Finds the exchange the given entity is taking part in.
+    pub(crate) fn find(&self, entity: Entity) -> Option<u32> {
+        self.exchanges
+            .iter()
+            .find(|(_, exchange)| exchange.participants.iter().any(|side| side.entity == entity))
+            .map(|(id, _)| *id)
+    }
+}
+
+/// A request to trade a player has not yet responded to.
+#[derive(Component)]
+pub(crate) struct PendingExchangeInvitation {
+    pub(crate) requester: Entity,
+    pub(crate) timeout: Timer,
+}
+
+impl PendingExchangeInvitation {
+    pub(crate) fn new(requester: Entity) -> Self {
+        PendingExchangeInvitation {
+            requester,
+            timeout: Timer::from_seconds(INVITATION_TIMEOUT_SECONDS, TimerMode::Once),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+pub(crate) mod component;
+mod system;
+
+use crate::exchange::component::Exchanges;
+use crate::exchange::system::{
+    cancel_changed_exchanges, cancel_distant_exchanges, expire_invitations, handle_exchange_requests, update_offers,
+};
+use bevy_app::{App, Plugin, PostUpdate, Update};
+use bevy_ecs::prelude::*;
+
+pub(crate) struct ExchangePlugin;
+
+impl Plugin for ExchangePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Exchanges>()
+            .add_systems(
+                Update,
+                (
+                    update_offers,
+                    handle_exchange_requests.after(update_offers),
+                    expire_invitations,
+                ),
+            )
+            .add_systems(PostUpdate, (cancel_changed_exchanges, cancel_distant_exchanges));
+    }
+}
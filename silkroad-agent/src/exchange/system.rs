@@ -0,0 +1,590 @@
+use crate::comp::inventory::PlayerInventory;
+use crate::comp::net::Client;
+use crate::comp::player::Player;
+use crate::comp::pos::Position;
+use crate::comp::GameEntity;
+use crate::exchange::component::{
+    Exchange, ExchangeOffer, ExchangeParticipant, Exchanges, PendingExchangeInvitation, MAX_EXCHANGE_ITEMS,
+};
+use crate::input::{ExchangeAction, PlayerInput};
+use crate::world::EntityLookup;
+use bevy_ecs::prelude::*;
+use bevy_time::Time;
+use silkroad_data::DataEntry;
+use silkroad_game_base::{Inventory, Item, ItemTypeData};
+use silkroad_protocol::exchange::{
+    ExchangeApproveResponse, ExchangeApproved, ExchangeCancelResponse, ExchangeCancelled, ExchangeCompleted,
+    ExchangeConfirmResponse, ExchangeConfirmed, ExchangeError, ExchangeInvitation, ExchangeOfferUpdate,
+    ExchangeRequestResponse, ExchangeResponse, ExchangeStarted,
+};
+use silkroad_protocol::inventory::{
+    InventoryItemBindingData, InventoryItemContentData, InventoryItemData, InventoryOperationError,
+    InventoryOperationRequest, InventoryOperationResponseData, InventoryOperationResult, RentInfo,
+};
+use silkroad_protocol::world::CharacterPointsUpdate;
+
+/// The maximum (squared) distance two players may be away from each other while trading.
+const MAX_EXCHANGE_DISTANCE: f32 = 100.0 * 100.0;
+
+fn item_data(slot: u8, item: &Item) -> InventoryItemData {
+    let content_data = match item.type_data {
+        ItemTypeData::Equipment { upgrade_level } => InventoryItemContentData::Equipment {
+            plus_level: upgrade_level,
+            variance: item.variance.unwrap_or_default(),
+            durability: 1,
+            magic: vec![],
+            bindings_1: InventoryItemBindingData::new(1, 0),
+            bindings_2: InventoryItemBindingData::new(2, 0),
+            bindings_3: InventoryItemBindingData::new(3, 0),
+            bindings_4: InventoryItemBindingData::new(4, 0),
+        },
+        _ => InventoryItemContentData::Expendable {
+            stack_size: item.stack_size(),
+        },
+    };
+    InventoryItemData::new(slot, RentInfo::Empty, item.reference.ref_id(), content_data)
+}
+
+fn offer_update(participant: &ExchangeParticipant) -> ExchangeOfferUpdate {
+    ExchangeOfferUpdate {
+        owner: participant.unique_id,
+        gold: participant.offer.gold,
+        items: participant
+            .offer
+            .items
+            .iter()
+            .enumerate()
+            .map(|(slot, (_, item))| item_data(slot as u8, item))
+            .collect(),
+    }
+}
+
+/// Ends the exchange without swapping anything, informing everyone involved except for the player who cancelled it.
+fn cancel_exchange(exchanges: &mut Exchanges, id: u32, cancelled_by: Option<Entity>, clients: &Query<&Client>) {
+    let Some(exchange) = exchanges.remove(id) else {
+        return;
+    };
+
+    for participant in exchange.participants.iter() {
+        if Some(participant.entity) == cancelled_by {
+            continue;
+        }
+        if let Ok(client) = clients.get(participant.entity) {
+            client.send(ExchangeCancelled);
+        }
+    }
+}
+
+fn offer_item(offer: &mut ExchangeOffer, inventory: &Inventory, slot: u8) -> Result<(), InventoryOperationError> {
+    if Inventory::is_equipment_slot(slot) || offer.items.iter().any(|(offered, _)| *offered == slot) {
+        return Err(InventoryOperationError::InvalidTarget);
+    }
+    if offer.items.len() >= MAX_EXCHANGE_ITEMS {
+        return Err(InventoryOperationError::InventoryFull);
+    }
+
+    let item = inventory
+        .get_item_at(slot)
+        .ok_or(InventoryOperationError::InvalidTarget)?;
+    offer.items.push((slot, *item));
+    Ok(())
+}
+
+fn change_offer(
+    offer: &mut ExchangeOffer,
+    inventory: &PlayerInventory,
+    request: &InventoryOperationRequest,
+) -> Result<InventoryOperationResponseData, InventoryOperationError> {
+    match *request {
+        InventoryOperationRequest::OfferExchangeItem { slot } => {
+            offer_item(offer, inventory, slot)?;
+            Ok(InventoryOperationResponseData::OfferExchangeItem { slot })
+        },
+        InventoryOperationRequest::RetractExchangeItem { slot } => {
+            if slot as usize >= offer.items.len() {
+                return Err(InventoryOperationError::InvalidTarget);
+            }
+            offer.items.remove(slot as usize);
+            Ok(InventoryOperationResponseData::RetractExchangeItem { slot })
+        },
+        InventoryOperationRequest::OfferExchangeGold { amount } => {
+            if amount > inventory.gold {
+                return Err(InventoryOperationError::NotEnoughGold);
+            }
+            offer.gold = amount;
+            Ok(InventoryOperationResponseData::OfferExchangeGold { amount })
+        },
+        _ => Err(InventoryOperationError::InvalidTarget),
+    }
+}
+
+pub(crate) fn update_offers(
+    query: Query<(Entity, &Client, &PlayerInput, &PlayerInventory)>,
+    clients: Query<&Client>,
+    mut exchanges: ResMut<Exchanges>,
+) {
+    for (entity, client, input, inventory) in query.iter() {
+        let Some(action) = input.inventory.as_ref().filter(|action| action.data.is_exchange()) else {
+            continue;
+        };
+
+        let Some((own, partner)) = exchanges
+            .find(entity)
+            .and_then(|id| exchanges.get_mut(id))
+            .and_then(|exchange| exchange.sides_mut(entity))
+        else {
+            client.send(InventoryOperationResult::Error(
+                InventoryOperationError::ExchangeCancelled,
+            ));
+            continue;
+        };
+
+        if own.confirmed {
+            client.send(InventoryOperationResult::Error(InventoryOperationError::Busy));
+            continue;
+        }
+
+        match change_offer(&mut own.offer, inventory, &action.data) {
+            Ok(response) => {
+                client.send(InventoryOperationResult::Success(response));
+                let update = offer_update(own);
+                if let Ok(partner_client) = clients.get(partner.entity) {
+                    partner_client.send(update.clone());
+                }
+                client.send(update);
+            },
+            Err(error) => client.send(InventoryOperationResult::Error(error)),
+        }
+    }
+}
+
+type Traders<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static GameEntity,
+        &'static Position,
+        Option<&'static PendingExchangeInvitation>,
+    ),
+    With<Player>,
+>;
+
+fn request_exchange(
+    requester: Entity,
+    target: Option<Entity>,
+    traders: &Traders,
+    exchanges: &Exchanges,
+    clients: &Query<&Client>,
+    cmd: &mut Commands,
+) -> Result<(), ExchangeError> {
+    let (requester_entity, requester_position, _) = traders.get(requester).map_err(|_| ExchangeError::InvalidTarget)?;
+    let target = target
+        .filter(|target| *target != requester)
+        .ok_or(ExchangeError::InvalidTarget)?;
+    let (_, position, pending) = traders.get(target).map_err(|_| ExchangeError::InvalidTarget)?;
+    if exchanges.find(requester).is_some() || exchanges.find(target).is_some() {
+        return Err(ExchangeError::AlreadyExchanging);
+    }
+    if pending.is_some() {
+        return Err(ExchangeError::InvitationPending);
+    }
+    if position.distance_to(requester_position) > MAX_EXCHANGE_DISTANCE {
+        return Err(ExchangeError::TooFarAway);
+    }
+
+    if let Ok(client) = clients.get(target) {
+        client.send(ExchangeInvitation {
+            requester: requester_entity.unique_id,
+        });
+    }
+    cmd.entity(target).insert(PendingExchangeInvitation::new(requester));
+    Ok(())
+}
+
+fn start_exchange(
+    requester: Entity,
+    invitee: Entity,
+    traders: &Traders,
+    exchanges: &mut Exchanges,
+    clients: &Query<&Client>,
+) -> Result<(), ExchangeError> {
+    let (requester_entity, requester_position, _) = traders.get(requester).map_err(|_| ExchangeError::InvalidTarget)?;
+    let (invitee_entity, invitee_position, _) = traders.get(invitee).map_err(|_| ExchangeError::InvalidTarget)?;
+    if exchanges.find(requester).is_some() || exchanges.find(invitee).is_some() {
+        return Err(ExchangeError::AlreadyExchanging);
+    }
+    if invitee_position.distance_to(requester_position) > MAX_EXCHANGE_DISTANCE {
+        return Err(ExchangeError::TooFarAway);
+    }
+
+    exchanges.start(
+        ExchangeParticipant::new(requester, requester_entity.unique_id),
+        ExchangeParticipant::new(invitee, invitee_entity.unique_id),
+    );
+    for (entity, partner) in [(requester, invitee_entity), (invitee, requester_entity)] {
+        if let Ok(client) = clients.get(entity) {
+            client.send(ExchangeStarted {
+                partner: partner.unique_id,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the offered gold and items are still in the inventory, as they might have been used, moved or
+/// otherwise changed since they were offered. Items need to be exactly the same as when they were offered, including
+/// their upgrade level and variance, not just the same kind of item.
+fn verify_offer(offer: &ExchangeOffer, inventory: &PlayerInventory) -> Result<(), ExchangeError> {
+    let unchanged = offer.gold <= inventory.gold
+        && offer.items.iter().all(|(slot, item)| {
+            inventory.get_item_at(*slot).is_some_and(|current| {
+                current.reference.ref_id() == item.reference.ref_id()
+                    && current.variance == item.variance
+                    && current.type_data == item.type_data
+            })
+        });
+    if unchanged {
+        Ok(())
+    } else {
+        Err(ExchangeError::OfferChanged)
+    }
+}
+
+/// Checks if the inventory can fit all received items, considering the slots that become free by giving away items.
+fn has_room_for(inventory: &PlayerInventory, given: &ExchangeOffer, received: &ExchangeOffer) -> bool {
+    inventory.free_slots() + given.items.len() >= received.items.len()
+}
+
+fn take_offer(offer: &ExchangeOffer, inventory: &mut PlayerInventory) -> Vec<Item> {
+    inventory.gold -= offer.gold;
+    offer
+        .items
+        .iter()
+        .map(|(slot, item)| {
+            inventory
+                .take_from(*slot, item.stack_size())
+                .expect("Offered item should exist after verifying the offer")
+        })
+        .collect()
+}
+
+fn receive_offer(items: Vec<Item>, gold: u64, inventory: &mut PlayerInventory) {
+    inventory.gold += gold;
+    for item in items {
+        inventory
+            .add_item(item)
+            .expect("Received item should fit after checking for room");
+    }
+}
+
+/// Swaps the offers of both participants. Either everything is swapped or, if any of the offers can no longer be
+/// fulfilled, nothing is.
+fn complete_exchange(exchange: &Exchange, inventories: &mut Query<&mut PlayerInventory>) -> Result<(), ExchangeError> {
+    let [first, second] = &exchange.participants;
+    let [mut first_inventory, mut second_inventory] = inventories
+        .get_many_mut([first.entity, second.entity])
+        .map_err(|_| ExchangeError::InvalidTarget)?;
+
+    verify_offer(&first.offer, &first_inventory)?;
+    verify_offer(&second.offer, &second_inventory)?;
+    if !has_room_for(&first_inventory, &first.offer, &second.offer)
+        || !has_room_for(&second_inventory, &second.offer, &first.offer)
+    {
+        return Err(ExchangeError::InventoryFull);
+    }
+
+    let first_items = take_offer(&first.offer, &mut first_inventory);
+    let second_items = take_offer(&second.offer, &mut second_inventory);
+    receive_offer(second_items, second.offer.gold, &mut first_inventory);
+    receive_offer(first_items, first.offer.gold, &mut second_inventory);
+    Ok(())
+}
+
+pub(crate) fn handle_exchange_requests(
+    query: Query<(Entity, &Client, &PlayerInput)>,
+    traders: Traders,
+    clients: Query<&Client>,
+    mut inventories: Query<&mut PlayerInventory>,
+    lookup: Res<EntityLookup>,
+    mut exchanges: ResMut<Exchanges>,
+    mut cmd: Commands,
+) {
+    for (entity, client, input) in query.iter() {
+        for action in input.exchange.iter() {
+            match action {
+                ExchangeAction::Request(request) => {
+                    if let Err(error) = request_exchange(
+                        entity,
+                        lookup.get_entity_for_id(request.target),
+                        &traders,
+                        &exchanges,
+                        &clients,
+                        &mut cmd,
+                    ) {
+                        client.send(ExchangeRequestResponse(ExchangeResponse::Error(error)));
+                    }
+                },
+                ExchangeAction::Reply(reply) => {
+                    let Some(invitation) = traders.get(entity).ok().and_then(|(_, _, invitation)| invitation) else {
+                        continue;
+                    };
+                    cmd.entity(entity).remove::<PendingExchangeInvitation>();
+
+                    let result = if reply.accepted {
+                        start_exchange(invitation.requester, entity, &traders, &mut exchanges, &clients)
+                    } else {
+                        Err(ExchangeError::InvitationDeclined)
+                    };
+
+                    if let Ok(requester) = clients.get(invitation.requester) {
+                        let response = match result {
+                            Ok(_) => ExchangeResponse::Success,
+                            Err(error) => ExchangeResponse::Error(error),
+                        };
+                        requester.send(ExchangeRequestResponse(response));
+                    }
+                },
+                ExchangeAction::Confirm => {
+                    let Some((own, partner)) = exchanges
+                        .find(entity)
+                        .and_then(|id| exchanges.get_mut(id))
+                        .and_then(|exchange| exchange.sides_mut(entity))
+                    else {
+                        client.send(ExchangeConfirmResponse(ExchangeResponse::Error(
+                            ExchangeError::NotExchanging,
+                        )));
+                        continue;
+                    };
+
+                    own.confirmed = true;
+                    client.send(ExchangeConfirmResponse(ExchangeResponse::Success));
+                    if let Ok(partner_client) = clients.get(partner.entity) {
+                        partner_client.send(ExchangeConfirmed);
+                    }
+                },
+                ExchangeAction::Approve => {
+                    let Some(id) = exchanges.find(entity) else {
+                        client.send(ExchangeApproveResponse(ExchangeResponse::Error(
+                            ExchangeError::NotExchanging,
+                        )));
+                        continue;
+                    };
+                    let exchange = exchanges.get_mut(id).expect("Exchange should exist after finding it");
+                    let (own, partner) = exchange
+                        .sides_mut(entity)
+                        .expect("Player should take part in the exchange they were found in");
+                    if !own.confirmed || !partner.confirmed {
+                        client.send(ExchangeApproveResponse(ExchangeResponse::Error(
+                            ExchangeError::NotConfirmed,
+                        )));
+                        continue;
+                    }
+
+                    own.approved = true;
+                    if !partner.approved {
+                        client.send(ExchangeApproveResponse(ExchangeResponse::Success));
+                        if let Ok(partner_client) = clients.get(partner.entity) {
+                            partner_client.send(ExchangeApproved);
+                        }
+                        continue;
+                    }
+
+                    let exchange = exchanges.remove(id).expect("Exchange should exist after finding it");
+                    match complete_exchange(&exchange, &mut inventories) {
+                        Ok(_) => {
+                            client.send(ExchangeApproveResponse(ExchangeResponse::Success));
+                            for participant in exchange.participants.iter() {
+                                let (Ok(participant_client), Ok(inventory)) =
+                                    (clients.get(participant.entity), inventories.get(participant.entity))
+                                else {
+                                    continue;
+                                };
+                                participant_client.send(ExchangeCompleted);
+                                participant_client.send(CharacterPointsUpdate::Gold {
+                                    amount: inventory.gold,
+                                    display: false,
+                                });
+                            }
+                        },
+                        Err(error) => {
+                            client.send(ExchangeApproveResponse(ExchangeResponse::Error(error)));
+                            for participant in exchange.participants.iter() {
+                                if let Ok(participant_client) = clients.get(participant.entity) {
+                                    participant_client.send(ExchangeCancelled);
+                                }
+                            }
+                        },
+                    }
+                },
+                ExchangeAction::Cancel => {
+                    let Some(id) = exchanges.find(entity) else {
+                        client.send(ExchangeCancelResponse(ExchangeResponse::Error(
+                            ExchangeError::NotExchanging,
+                        )));
+                        continue;
+                    };
+
+                    cancel_exchange(&mut exchanges, id, Some(entity), &clients);
+                    client.send(ExchangeCancelResponse(ExchangeResponse::Success));
+                },
+            }
+        }
+    }
+}
+
+pub(crate) fn expire_invitations(
+    mut query: Query<(Entity, &mut PendingExchangeInvitation)>,
+    clients: Query<&Client>,
+    time: Res<Time>,
+    mut cmd: Commands,
+) {
+    for (entity, mut invitation) in query.iter_mut() {
+        if !invitation.timeout.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        cmd.entity(entity).remove::<PendingExchangeInvitation>();
+        if let Ok(requester) = clients.get(invitation.requester) {
+            requester.send(ExchangeRequestResponse(ExchangeResponse::Error(
+                ExchangeError::InvitationDeclined,
+            )));
+        }
+    }
+}
+
+/// Cancels exchanges in which one of the players changed what they offered in their inventory, e.g. by moving, using,
+/// selling or reinforcing an offered item, or by spending the offered gold. This way, the partner never gets to
+/// approve an offer that no longer matches what would actually be swapped.
+pub(crate) fn cancel_changed_exchanges(
+    inventories: Query<Ref<PlayerInventory>>,
+    clients: Query<&Client>,
+    mut exchanges: ResMut<Exchanges>,
+) {
+    let changed = exchanges
+        .iter()
+        .filter(|(_, exchange)| {
+            exchange.participants.iter().any(|participant| {
+                inventories.get(participant.entity).is_ok_and(|inventory| {
+                    inventory.is_changed() && verify_offer(&participant.offer, &inventory).is_err()
+                })
+            })
+        })
+        .map(|(id, _)| *id)
+        .collect::<Vec<_>>();
+
+    for id in changed {
+        cancel_exchange(&mut exchanges, id, None, &clients);
+    }
+}
+
+/// Cancels exchanges in which the players moved too far away from each other, or in which one of the players is gone
+/// entirely, e.g. because they disconnected.
+pub(crate) fn cancel_distant_exchanges(
+    positions: Query<&Position>,
+    clients: Query<&Client>,
+    mut exchanges: ResMut<Exchanges>,
+) {
+    let broken = exchanges
+        .iter()
+        .filter(|(_, exchange)| {
+            let [first, second] = &exchange.participants;
+            match (positions.get(first.entity), positions.get(second.entity)) {
+                (Ok(first), Ok(second)) => first.distance_to(second) > MAX_EXCHANGE_DISTANCE,
+                _ => true,
+            }
+        })
+        .map(|(id, _)| *id)
+        .collect::<Vec<_>>();
+
+    for id in broken {
+        cancel_exchange(&mut exchanges, id, None, &clients);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy_ecs::system::SystemState;
+    use once_cell::sync::Lazy;
+    use silkroad_data::common::{RefCommon, RefOrigin};
+    use silkroad_data::itemdata::{RefBiologicalType, RefItemData};
+    use silkroad_definitions::type_id::{ObjectEquippable, ObjectItem, ObjectType, ObjectWeaponType};
+
+    const OFFERED_SLOT: u8 = 13;
+
+    static WEAPON_DATA: Lazy<RefItemData> = Lazy::new(|| RefItemData {
+        common: RefCommon {
+            ref_id: 1,
+            id: "TestWeapon".to_string(),
+            type_id: ObjectType::Item(ObjectItem::Equippable(ObjectEquippable::Weapon(
+                ObjectWeaponType::Blade,
+            )))
+            .type_id(),
+            country: RefOrigin::Chinese,
+            despawn_time: Default::default(),
+        },
+        price: 100,
+        sell_price: 25,
+        max_stack_size: 1,
+        range: None,
+        required_level: None,
+        biological_type: RefBiologicalType::Both,
+        item_class: 1,
+        params: [0, 0, 0, 0],
+        stats: Default::default(),
+    });
+
+    fn weapon(upgrade_level: u8) -> Item {
+        Item {
+            reference: &WEAPON_DATA,
+            variance: None,
+            type_data: ItemTypeData::Equipment { upgrade_level },
+        }
+    }
+
+    #[test]
+    fn test_changed_offer_is_rejected() {
+        let mut world = World::new();
+        let mut first_inventory = PlayerInventory::new(Inventory::new(45), 0);
+        first_inventory.set_item(OFFERED_SLOT, weapon(0));
+        let first = world.spawn(first_inventory).id();
+        let second = world.spawn(PlayerInventory::new(Inventory::new(45), 0)).id();
+
+        let mut offering = ExchangeParticipant::new(first, 1);
+        let offered = offer_item(
+            &mut offering.offer,
+            world.get::<PlayerInventory>(first).unwrap(),
+            OFFERED_SLOT,
+        );
+        assert!(offered.is_ok());
+        offering.confirmed = true;
+        let mut receiving = ExchangeParticipant::new(second, 2);
+        receiving.confirmed = true;
+        let exchange = Exchange {
+            participants: [offering, receiving],
+        };
+
+        // Reinforcing the offered item after confirming turns it into a different item than the one offered.
+        world
+            .get_mut::<PlayerInventory>(first)
+            .unwrap()
+            .set_item(OFFERED_SLOT, weapon(3));
+
+        let mut state: SystemState<Query<&mut PlayerInventory>> = SystemState::new(&mut world);
+        let mut inventories = state.get_mut(&mut world);
+        assert!(matches!(
+            complete_exchange(&exchange, &mut inventories),
+            Err(ExchangeError::OfferChanged)
+        ));
+        assert!(inventories.get(second).unwrap().get_item_at(OFFERED_SLOT).is_none());
+        assert_eq!(
+            3,
+            inventories
+                .get(first)
+                .unwrap()
+                .get_item_at(OFFERED_SLOT)
+                .unwrap()
+                .upgrade_level()
+        );
+    }
+}
@@ -2,7 +2,8 @@ use bevy_ecs_macros::Resource;
 use derive_more::{Deref, DerefMut, From};
 use id_pool::IdPool;
 use silkroad_data::npc_pos::NpcPosition;
-use silkroad_game_base::LocalLocation;
+use silkroad_game_base::{GlobalLocation, GlobalPosition, LocalLocation};
+use silkroad_navmesh::pathfinding::{NavmeshLocation, PathfindingError};
 use silkroad_navmesh::GlobalNavmesh;
 use silkroad_network::server::SilkroadServer;
 use sqlx::PgPool;
@@ -23,6 +24,11 @@ pub struct DbPool(PgPool);
 #[derive(Resource, Deref, From)]
 pub struct Navmesh(GlobalNavmesh);
 
+fn to_navmesh_location(location: GlobalLocation) -> NavmeshLocation {
+    let local = location.to_local();
+    NavmeshLocation(local.0, local.1)
+}
+
 impl Navmesh {
     fn height_for_location(&self, local: LocalLocation) -> Option<f32> {
         self.0
@@ -34,6 +40,35 @@ impl Navmesh {
         let local = location.into();
         self.height_for_location(local)
     }
+
+    /// Checks if anything is in the way when walking from `from` to `to` in a straight line. If there is,
+    /// returns the last location that can be reached.
+    pub fn raycast(&self, from: GlobalLocation, to: GlobalLocation) -> Option<GlobalLocation> {
+        let hit = self.0.raycast(to_navmesh_location(from), to_navmesh_location(to))?;
+        Some(LocalLocation(hit.0, hit.1).to_global())
+    }
+
+    pub fn has_line_of_sight(&self, from: GlobalLocation, to: GlobalLocation) -> bool {
+        self.0
+            .has_line_of_sight(to_navmesh_location(from), to_navmesh_location(to))
+    }
+
+    /// Finds a walkable path from `from` to `to` and returns the positions to walk to in order, excluding
+    /// the starting position.
+    pub fn find_path(&self, from: GlobalPosition, to: GlobalPosition) -> Result<Vec<GlobalPosition>, PathfindingError> {
+        let path = self.0.find_path(
+            to_navmesh_location(from.to_location()),
+            to_navmesh_location(to.to_location()),
+        )?;
+        Ok(path
+            .into_iter()
+            .map(|waypoint| {
+                let location = LocalLocation(waypoint.0, waypoint.1);
+                let height = self.height_for_location(location).unwrap_or(to.y);
+                location.to_global().with_y(height)
+            })
+            .collect())
+    }
 }
 
 #[derive(Resource, Deref, DerefMut, From)]
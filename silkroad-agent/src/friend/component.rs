@@ -0,0 +1,131 @@
+use crate::db::friend::{Friend as DbFriend, FriendGroup as DbFriendGroup};
+use bevy_ecs::prelude::*;
+use silkroad_protocol::world::{FriendListEntry, FriendListGroup, FriendListInfo};
+use tokio::sync::oneshot::Receiver;
+
+#[derive(Clone)]
+pub(crate) struct Friend {
+    pub(crate) character_id: u32,
+    pub(crate) name: String,
+    pub(crate) ref_id: u32,
+    pub(crate) group_id: u16,
+}
+
+impl Friend {
+    pub(crate) fn as_protocol(&self, online: bool) -> FriendListEntry {
+        FriendListEntry::new(
+            self.character_id,
+            self.name.clone(),
+            self.ref_id,
+            self.group_id,
+            !online,
+        )
+    }
+}
+
+#[derive(Component)]
+pub(crate) struct FriendList {
+    groups: Vec<FriendListGroup>,
+    friends: Vec<Friend>,
+}
+
+impl FriendList {
+    pub(crate) fn from_db(friends: &[DbFriend], groups: &[DbFriendGroup]) -> Self {
+        FriendList {
+            groups: groups
+                .iter()
+                .map(|group| FriendListGroup::new(group.id as u16, group.name.clone()))
+                .collect(),
+            friends: friends
+                .iter()
+                .map(|friend| Friend {
+                    character_id: friend.friend_id as u32,
+                    name: friend.name.clone(),
+                    ref_id: friend.character_type as u32,
+                    group_id: friend.group_id.unwrap_or_default() as u16,
+                })
+                .collect(),
+        }
+    }
+
+    pub(crate) fn friends(&self) -> impl Iterator<Item = &Friend> {
+        self.friends.iter()
+    }
+
+    pub(crate) fn is_friend(&self, character_id: u32) -> bool {
+        self.friends.iter().any(|friend| friend.character_id == character_id)
+    }
+
+    pub(crate) fn add(&mut self, friend: Friend) {
+        if !self.is_friend(friend.character_id) {
+            self.friends.push(friend);
+        }
+    }
+
+    pub(crate) fn remove(&mut self, character_id: u32) -> Option<Friend> {
+        let index = self
+            .friends
+            .iter()
+            .position(|friend| friend.character_id == character_id)?;
+        Some(self.friends.remove(index))
+    }
+
+    pub(crate) fn has_group(&self, name: &str) -> bool {
+        self.groups.iter().any(|group| group.name == name)
+    }
+
+    pub(crate) fn add_group(&mut self, group: FriendListGroup) {
+        self.groups.push(group);
+    }
+
+    /// Creates the friend list as shown to the client, where `is_online` decides whether a friend is shown as online.
+    pub(crate) fn as_protocol<F: Fn(&Friend) -> bool>(&self, is_online: F) -> FriendListInfo {
+        let mut groups = vec![FriendListGroup::not_assigned()];
+        groups.extend(self.groups.iter().cloned());
+        FriendListInfo::new(
+            groups,
+            self.friends
+                .iter()
+                .map(|friend| friend.as_protocol(is_online(friend)))
+                .collect(),
+        )
+    }
+}
+
+pub(crate) struct PendingFriendRequest {
+    pub(crate) requester: Entity,
+    pub(crate) unique_id: u32,
+    pub(crate) friend: Friend,
+    /// Finishes once the request has been stored, which needs to happen before its answer is stored.
+    pub(crate) stored: Receiver<()>,
+}
+
+/// The friend requests a player has received but not yet answered.
+#[derive(Component, Default)]
+pub(crate) struct FriendRequests(Vec<PendingFriendRequest>);
+
+impl FriendRequests {
+    pub(crate) fn has_request_from(&self, unique_id: u32) -> bool {
+        self.0.iter().any(|request| request.unique_id == unique_id)
+    }
+
+    pub(crate) fn add(&mut self, request: PendingFriendRequest) {
+        self.0.push(request);
+    }
+
+    pub(crate) fn take(&mut self, unique_id: u32) -> Option<PendingFriendRequest> {
+        let index = self.0.iter().position(|request| request.unique_id == unique_id)?;
+        Some(self.0.remove(index))
+    }
+
+    pub(crate) fn drain(&mut self) -> impl Iterator<Item = PendingFriendRequest> + '_ {
+        self.0.drain(..)
+    }
+}
+
+#[derive(Component)]
+#[component(storage = "SparseSet")]
+pub(crate) struct FriendGroupCreation {
+    pub(crate) name: String,
+    pub(crate) task: Receiver<Result<i32, sqlx::Error>>,
+}
@@ -0,0 +1,20 @@
+pub(crate) mod component;
+mod system;
+
+use crate::friend::system::{
+    discard_friend_requests, finish_friend_group_creation, handle_friend_input, notify_friends_offline,
+    notify_friends_online,
+};
+use bevy_app::{App, Plugin, PreUpdate, Update};
+
+pub(crate) struct FriendPlugin;
+
+impl Plugin for FriendPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreUpdate, (notify_friends_offline, discard_friend_requests))
+            .add_systems(
+                Update,
+                (handle_friend_input, finish_friend_group_creation, notify_friends_online),
+            );
+    }
+}
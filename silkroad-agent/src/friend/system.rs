@@ -0,0 +1,327 @@
+use crate::comp::net::Client;
+use crate::comp::player::Player;
+use crate::comp::GameEntity;
+use crate::db::friend::{Friend as DbFriend, FriendGroup as DbFriendGroup};
+use crate::event::{ClientDisconnectedEvent, LoadingFinishedEvent};
+use crate::ext::DbPool;
+use crate::friend::component::{Friend, FriendGroupCreation, FriendList, FriendRequests, PendingFriendRequest};
+use crate::input::{FriendAction, PlayerInput};
+use crate::tasks::TaskCreator;
+use crate::world::EntityLookup;
+use bevy_ecs::prelude::*;
+use bevy_ecs::query::WorldQuery;
+use silkroad_protocol::world::{
+    AddFriendResponse, CreateFriendGroupResponse, DeleteFriendResponse, FriendError, FriendListGroup, FriendRequest,
+    FriendUpdate,
+};
+use sqlx::PgPool;
+use std::future::Future;
+use tokio::sync::oneshot::error::TryRecvError;
+use tokio::sync::oneshot::Receiver;
+use tracing::warn;
+
+#[derive(WorldQuery)]
+#[world_query(mutable)]
+pub(crate) struct FriendHolder {
+    client: &'static Client,
+    game_entity: &'static GameEntity,
+    player: &'static Player,
+    friends: &'static mut FriendList,
+    requests: &'static mut FriendRequests,
+}
+
+fn as_friend(player: &Player, game_entity: &GameEntity) -> Friend {
+    Friend {
+        character_id: player.character.id,
+        name: player.character.name.clone(),
+        ref_id: game_entity.ref_id,
+        group_id: 0,
+    }
+}
+
+async fn log_failure<F>(update: F)
+where
+    F: Future<Output = Result<(), sqlx::Error>>,
+{
+    if let Err(e) = update.await {
+        warn!("Could not update friends. {:?}", e);
+    }
+}
+
+fn update_in_background<F>(task_creator: &TaskCreator, update: F)
+where
+    F: Future<Output = Result<(), sqlx::Error>> + Send + 'static,
+{
+    task_creator.spawn(log_failure(update));
+}
+
+/// Stores the answer to a friend request once the request itself has been stored, as storing the request afterwards
+/// would otherwise overwrite the answer.
+fn answer_in_background<F>(task_creator: &TaskCreator, stored: Receiver<()>, answer: F)
+where
+    F: Future<Output = Result<(), sqlx::Error>> + Send + 'static,
+{
+    update_in_background(task_creator, async move {
+        // Even if storing the request failed, the answer should still be stored.
+        let _ = stored.await;
+        answer.await
+    });
+}
+
+pub(crate) fn handle_friend_input(
+    inputs: Query<(Entity, &PlayerInput)>,
+    mut players: Query<FriendHolder>,
+    lookup: Res<EntityLookup>,
+    task_creator: Res<TaskCreator>,
+    pool: Res<DbPool>,
+    mut cmd: Commands,
+) {
+    for (entity, input) in inputs.iter() {
+        for action in input.friend.iter() {
+            match action {
+                FriendAction::Add(add) => {
+                    let target = lookup.get_entity_for_name(&add.name).filter(|target| *target != entity);
+                    let Some(Ok([requester, mut target])) = target.map(|target| players.get_many_mut([entity, target]))
+                    else {
+                        if let Ok(requester) = players.get(entity) {
+                            requester
+                                .client
+                                .send(AddFriendResponse::Error(FriendError::InvalidTarget));
+                        }
+                        continue;
+                    };
+
+                    if requester.friends.is_friend(target.player.character.id) {
+                        requester
+                            .client
+                            .send(AddFriendResponse::Error(FriendError::AlreadyFriends));
+                        continue;
+                    }
+                    if target.requests.has_request_from(requester.game_entity.unique_id) {
+                        requester
+                            .client
+                            .send(AddFriendResponse::Error(FriendError::RequestPending));
+                        continue;
+                    }
+
+                    target.client.send(FriendRequest {
+                        requester: requester.game_entity.unique_id,
+                        name: requester.player.character.name.clone(),
+                    });
+                    let stored = task_creator.create_task(log_failure(DbFriend::request(
+                        requester.player.character.id,
+                        target.player.character.id,
+                        PgPool::clone(&pool),
+                    )));
+                    target.requests.add(PendingFriendRequest {
+                        requester: entity,
+                        unique_id: requester.game_entity.unique_id,
+                        friend: as_friend(requester.player, requester.game_entity),
+                        stored,
+                    });
+                },
+                FriendAction::Reply(reply) => {
+                    let Ok(mut acceptor) = players.get_mut(entity) else {
+                        continue;
+                    };
+                    let Some(request) = acceptor.requests.take(reply.requester) else {
+                        continue;
+                    };
+                    let acceptor_friend = as_friend(acceptor.player, acceptor.game_entity);
+
+                    if reply.accepted {
+                        answer_in_background(
+                            &task_creator,
+                            request.stored,
+                            DbFriend::accept(
+                                request.friend.character_id,
+                                acceptor_friend.character_id,
+                                PgPool::clone(&pool),
+                            ),
+                        );
+                    } else {
+                        answer_in_background(
+                            &task_creator,
+                            request.stored,
+                            DbFriend::decline(
+                                request.friend.character_id,
+                                acceptor_friend.character_id,
+                                PgPool::clone(&pool),
+                            ),
+                        );
+                    }
+
+                    let requester_online = players
+                        .get(request.requester)
+                        .is_ok_and(|requester| requester.game_entity.unique_id == request.unique_id);
+                    if reply.accepted {
+                        let mut acceptor = players.get_mut(entity).expect("Acceptor should still exist");
+                        acceptor
+                            .client
+                            .send(FriendUpdate::Added(request.friend.as_protocol(requester_online)));
+                        acceptor.friends.add(request.friend);
+                    }
+
+                    if !requester_online {
+                        continue;
+                    }
+                    let Ok(mut requester) = players.get_mut(request.requester) else {
+                        continue;
+                    };
+                    if reply.accepted {
+                        requester
+                            .client
+                            .send(AddFriendResponse::Success(acceptor_friend.as_protocol(true)));
+                        requester.friends.add(acceptor_friend);
+                    } else {
+                        requester
+                            .client
+                            .send(AddFriendResponse::Error(FriendError::RequestDeclined));
+                    }
+                },
+                FriendAction::CreateGroup(create) => {
+                    let Ok(player) = players.get(entity) else {
+                        continue;
+                    };
+                    if player.friends.has_group(&create.name) {
+                        player
+                            .client
+                            .send(CreateFriendGroupResponse::Error(FriendError::GroupExists));
+                        continue;
+                    }
+
+                    let task = task_creator.create_task(DbFriendGroup::create(
+                        player.player.character.id,
+                        create.name.clone(),
+                        PgPool::clone(&pool),
+                    ));
+                    cmd.entity(entity).insert(FriendGroupCreation {
+                        name: create.name.clone(),
+                        task,
+                    });
+                },
+                FriendAction::Delete(delete) => {
+                    let Ok(mut player) = players.get_mut(entity) else {
+                        continue;
+                    };
+                    let Some(friend) = player.friends.remove(delete.friend_character_id) else {
+                        player.client.send(DeleteFriendResponse::Error(FriendError::NotFriends));
+                        continue;
+                    };
+                    player.client.send(DeleteFriendResponse::Success {
+                        friend_character_id: friend.character_id,
+                    });
+                    let character_id = player.player.character.id;
+                    update_in_background(
+                        &task_creator,
+                        DbFriend::delete(character_id, friend.character_id, PgPool::clone(&pool)),
+                    );
+
+                    if let Some(mut other) = lookup
+                        .get_entity_for_name(&friend.name)
+                        .and_then(|other| players.get_mut(other).ok())
+                    {
+                        if other.friends.remove(character_id).is_some() {
+                            other.client.send(FriendUpdate::Removed { char_id: character_id });
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+pub(crate) fn finish_friend_group_creation(
+    mut query: Query<(Entity, &Client, &mut FriendList, &mut FriendGroupCreation)>,
+    mut cmd: Commands,
+) {
+    for (entity, client, mut friends, mut creation) in query.iter_mut() {
+        match creation.task.try_recv() {
+            Ok(Ok(id)) => {
+                let group = FriendListGroup::new(id as u16, creation.name.clone());
+                friends.add_group(group.clone());
+                client.send(CreateFriendGroupResponse::Success(group));
+            },
+            Ok(Err(e)) => {
+                warn!(id = ?client.0.id(), "Could not create friend group. {:?}", e);
+                client.send(CreateFriendGroupResponse::Error(FriendError::GroupExists));
+            },
+            Err(TryRecvError::Empty) => continue,
+            Err(e) => {
+                warn!(id = ?client.0.id(), "Error when creating friend group. {:?}", e);
+            },
+        }
+        cmd.entity(entity).remove::<FriendGroupCreation>();
+    }
+}
+
+pub(crate) fn discard_friend_requests(
+    mut events: EventReader<ClientDisconnectedEvent>,
+    mut query: Query<(&Player, &mut FriendRequests)>,
+    task_creator: Res<TaskCreator>,
+    pool: Res<DbPool>,
+) {
+    for event in events.iter() {
+        let Ok((player, mut requests)) = query.get_mut(event.0) else {
+            continue;
+        };
+        for request in requests.drain() {
+            answer_in_background(
+                &task_creator,
+                request.stored,
+                DbFriend::decline(request.friend.character_id, player.character.id, PgPool::clone(&pool)),
+            );
+        }
+    }
+}
+
+fn notify_friends(friends: &FriendList, lookup: &EntityLookup, clients: &Query<&Client>, update: FriendUpdate) {
+    for friend in friends.friends() {
+        if let Some(client) = lookup
+            .get_entity_for_name(&friend.name)
+            .and_then(|entity| clients.get(entity).ok())
+        {
+            client.send(update.clone());
+        }
+    }
+}
+
+pub(crate) fn notify_friends_online(
+    mut events: EventReader<LoadingFinishedEvent>,
+    query: Query<(&Player, &FriendList)>,
+    clients: Query<&Client>,
+    lookup: Res<EntityLookup>,
+) {
+    for event in events.iter() {
+        if let Ok((player, friends)) = query.get(event.0) {
+            notify_friends(
+                friends,
+                &lookup,
+                &clients,
+                FriendUpdate::Online {
+                    char_id: player.character.id,
+                },
+            );
+        }
+    }
+}
+
+pub(crate) fn notify_friends_offline(
+    mut events: EventReader<ClientDisconnectedEvent>,
+    query: Query<(&Player, &FriendList)>,
+    clients: Query<&Client>,
+    lookup: Res<EntityLookup>,
+) {
+    for event in events.iter() {
+        if let Ok((player, friends)) = query.get(event.0) {
+            notify_friends(
+                friends,
+                &lookup,
+                &clients,
+                FriendUpdate::Offline {
+                    char_id: player.character.id,
+                },
+            );
+        }
+    }
+}
@@ -1,20 +1,42 @@
+use crate::agent::states::Dead;
 use crate::comp::drop::Drop;
 use crate::comp::net::Client;
+use crate::comp::player::{Buffed, Player};
 use crate::comp::{EntityReference, GameEntity};
-use crate::game::mind::Mind;
+use crate::game::mind::{Mind, SkillTarget};
 use crate::input::PlayerInput;
 use crate::world::{EntityLookup, WorldData};
 use bevy_ecs::prelude::*;
+use bevy_ecs::query::WorldQuery;
+use cgmath::Vector3;
+use silkroad_game_base::LocalPosition;
 use silkroad_protocol::combat::{ActionTarget, DoActionType, PerformAction, PerformActionError, PerformActionResponse};
-use tracing::warn;
+use tracing::debug;
+
+#[derive(WorldQuery)]
+#[world_query(mutable)]
+pub(crate) struct ActingPlayer {
+    client: &'static Client,
+    player: &'static Player,
+    input: &'static PlayerInput,
+    mind: &'static mut Mind,
+    buffed: &'static mut Buffed,
+}
 
 pub(crate) fn handle_action(
-    mut query: Query<(&Client, &PlayerInput, &mut Mind)>,
+    mut query: Query<ActingPlayer, Without<Dead>>,
     lookup: Res<EntityLookup>,
     target_query: Query<&GameEntity>,
     pickup_query: Query<&GameEntity, With<Drop>>,
 ) {
-    for (client, input, mut mind) in query.iter_mut() {
+    for ActingPlayerItem {
+        client,
+        player,
+        input,
+        mut mind,
+        mut buffed,
+    } in query.iter_mut()
+    {
         let Some(ref action) = input.action else {
             continue;
         };
@@ -53,30 +75,55 @@ pub(crate) fn handle_action(
                     },
                     _ => continue,
                 },
-                DoActionType::UseSkill { ref_id, target } => match target {
-                    ActionTarget::Entity(unique_id) => {
-                        let Some(target) = lookup.get_entity_for_id(*unique_id) else {
-                            client.send(PerformActionResponse::Stop(PerformActionError::InvalidTarget));
-                            continue;
-                        };
+                DoActionType::UseSkill { ref_id, target } => {
+                    let target = match target {
+                        ActionTarget::Entity(unique_id) => {
+                            let Some(target) = lookup.get_entity_for_id(*unique_id) else {
+                                client.send(PerformActionResponse::Stop(PerformActionError::InvalidTarget));
+                                continue;
+                            };
 
-                        let Ok(found_target) = target_query.get(target) else {
-                            client.send(PerformActionResponse::Stop(PerformActionError::InvalidTarget));
-                            continue;
-                        };
+                            let Ok(found_target) = target_query.get(target) else {
+                                client.send(PerformActionResponse::Stop(PerformActionError::InvalidTarget));
+                                continue;
+                            };
 
-                        let Some(skill) = WorldData::skills().find_id(*ref_id) else {
-                            client.send(PerformActionResponse::Stop(PerformActionError::NotLearned));
-                            continue;
-                        };
+                            SkillTarget::Entity(EntityReference(target, *found_target))
+                        },
+                        // Skills without a target, like most buffs or area skills around the player, are used by
+                        // the player on themselves.
+                        ActionTarget::None => SkillTarget::Own,
+                        ActionTarget::Area(location) => SkillTarget::Location(
+                            LocalPosition(
+                                location.region.into(),
+                                Vector3::new(location.pos_x, location.pos_y, location.pos_z),
+                            )
+                            .to_global(),
+                        ),
+                    };
 
-                        mind.attack_with(EntityReference(target, *found_target), skill)
-                    },
-                    _ => {
-                        warn!("Tried to use a skill on unsupported target.")
-                    },
+                    let Some(skill) = WorldData::skills().find_id(*ref_id) else {
+                        client.send(PerformActionResponse::Stop(PerformActionError::NotLearned));
+                        continue;
+                    };
+
+                    if !player
+                        .character
+                        .skills
+                        .iter()
+                        .any(|learned| learned.ref_id == skill.ref_id)
+                    {
+                        client.send(PerformActionResponse::Stop(PerformActionError::NotLearned));
+                        continue;
+                    }
+
+                    mind.attack_with(target, skill)
+                },
+                DoActionType::CancelBuff { ref_id, .. } => {
+                    if !buffed.remove_skill(*ref_id) {
+                        debug!("Tried to cancel buff {} which was not active.", ref_id);
+                    }
                 },
-                DoActionType::CancelBuff { .. } => {},
             },
             PerformAction::Stop => mind.cancel(),
         }
@@ -0,0 +1,147 @@
+use crate::comp::inventory::PlayerInventory;
+use crate::comp::net::Client;
+use crate::config::GameConfig;
+use crate::input::PlayerInput;
+use bevy_ecs::prelude::*;
+use silkroad_definitions::type_id::{
+    ObjectConsumable, ObjectConsumableAlchemy, ObjectEquippable, ObjectItem, ObjectType,
+};
+use silkroad_game_base::{reinforce, Inventory, Item, ItemTypeData, MAX_UPGRADE_LEVEL};
+use silkroad_protocol::alchemy::{AlchemyError, AlchemyReinforceRequest, AlchemyReinforceResponse};
+use tracing::debug;
+
+fn item_type(item: &Item) -> Option<ObjectItem> {
+    ObjectType::from_type_id(&item.reference.common.type_id).and_then(|ty| ty.as_item())
+}
+
+fn is_alchemy_item(item: &Item, kind: ObjectConsumableAlchemy) -> bool {
+    matches!(
+        item_type(item),
+        Some(ObjectItem::Consumable(ObjectConsumable::AlchemyUpgrade(alchemy))) if alchemy == kind
+    )
+}
+
+/// Checks if the elixir can be used on the given kind of equipment. Elixirs don't carry their target in their type,
+/// only their code name ends in the kind of equipment they are meant for.
+fn elixir_fits(elixir: &Item, equipment: ObjectEquippable) -> bool {
+    let kind = match equipment {
+        ObjectEquippable::Weapon(_) => "_WEAPON",
+        ObjectEquippable::Shield(_) => "_SHIELD",
+        ObjectEquippable::Clothing(_, _) => "_ARMOR",
+        ObjectEquippable::Jewelry(_, _) => "_ACCESSARY",
+        _ => return false,
+    };
+    elixir.reference.common.id.contains(kind)
+}
+
+/// Provides the degree of a lucky powder. Like elixirs, lucky powders only carry the degree they're meant for at the
+/// end of their code name.
+fn powder_degree(powder: &Item) -> Option<u8> {
+    powder.reference.common.id.rsplit('_').next()?.parse().ok()
+}
+
+/// Validates the items used for reinforcing and provides the slot of the equipment, the elixir and optionally the
+/// lucky powder. Items can only be reinforced as far as there are success chances configured for.
+fn reinforcement_items(
+    inventory: &Inventory,
+    slots: &[u8],
+    chances: &[f32],
+) -> Result<(u8, u8, Option<u8>), AlchemyError> {
+    let (&equipment_slot, &elixir_slot, powder_slot) = match slots {
+        [equipment, elixir] => (equipment, elixir, None),
+        [equipment, elixir, powder] => (equipment, elixir, Some(*powder)),
+        _ => return Err(AlchemyError::InvalidItem),
+    };
+
+    if Inventory::is_equipment_slot(equipment_slot) {
+        return Err(AlchemyError::InvalidItem);
+    }
+    let equipment = inventory.get_item_at(equipment_slot).ok_or(AlchemyError::InvalidItem)?;
+    let Some(ObjectItem::Equippable(kind)) = item_type(equipment) else {
+        return Err(AlchemyError::InvalidItem);
+    };
+    if !matches!(equipment.type_data, ItemTypeData::Equipment { .. }) {
+        return Err(AlchemyError::InvalidItem);
+    }
+    let max_level = chances.len().min(MAX_UPGRADE_LEVEL as usize);
+    if equipment.upgrade_level() as usize >= max_level {
+        return Err(AlchemyError::MaxLevelReached);
+    }
+
+    let elixir = inventory.get_item_at(elixir_slot).ok_or(AlchemyError::InvalidElixir)?;
+    if !is_alchemy_item(elixir, ObjectConsumableAlchemy::Elixir) || !elixir_fits(elixir, kind) {
+        return Err(AlchemyError::InvalidElixir);
+    }
+
+    if let Some(powder_slot) = powder_slot {
+        let powder = inventory
+            .get_item_at(powder_slot)
+            .ok_or(AlchemyError::InvalidLuckyPowder)?;
+        if !is_alchemy_item(powder, ObjectConsumableAlchemy::LuckyPowder)
+            || powder_degree(powder) != Some(equipment.reference.degree())
+        {
+            return Err(AlchemyError::InvalidLuckyPowder);
+        }
+    }
+
+    Ok((equipment_slot, elixir_slot, powder_slot))
+}
+
+pub(crate) fn handle_reinforce(
+    mut query: Query<(&Client, &PlayerInput, &mut PlayerInventory)>,
+    config: Res<GameConfig>,
+) {
+    for (client, input, mut inventory) in query.iter_mut() {
+        let Some(ref request) = input.alchemy else {
+            continue;
+        };
+
+        let slots = match request {
+            AlchemyReinforceRequest::Fuse { slots, .. } => slots,
+            AlchemyReinforceRequest::Cancel { .. } => {
+                client.send(AlchemyReinforceResponse::Cancelled);
+                continue;
+            },
+        };
+
+        let (equipment_slot, elixir_slot, powder_slot) =
+            match reinforcement_items(&inventory, slots, &config.reinforce_chances) {
+                Ok(items) => items,
+                Err(error) => {
+                    client.send(AlchemyReinforceResponse::Error(error));
+                    continue;
+                },
+            };
+
+        inventory.consume_at(elixir_slot);
+        if let Some(powder_slot) = powder_slot {
+            inventory.consume_at(powder_slot);
+        }
+
+        let current_level = inventory
+            .get_item_at(equipment_slot)
+            .expect("Equipment should exist after validating it")
+            .upgrade_level();
+        let outcome = reinforce(
+            &mut rand::thread_rng(),
+            &config.reinforce_chances,
+            current_level,
+            powder_slot.is_some(),
+        );
+        inventory
+            .set_upgrade_level(equipment_slot, outcome.upgrade_level())
+            .expect("Equipment should be upgradable after validating it");
+        debug!(
+            "Reinforced item in slot {} from +{} to +{}.",
+            equipment_slot,
+            current_level,
+            outcome.upgrade_level()
+        );
+
+        client.send(AlchemyReinforceResponse::Fused {
+            succeeded: outcome.is_success(),
+            slot: equipment_slot,
+            upgrade_level: outcome.upgrade_level(),
+        });
+    }
+}
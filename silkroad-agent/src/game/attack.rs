@@ -1,16 +1,20 @@
 use crate::agent::states;
 use crate::agent::states::{Action, ActionDescription, MovementGoal, Moving, StateTransitionQueue};
+use crate::comp::cooldown::SkillCooldowns;
 use crate::comp::inventory::PlayerInventory;
 use crate::comp::pos::Position;
-use crate::comp::{EntityReference, GameEntity};
+use crate::comp::{GameEntity, Mana};
 use crate::ext::Navmesh;
 use crate::world::WorldData;
-use bevy_ecs_macros::Resource;
+use bevy_ecs::prelude::*;
+use bevy_time::Time;
 use cgmath::num_traits::Pow;
+use cgmath::MetricSpace;
 use derive_more::Constructor;
 use silkroad_data::skilldata::RefSkillData;
 use silkroad_definitions::inventory::EquipmentSlot;
-use silkroad_game_base::{AttackSkill, AttackSkillError, Item};
+use silkroad_game_base::{AttackSkill, AttackSkillError, GlobalPosition, Item};
+use silkroad_protocol::combat::PerformActionError;
 
 #[derive(Default, Resource)]
 pub struct AttackInstanceCounter(u32);
@@ -41,36 +45,163 @@ impl Attack {
     }
 }
 
+pub(crate) fn tick_skill_cooldowns(mut query: Query<&mut SkillCooldowns>, time: Res<Time>) {
+    let delta = time.delta();
+    for mut cooldowns in query.iter_mut().filter(|cooldowns| !cooldowns.is_empty()) {
+        cooldowns.tick(delta);
+    }
+}
+
+/// Pays for using the skill by consuming the required mana and putting the skill on cooldown. This only happens once
+/// the skill is actually being used, as the entity may still get interrupted before getting to use it. Since the
+/// skill was only checked when it got queued, it needs to be checked again using [Caster::check] right before paying,
+/// as another skill may have used up the mana or started the cooldown in the meantime.
+pub(crate) fn pay_for_skill(skill: &RefSkillData, mana: Option<&mut Mana>, cooldowns: Option<&mut SkillCooldowns>) {
+    if let Some(mana) = mana {
+        mana.reduce(skill.consumed_mp);
+    }
+
+    if let Some(cooldowns) = cooldowns {
+        cooldowns.start(skill);
+    }
+}
+
+/// The parts of an entity which decide whether it is able to use a skill. Monsters neither have mana nor do they keep
+/// track of their cooldowns, so they can use their skills freely.
+pub(crate) struct Caster<'a> {
+    weapon: Option<&'a Item>,
+    secondary: Option<&'a Item>,
+    mana: Option<&'a Mana>,
+    cooldowns: Option<&'a SkillCooldowns>,
+}
+
+impl<'a> Caster<'a> {
+    pub(crate) fn new(
+        inventory: Option<&'a PlayerInventory>,
+        mana: Option<&'a Mana>,
+        cooldowns: Option<&'a SkillCooldowns>,
+    ) -> Self {
+        Caster {
+            weapon: inventory.and_then(|inv| inv.get_equipment_item(EquipmentSlot::Weapon)),
+            secondary: inventory.and_then(|inv| inv.get_equipment_item(EquipmentSlot::SecondaryWeapon)),
+            mana,
+            cooldowns,
+        }
+    }
+
+    /// Checks if the equipped weapons fulfill the requirements of the skill.
+    pub(crate) fn check_requirements(&self, skill: &RefSkillData) -> Result<(), AttackSkillError> {
+        AttackSkill::check_requirements(
+            skill,
+            self.weapon.map(|item| item.reference),
+            self.secondary.map(|item| item.reference),
+        )
+    }
+
+    /// Checks if the skill could be used right now.
+    pub(crate) fn check(&self, skill: &RefSkillData) -> Result<(), AttackProcessError> {
+        self.check_requirements(skill)?;
+
+        if self.cooldowns.is_some_and(|cooldowns| cooldowns.is_active(skill)) {
+            return Err(AttackProcessError::Cooldown);
+        }
+
+        if self.mana.is_some_and(|mana| mana.current_mana < skill.consumed_mp) {
+            return Err(AttackProcessError::InsufficientMana);
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Constructor)]
 pub(crate) struct AttackProcess<'a> {
     state: &'a mut StateTransitionQueue,
     position: &'a Position,
     skill: &'static RefSkillData,
-    weapon: Option<&'a Item>,
-    target: &'a EntityReference,
-    target_pos: &'a Position,
+    caster: Caster<'a>,
+    target: states::ActionTarget,
+    target_pos: GlobalPosition,
     navmesh: &'a Navmesh,
 }
 
+#[derive(Debug)]
+pub(crate) enum AttackProcessError {
+    Skill(AttackSkillError),
+    /// There is no walkable path to get in range of the target.
+    Unreachable,
+    /// The skill has been used recently and cannot be used again yet.
+    Cooldown,
+    InsufficientMana,
+}
+
+impl AttackProcessError {
+    pub(crate) fn as_protocol(&self) -> PerformActionError {
+        match self {
+            AttackProcessError::Skill(
+                AttackSkillError::NotAWeapon | AttackSkillError::UnknownWeapon | AttackSkillError::WeaponMismatch,
+            ) => PerformActionError::InvalidWeapon,
+            AttackProcessError::Skill(AttackSkillError::SkillNotFound) => PerformActionError::NotLearned,
+            AttackProcessError::Unreachable => PerformActionError::ObstacleInPath,
+            AttackProcessError::Cooldown => PerformActionError::Cooldown,
+            AttackProcessError::InsufficientMana => PerformActionError::InsufficientMP,
+        }
+    }
+}
+
+impl From<AttackSkillError> for AttackProcessError {
+    fn from(value: AttackSkillError) -> Self {
+        AttackProcessError::Skill(value)
+    }
+}
+
 impl AttackProcess<'_> {
-    pub(crate) fn try_attack(&mut self) -> Result<(), AttackSkillError> {
-        let description = ActionDescription(self.skill, states::ActionTarget::Entity(self.target.0));
-        let range = AttackSkill::get_range_for_attack(self.skill, self.weapon.map(|item| item.reference));
-        let range_squared = range.pow(2);
-        if self.position.distance_to(self.target_pos) <= range_squared {
+    fn range(&self) -> f32 {
+        AttackSkill::get_range_for_attack(self.skill, self.caster.weapon.map(|item| item.reference))
+    }
+
+    fn in_range(&self, range: f32) -> bool {
+        self.position.location().0.distance2(self.target_pos.to_location().0) <= range.pow(2)
+    }
+
+    /// Checks if the target can be attacked right away, without having to move closer to it first.
+    pub(crate) fn can_execute(&self) -> bool {
+        self.in_range(self.range())
+            && self
+                .navmesh
+                .has_line_of_sight(self.position.location(), self.target_pos.to_location())
+    }
+
+    pub(crate) fn try_attack(&mut self) -> Result<(), AttackProcessError> {
+        self.caster.check(self.skill)?;
+
+        let description = ActionDescription(self.skill, self.target);
+        let range = self.range();
+        let in_range = self.in_range(range);
+        if self.can_execute() {
             self.state.request_transition(Action::from(description));
+        } else if in_range {
+            // Something is in the way, so we need to walk around it to get a clear view of the target.
+            let goal = MovementGoal::path_to(self.navmesh, self.position.position(), self.target_pos)
+                .ok_or(AttackProcessError::Unreachable)?;
+            self.state.request_transition(Moving(goal));
         } else {
             let new_target_position = self
                 .position
                 .position()
                 .to_location()
-                .point_in_line_with_range(self.target_pos.location(), range);
+                .point_in_line_with_range(self.target_pos.to_location(), range);
             let new_height = self
                 .navmesh
                 .height_for(new_target_position)
-                .unwrap_or(self.target_pos.position().y);
-            self.state
-                .request_transition(Moving(MovementGoal::Location(new_target_position.with_y(new_height))));
+                .unwrap_or(self.target_pos.y);
+            let goal = MovementGoal::path_to(
+                self.navmesh,
+                self.position.position(),
+                new_target_position.with_y(new_height),
+            )
+            .ok_or(AttackProcessError::Unreachable)?;
+            self.state.request_transition(Moving(goal));
         }
 
         Ok(())
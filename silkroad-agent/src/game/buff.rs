@@ -0,0 +1,80 @@
+use crate::agent::states::Dead;
+use crate::agent::Agent;
+use crate::comp::player::Buffed;
+use crate::comp::GameEntity;
+use crate::event::BuffApplyEvent;
+use crate::world::WorldData;
+use bevy_ecs::prelude::*;
+use bevy_ecs::query::Has;
+use bevy_time::Time;
+use silkroad_game_base::MovementSpeed;
+use tracing::debug;
+
+/// Provides the tokens that identify a single applied buff towards the client.
+#[derive(Resource)]
+pub(crate) struct BuffTokenCounter(u32);
+
+impl Default for BuffTokenCounter {
+    fn default() -> Self {
+        BuffTokenCounter(1)
+    }
+}
+
+impl BuffTokenCounter {
+    pub fn next(&mut self) -> u32 {
+        let current = self.0;
+        self.0 = self.0.wrapping_add(1).max(1);
+        current
+    }
+}
+
+pub(crate) fn apply_buffs(
+    mut events: EventReader<BuffApplyEvent>,
+    mut query: Query<&mut Buffed, Without<Dead>>,
+    mut tokens: ResMut<BuffTokenCounter>,
+) {
+    for event in events.iter() {
+        let Ok(mut buffed) = query.get_mut(event.target) else {
+            continue;
+        };
+
+        if !buffed.add(event.skill, tokens.next(), event.duration) {
+            debug!("Buff {} was rejected by a stronger active buff.", event.skill.id);
+        }
+    }
+}
+
+pub(crate) fn tick_buffs(mut query: Query<(&mut Buffed, Has<Dead>)>, time: Res<Time>) {
+    let delta = time.delta();
+    for (mut buffed, dead) in query.iter_mut() {
+        if buffed.buffs.is_empty() {
+            continue;
+        }
+
+        if dead {
+            buffed.clear();
+        } else {
+            // Only mark the buffs as changed if one of them actually expired, as this runs every tick.
+            let active = buffed.buffs.len();
+            buffed.bypass_change_detection().tick(delta);
+            if buffed.buffs.len() != active {
+                buffed.set_changed();
+            }
+        }
+    }
+}
+
+/// Updates the movement speed of entities whose buffs changed, in case they affected it.
+pub(crate) fn update_buffed_speed(mut query: Query<(&GameEntity, &Buffed, &mut Agent), Changed<Buffed>>) {
+    for (game_entity, buffed, mut agent) in query.iter_mut() {
+        let Some(character_data) = WorldData::characters().find_id(game_entity.ref_id) else {
+            continue;
+        };
+
+        let base = Agent::from_character_data(character_data);
+        let multiplier = buffed.effect().speed_multiplier();
+        for speed in [MovementSpeed::Running, MovementSpeed::Walking] {
+            agent.set_speed(speed, base.get_speed_value(speed) * multiplier);
+        }
+    }
+}
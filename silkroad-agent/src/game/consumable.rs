@@ -0,0 +1,237 @@
+use crate::agent::states::Dead;
+use crate::comp::cooldown::ItemCooldowns;
+use crate::comp::inventory::PlayerInventory;
+use crate::comp::net::Client;
+use crate::comp::player::Player;
+use crate::comp::pos::Position;
+use crate::comp::{Health, Mana};
+use crate::config::{GameConfig, ItemCooldownConfig};
+use crate::event::{DamageReceiveEvent, PlayerTeleportEvent};
+use crate::game::stats::ResetStatsEvent;
+use crate::input::PlayerInput;
+use crate::status::component::StatusEffects;
+use bevy_ecs::prelude::*;
+use bevy_ecs::query::{Has, WorldQuery};
+use bevy_time::{Time, Timer, TimerMode};
+use silkroad_data::DataEntry;
+use silkroad_definitions::type_id::{
+    ObjectConsumable, ObjectConsumableItemMall, ObjectConsumableRecovery, ObjectConsumableScroll, ObjectItem,
+    ObjectType,
+};
+use silkroad_game_base::Item;
+use silkroad_protocol::inventory::{InventoryOperationError, UseItemResponse};
+use std::time::Duration;
+
+/// Time it takes for a return scroll to teleport the player, during which moving or getting hit cancels it.
+const RETURN_SCROLL_CAST_TIME: Duration = Duration::from_secs(10);
+
+/// A return scroll that has been used and will teleport the player to the closest town once finished. The scroll is
+/// only consumed, and its use confirmed to the client, once the teleport happens, such that a cancelled cast doesn't
+/// cost the player their scroll.
+#[derive(Component)]
+#[component(storage = "SparseSet")]
+pub(crate) struct ReturnScrollCast {
+    timer: Timer,
+    slot: u8,
+    ref_id: u32,
+    item_type: u16,
+}
+
+#[derive(WorldQuery)]
+#[world_query(mutable)]
+pub(crate) struct ItemUser {
+    entity: Entity,
+    client: &'static Client,
+    player: &'static Player,
+    input: &'static PlayerInput,
+    inventory: &'static mut PlayerInventory,
+    health: &'static mut Health,
+    mana: &'static mut Mana,
+    cooldowns: &'static mut ItemCooldowns,
+    status: &'static mut StatusEffects,
+    casting_return: Has<ReturnScrollCast>,
+}
+
+/// Provides the value of the given parameter of the item. Recovery items contain the amount of HP they heal in the
+/// first parameter and the amount of MP in the third. Cure items contain the abnormal states they cure in the first
+/// parameter, where no state at all means that every state is cured.
+fn param(item: &Item, index: usize) -> u32 {
+    item.reference.params[index].max(0) as u32
+}
+
+fn cooldown_of(consumable: ObjectConsumable, cooldowns: &ItemCooldownConfig) -> Option<Duration> {
+    match consumable {
+        ObjectConsumable::Recovery(_) => Some(Duration::from_millis(cooldowns.potion)),
+        ObjectConsumable::Cure(_) => Some(Duration::from_millis(cooldowns.cure)),
+        _ => None,
+    }
+}
+
+pub(crate) fn use_consumable(
+    mut query: Query<ItemUser, Without<Dead>>,
+    mut reset_events: EventWriter<ResetStatsEvent>,
+    config: Res<GameConfig>,
+    mut cmd: Commands,
+) {
+    for mut user in query.iter_mut() {
+        let Some(ref request) = user.input.use_item else {
+            continue;
+        };
+
+        let Some(item) = user.inventory.get_item_at(request.slot) else {
+            user.client
+                .send(UseItemResponse::Error(InventoryOperationError::InvalidTarget));
+            continue;
+        };
+
+        let Some(ObjectType::Item(ObjectItem::Consumable(consumable))) =
+            ObjectType::from_type_id(&item.reference.common.type_id)
+        else {
+            user.client
+                .send(UseItemResponse::Error(InventoryOperationError::Unusable));
+            continue;
+        };
+
+        let group = consumable.type_value();
+        if user.cooldowns.is_active(group) {
+            user.client.send(UseItemResponse::Error(InventoryOperationError::Busy));
+            continue;
+        }
+
+        // Potions would be wasted without anything to restore, so they aren't used up in that case.
+        let nothing_to_restore = match consumable {
+            ObjectConsumable::Recovery(ObjectConsumableRecovery::HP) => user.health.is_full(),
+            ObjectConsumable::Recovery(ObjectConsumableRecovery::MP) => user.mana.is_full(),
+            ObjectConsumable::Recovery(ObjectConsumableRecovery::Vigor) => user.health.is_full() && user.mana.is_full(),
+            _ => false,
+        };
+        if nothing_to_restore {
+            user.client
+                .send(UseItemResponse::Error(InventoryOperationError::InvalidTarget));
+            continue;
+        }
+
+        match consumable {
+            ObjectConsumable::Recovery(ObjectConsumableRecovery::HP) => {
+                let amount = param(item, 0);
+                user.health.regenerate(amount);
+            },
+            ObjectConsumable::Recovery(ObjectConsumableRecovery::MP) => {
+                let amount = param(item, 2);
+                user.mana.regenerate(amount);
+            },
+            ObjectConsumable::Recovery(ObjectConsumableRecovery::Vigor) => {
+                let (hp, mp) = (param(item, 0), param(item, 2));
+                user.health.regenerate(hp);
+                user.mana.regenerate(mp);
+            },
+            ObjectConsumable::Cure(_) => {
+                let cured = match param(item, 0) {
+                    0 => u32::MAX,
+                    states => states,
+                };
+                user.status.cure(cured);
+            },
+            ObjectConsumable::Scroll(ObjectConsumableScroll::Return) => {
+                if user.casting_return {
+                    user.client.send(UseItemResponse::Error(InventoryOperationError::Busy));
+                    continue;
+                }
+                cmd.entity(user.entity).insert(ReturnScrollCast {
+                    timer: Timer::new(RETURN_SCROLL_CAST_TIME, TimerMode::Once),
+                    slot: request.slot,
+                    ref_id: item.reference.ref_id(),
+                    item_type: request.item_type,
+                });
+                continue;
+            },
+            ObjectConsumable::ItemMall(ObjectConsumableItemMall::StatPointsReset) => {
+                if user.player.character.stats.allocated() == 0 {
+                    user.client
+                        .send(UseItemResponse::Error(InventoryOperationError::InvalidTarget));
+                    continue;
+                }
+                reset_events.send(ResetStatsEvent(user.entity));
+            },
+            _ => {
+                user.client
+                    .send(UseItemResponse::Error(InventoryOperationError::Unusable));
+                continue;
+            },
+        }
+
+        if let Some(cooldown) = cooldown_of(consumable, &config.item_cooldowns) {
+            user.cooldowns.start(group, item.reference.ref_id(), cooldown);
+        }
+
+        let remaining = user.inventory.consume_at(request.slot).unwrap_or(0);
+        user.client.send(UseItemResponse::Success {
+            slot: request.slot,
+            remaining,
+            item_type: request.item_type,
+        });
+    }
+}
+
+pub(crate) fn tick_item_cooldowns(mut query: Query<&mut ItemCooldowns>, time: Res<Time>) {
+    let delta = time.delta();
+    for mut cooldowns in query.iter_mut().filter(|cooldowns| !cooldowns.is_empty()) {
+        cooldowns.tick(delta);
+    }
+}
+
+pub(crate) fn cast_return_scroll(
+    mut query: Query<(
+        Entity,
+        &Client,
+        &mut ReturnScrollCast,
+        &mut PlayerInventory,
+        &PlayerInput,
+        &Position,
+    )>,
+    mut teleports: EventWriter<PlayerTeleportEvent>,
+    config: Res<GameConfig>,
+    time: Res<Time>,
+    mut cmd: Commands,
+) {
+    let delta = time.delta();
+    for (entity, client, mut cast, mut inventory, input, position) in query.iter_mut() {
+        if input.movement.is_some() {
+            client.send(UseItemResponse::Error(InventoryOperationError::Busy));
+            cmd.entity(entity).remove::<ReturnScrollCast>();
+            continue;
+        }
+
+        if cast.timer.tick(delta).finished() {
+            // The scroll may have been moved or gotten rid of in the meantime, in which case there's nothing to use up.
+            let has_scroll = inventory
+                .get_item_at(cast.slot)
+                .is_some_and(|item| item.reference.ref_id() == cast.ref_id);
+            if let Some(return_point) = config.closest_return_point(position.location()).filter(|_| has_scroll) {
+                let remaining = inventory.consume_at(cast.slot).unwrap_or(0);
+                client.send(UseItemResponse::Success {
+                    slot: cast.slot,
+                    remaining,
+                    item_type: cast.item_type,
+                });
+                teleports.send(PlayerTeleportEvent(entity, return_point));
+            } else {
+                client.send(UseItemResponse::Error(InventoryOperationError::InvalidTarget));
+            }
+            cmd.entity(entity).remove::<ReturnScrollCast>();
+        }
+    }
+}
+
+pub(crate) fn interrupt_return_scroll(
+    mut damage_events: EventReader<DamageReceiveEvent>,
+    query: Query<&Client, With<ReturnScrollCast>>,
+    mut cmd: Commands,
+) {
+    for event in damage_events.iter() {
+        if let Ok(client) = query.get(event.target.0) {
+            client.send(UseItemResponse::Error(InventoryOperationError::Busy));
+            cmd.entity(event.target.0).remove::<ReturnScrollCast>();
+        }
+    }
+}
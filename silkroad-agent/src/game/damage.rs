@@ -1,17 +1,132 @@
 use crate::agent::states::{Dead, StateTransitionQueue};
 use crate::comp::damage::{DamageReceiver, Invincible};
+use crate::comp::exp::Leveled;
+use crate::comp::inventory::PlayerInventory;
 use crate::comp::monster::Monster;
 use crate::comp::net::Client;
-use crate::comp::player::Player;
-use crate::comp::{GameEntity, Health};
+use crate::comp::player::{Buffed, Player};
+use crate::comp::{EntityReference, GameEntity, Health};
 use crate::event::{DamageReceiveEvent, EntityDeath};
 use crate::game::mind::Mind;
+use crate::game::stats::calculate_stats;
+use crate::world::WorldData;
 use bevy_ecs::prelude::*;
+use bevy_ecs::query::WorldQuery;
+use silkroad_game_base::{DamageOutcome, Defense, Offense};
 use silkroad_protocol::combat::{
     ActionType, DamageContent, DamageKind, DamageValue, PerEntityDamage, PerformActionError, PerformActionUpdate,
     SkillPartDamage,
 };
 
+/// The components that make up how well an entity can attack or defend itself in combat.
+#[derive(WorldQuery)]
+pub(crate) struct CombatStats {
+    pub(crate) entity: &'static GameEntity,
+    player: Option<&'static Player>,
+    level: Option<&'static Leveled>,
+    inventory: Option<&'static PlayerInventory>,
+    buffs: Option<&'static Buffed>,
+}
+
+impl CombatStatsItem<'_> {
+    fn level(&self, player: &Player) -> u8 {
+        self.level
+            .map(|level| level.current_level())
+            .unwrap_or(player.character.level)
+    }
+
+    /// The level of the entity, which is either the level of the player or the level of the monster.
+    pub(crate) fn entity_level(&self) -> u8 {
+        match self.player {
+            Some(player) => self.level(player),
+            None => WorldData::characters()
+                .find_id(self.entity.ref_id)
+                .map(|character| character.level)
+                .unwrap_or(1),
+        }
+    }
+
+    pub(crate) fn offense(&self) -> Offense {
+        if let (Some(player), Some(inventory), Some(buffs)) = (self.player, self.inventory, self.buffs) {
+            let level = self.level(player);
+            calculate_stats(player, inventory, buffs, level).offense(level)
+        } else {
+            WorldData::characters()
+                .find_id(self.entity.ref_id)
+                .map(Offense::for_monster)
+                .unwrap_or_default()
+        }
+    }
+
+    pub(crate) fn defense(&self) -> Defense {
+        if let (Some(player), Some(inventory), Some(buffs)) = (self.player, self.inventory, self.buffs) {
+            let level = self.level(player);
+            calculate_stats(player, inventory, buffs, level).defense(level)
+        } else {
+            WorldData::characters()
+                .find_id(self.entity.ref_id)
+                .map(Defense::for_monster)
+                .unwrap_or_default()
+        }
+    }
+}
+
+/// The damage all entities hit by a single attack received, which is reported as one update once all damage has
+/// been applied. Skills hitting multiple times produce one damage part per hit for each entity, all of which are
+/// reported with the first hit.
+struct AttackResult {
+    instance: u32,
+    skill: u32,
+    source: EntityReference,
+    target: EntityReference,
+    hit: Vec<Entity>,
+    damage: Vec<PerEntityDamage>,
+}
+
+impl AttackResult {
+    fn hit(&mut self, target: EntityReference, damage: SkillPartDamage) {
+        match self
+            .damage
+            .iter_mut()
+            .find(|entity| entity.target == target.1.unique_id)
+        {
+            Some(entity) => entity.damage.push(damage),
+            None => {
+                self.hit.push(target.0);
+                self.damage.push(PerEntityDamage {
+                    target: target.1.unique_id,
+                    damage: vec![damage],
+                });
+            },
+        }
+    }
+
+    fn damage_instances(&self) -> u8 {
+        self.damage
+            .iter()
+            .map(|entity| entity.damage.len())
+            .max()
+            .unwrap_or(1)
+            .try_into()
+            .unwrap_or(u8::MAX)
+    }
+}
+
+fn damage_part(outcome: &DamageOutcome, amount: u32, killed: bool) -> SkillPartDamage {
+    let kind = match outcome {
+        DamageOutcome::Critical(_) => DamageKind::Critical,
+        _ => DamageKind::Standard,
+    };
+    if killed {
+        SkillPartDamage::KillingBlow(DamageValue::new(kind, amount))
+    } else if outcome.is_avoided() {
+        // We don't know of a separate way to show a miss to the client, so it is shown just like a block.
+        SkillPartDamage::Blocked
+    } else {
+        SkillPartDamage::Default(DamageValue::new(kind, amount))
+    }
+}
+
 pub(crate) fn handle_damage(
     mut reader: EventReader<DamageReceiveEvent>,
     mut receiver_query: Query<(
@@ -19,72 +134,74 @@ pub(crate) fn handle_damage(
         &mut StateTransitionQueue,
         &mut DamageReceiver,
         Option<&Player>,
-        Option<&Client>,
         Option<&Invincible>,
     )>,
-    sender_query: Query<(&GameEntity, Option<&Client>)>,
+    sender_query: Query<&Client>,
     mut entity_died: EventWriter<EntityDeath>,
 ) {
+    let mut attacks: Vec<AttackResult> = Vec::new();
     for damage_event in reader.iter() {
-        let Ok((mut health, mut controller, mut receiver, player, maybe_client, invincible)) =
+        let Ok((mut health, mut controller, mut receiver, player, invincible)) =
             receiver_query.get_mut(damage_event.target.0)
         else {
             continue;
         };
 
-        let (attacker, attacker_client) = sender_query
-            .get(damage_event.source.0)
-            .expect("Sender for damage event should exist");
+        // The attacker may already be gone when damage is dealt over time.
+        let attacker_client = sender_query.get(damage_event.source.0).ok();
 
         if health.is_dead() {
+            // A target killed by an earlier hit of the same attack has already been reported as killed, so only a new
+            // attack on a dead entity is answered with an error.
             // TODO: this might be wrong
-            if let Some(client) = attacker_client {
+            if let (Some(client), Some(_), true) = (
+                attacker_client,
+                damage_event.attack.instance,
+                damage_event.attack.first_hit,
+            ) {
                 client.send(PerformActionUpdate::Error(PerformActionError::Completed))
             }
             continue;
         }
 
-        let amount = if invincible.is_some() { damage_event.amount } else { 0 };
+        let amount_of = |outcome: &DamageOutcome| if invincible.is_some() { 0 } else { outcome.amount() };
+        let amount = amount_of(&damage_event.damage);
 
-        receiver.record_damage(attacker.unique_id, amount as u64);
+        receiver.record_damage(damage_event.source.1.unique_id, amount as u64);
         health.reduce(amount);
-        let damage_data = if health.is_dead() {
-            SkillPartDamage::KillingBlow(DamageValue::new(DamageKind::Standard, amount))
-        } else {
-            SkillPartDamage::Default(DamageValue::new(DamageKind::Standard, amount))
-        };
-        if let Some(client) = attacker_client {
-            client.send(PerformActionUpdate::success(
-                damage_event.attack.skill.ref_id,
-                damage_event.source.1.unique_id,
-                damage_event.target.1.unique_id,
-                damage_event.attack.instance,
-                ActionType::Attack {
-                    damage: Some(DamageContent {
-                        damage_instances: 1,
-                        entities: vec![PerEntityDamage {
-                            target: damage_event.target.1.unique_id,
-                            damage: vec![damage_data],
-                        }],
-                    }),
+        // Damage over time is not part of an attack and is only visible through the health of the target. Later hits
+        // of an attack are only applied, as they have already been reported together with the first hit.
+        if let (Some(instance), true) = (damage_event.attack.instance, damage_event.attack.first_hit) {
+            let attack = match attacks.iter().position(|attack| attack.instance == instance) {
+                Some(index) => &mut attacks[index],
+                None => {
+                    attacks.push(AttackResult {
+                        instance,
+                        skill: damage_event.attack.skill.ref_id,
+                        source: damage_event.source,
+                        target: damage_event.target,
+                        hit: Vec::new(),
+                        damage: Vec::new(),
+                    });
+                    attacks.last_mut().expect("Attack should have just been added")
                 },
-            ));
-        } else if let Some(client) = maybe_client {
-            client.send(PerformActionUpdate::success(
-                damage_event.attack.skill.ref_id,
-                damage_event.source.1.unique_id,
-                damage_event.target.1.unique_id,
-                damage_event.attack.instance,
-                ActionType::Attack {
-                    damage: Some(DamageContent {
-                        damage_instances: 1,
-                        entities: vec![PerEntityDamage {
-                            target: damage_event.target.1.unique_id,
-                            damage: vec![damage_data],
-                        }],
-                    }),
-                },
-            ));
+            };
+            attack.hit(
+                damage_event.target,
+                damage_part(&damage_event.damage, amount, health.is_dead()),
+            );
+
+            // The later hits are only dealt over the execution of the skill, so which of them kills the target is
+            // based on its current health.
+            let mut health_left = health.current_health;
+            for outcome in damage_event.later_hits.iter() {
+                if health_left == 0 {
+                    break;
+                }
+                let amount = amount_of(outcome);
+                health_left = health_left.saturating_sub(amount);
+                attack.hit(damage_event.target, damage_part(outcome, amount, health_left == 0));
+            }
         }
 
         if health.is_dead() {
@@ -100,6 +217,29 @@ pub(crate) fn handle_damage(
             controller.request_transition(dead_state);
         }
     }
+
+    for attack in attacks {
+        let damage_instances = attack.damage_instances();
+        let update = PerformActionUpdate::success(
+            attack.skill,
+            attack.source.1.unique_id,
+            attack.target.1.unique_id,
+            attack.instance,
+            ActionType::Attack {
+                damage: Some(DamageContent {
+                    damage_instances,
+                    entities: attack.damage,
+                }),
+            },
+        );
+        if let Ok(client) = sender_query.get(attack.source.0) {
+            client.send(update);
+        } else {
+            for client in attack.hit.iter().filter_map(|target| sender_query.get(*target).ok()) {
+                client.send(update.clone());
+            }
+        }
+    }
 }
 
 pub(crate) fn attack_player(mut query: Query<&mut Mind, With<Monster>>, mut events: EventReader<DamageReceiveEvent>) {
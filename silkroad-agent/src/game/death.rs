@@ -0,0 +1,63 @@
+use crate::agent::states::Dead;
+use crate::comp::exp::{Experienced, Leveled};
+use crate::comp::net::Client;
+use crate::comp::player::Player;
+use crate::comp::pos::Position;
+use crate::comp::{Health, Mana};
+use crate::config::GameConfig;
+use crate::event::{EntityDeath, PlayerTeleportEvent};
+use crate::game::mind::Mind;
+use crate::input::PlayerInput;
+use crate::world::WorldData;
+use bevy_ecs::prelude::*;
+use silkroad_protocol::world::{CharacterDied, DeathKind, ResurrectKind};
+use tracing::debug;
+
+pub(crate) fn handle_player_death(
+    mut deaths: EventReader<EntityDeath>,
+    mut query: Query<(&Client, &Player, &Leveled, &mut Experienced, &mut Mind)>,
+    config: Res<GameConfig>,
+) {
+    for death in deaths.iter() {
+        let Ok((client, player, level, mut experienced, mut mind)) = query.get_mut(death.died.0) else {
+            continue;
+        };
+
+        mind.cancel();
+
+        // Characters still carrying the beginner mark are protected from losing experience.
+        if !player.character.beginner_mark {
+            let required = WorldData::levels()
+                .get_exp_for_level(level.current_level())
+                .unwrap_or(0);
+            let penalty = (required as f32 * config.death_exp_penalty) as u64;
+            let lost = experienced.lose(penalty);
+            debug!(player = ?player.character.name, "Lost {} experience due to dying.", lost);
+        }
+
+        client.send(CharacterDied(DeathKind::Killed));
+    }
+}
+
+pub(crate) fn resurrect(
+    mut query: Query<(Entity, &PlayerInput, &Position, &mut Health, &mut Mana), With<Dead>>,
+    mut teleports: EventWriter<PlayerTeleportEvent>,
+    config: Res<GameConfig>,
+    mut cmd: Commands,
+) {
+    for (entity, input, position, mut health, mut mana) in query.iter_mut() {
+        let Some(kind) = input.resurrect else {
+            continue;
+        };
+
+        if let ResurrectKind::ReturnPoint = kind {
+            if let Some(return_point) = config.closest_return_point(position.location()) {
+                teleports.send(PlayerTeleportEvent(entity, return_point));
+            }
+        }
+
+        health.refill();
+        mana.refill();
+        cmd.entity(entity).remove::<Dead>();
+    }
+}
@@ -1,9 +1,10 @@
-use crate::comp::drop::{Drop, DropBundle};
+use crate::comp::drop::{Drop, DropBundle, DropOrigin};
 use crate::comp::pos::Position;
 use crate::comp::{Despawn, EntityReference, GameEntity};
+use crate::config::GameConfig;
 use crate::ext::{EntityIdPool, Navmesh};
 use bevy_ecs::prelude::*;
-use bevy_time::Time;
+use bevy_time::{Time, Timer, TimerMode};
 use derive_more::Constructor;
 use rand::Rng;
 use silkroad_data::DataEntry;
@@ -14,6 +15,7 @@ pub(crate) struct SpawnDrop {
     pub item: Item,
     pub relative_position: GlobalLocation,
     pub owner: Option<EntityReference>,
+    pub source: DropOrigin,
 }
 
 pub(crate) fn tick_drop(mut cmd: Commands, time: Res<Time>, mut drops: Query<(Entity, &mut Despawn)>) {
@@ -25,10 +27,23 @@ pub(crate) fn tick_drop(mut cmd: Commands, time: Res<Time>, mut drops: Query<(En
     }
 }
 
+pub(crate) fn tick_drop_protection(time: Res<Time>, mut drops: Query<&mut Drop>) {
+    for mut drop in drops.iter_mut() {
+        let Some(protection) = drop.protection.as_mut() else {
+            continue;
+        };
+        if protection.tick(time.delta()).finished() {
+            drop.protection = None;
+            drop.owner = None;
+        }
+    }
+}
+
 pub(crate) fn create_drops(
     mut reader: EventReader<SpawnDrop>,
     navmesh: Res<Navmesh>,
     mut id_gen: ResMut<EntityIdPool>,
+    config: Res<GameConfig>,
     mut cmd: Commands,
 ) {
     for spawn in reader.iter() {
@@ -40,6 +55,10 @@ pub(crate) fn create_drops(
             drop: Drop {
                 owner: spawn.owner,
                 item: spawn.item,
+                source: spawn.source,
+                protection: spawn
+                    .owner
+                    .map(|_| Timer::from_seconds(config.drop_protection as f32, TimerMode::Once)),
             },
             position: Position::new(pos, Heading(rotation)),
             game_entity: GameEntity {
@@ -1,11 +1,16 @@
 use crate::comp::damage::DamageReceiver;
 use crate::comp::exp::{Experienced, Leveled, SP};
+use crate::comp::monster::Monster;
 use crate::comp::player::Player;
 use crate::comp::pos::Position;
 use crate::comp::{EntityReference, GameEntity, Health, Mana};
+use crate::config::GameConfig;
 use crate::event::EntityDeath;
+use crate::party::component::{Parties, PartyMembership};
 use crate::world::{EntityLookup, WorldData};
 use bevy_ecs::prelude::*;
+use silkroad_game_base::ExperienceSource;
+use std::collections::HashMap;
 use tracing::warn;
 
 const EXP_RECEIVE_RANGE_SQUARED: f32 = 1000.0 * 1000.0;
@@ -18,34 +23,109 @@ pub struct ReceiveExperienceEvent {
     pub sp: u64,
 }
 
+/// Experience gained by the members of an exp-sharing party, which is split up among all members close to the kill.
+#[derive(Default)]
+struct PartyPool {
+    exp: u64,
+    sp: u64,
+}
+
 pub(crate) fn distribute_experience(
     mut death_events: EventReader<EntityDeath>,
     mut experience_writer: EventWriter<ReceiveExperienceEvent>,
-    dead_query: Query<(&DamageReceiver, &Position)>,
+    dead_query: Query<(&DamageReceiver, &Position, &Monster)>,
     lookup: Res<EntityLookup>,
-    receiver_query: Query<(&GameEntity, &Position)>,
+    receiver_query: Query<(&GameEntity, &Position, &Leveled, Option<&PartyMembership>)>,
+    parties: Res<Parties>,
+    config: Res<GameConfig>,
 ) {
     for event in death_events.iter() {
-        let Ok((damage_distribution, death_location)) = dead_query.get(event.died.0) else {
+        let Ok((damage_distribution, death_location, monster)) = dead_query.get(event.died.0) else {
             continue;
         };
 
+        let Some(character_data) = WorldData::characters().find_id(event.died.1.ref_id) else {
+            continue;
+        };
+        let source = ExperienceSource::new(character_data, monster.rarity);
+        let total_damage = damage_distribution.total_damage();
+        if total_damage == 0 {
+            continue;
+        }
+
+        let mut pools: HashMap<u32, PartyPool> = HashMap::new();
         for attacker_id in damage_distribution.all_attackers() {
-            if let Some(((game_entity, position), target_entity)) = lookup
+            if let Some(((game_entity, position, level, membership), target_entity)) = lookup
                 .get_entity_for_id(attacker_id)
                 .and_then(|entity| receiver_query.get(entity).ok().zip(Some(entity)))
             {
                 if death_location.distance_to(position) <= EXP_RECEIVE_RANGE_SQUARED {
+                    let share = damage_distribution.total_damage_of(attacker_id) as f32 / total_damage as f32;
+                    let gain = source.experience_for(level.current_level(), share);
+                    let exp = (gain.exp as f32 * config.rates.exp) as u64;
+                    let sp = (gain.sp as f32 * config.rates.sp) as u64;
+                    if exp == 0 && sp == 0 {
+                        continue;
+                    }
+
+                    let sharing_party = membership.filter(|membership| {
+                        parties
+                            .get(membership.0)
+                            .is_some_and(|party| party.settings.shares_exp())
+                    });
+                    if let Some(membership) = sharing_party {
+                        let pool = pools.entry(membership.0).or_default();
+                        pool.exp += exp;
+                        pool.sp += sp;
+                        continue;
+                    }
+
                     let event = ReceiveExperienceEvent {
                         source: Some(event.died),
                         target: EntityReference(target_entity, *game_entity),
-                        exp: 100,
-                        sp: 100,
+                        exp,
+                        sp,
                     };
                     experience_writer.send(event);
                 }
             }
         }
+
+        for (party_id, pool) in pools {
+            let Some(party) = parties.get(party_id) else {
+                continue;
+            };
+
+            let receivers = party
+                .members
+                .iter()
+                .filter_map(|member| receiver_query.get(member.entity).ok().zip(Some(member.entity)))
+                .filter(|((_, position, _, _), _)| death_location.distance_to(position) <= EXP_RECEIVE_RANGE_SQUARED)
+                .collect::<Vec<_>>();
+            let total_levels: u64 = receivers
+                .iter()
+                .map(|((_, _, level, _), _)| level.current_level() as u64)
+                .sum();
+            if total_levels == 0 {
+                continue;
+            }
+
+            for ((game_entity, _, level, _), entity) in receivers {
+                let weight = level.current_level() as u64;
+                let exp = pool.exp * weight / total_levels;
+                let sp = pool.sp * weight / total_levels;
+                if exp == 0 && sp == 0 {
+                    continue;
+                }
+
+                experience_writer.send(ReceiveExperienceEvent {
+                    source: Some(event.died),
+                    target: EntityReference(entity, *game_entity),
+                    exp,
+                    sp,
+                });
+            }
+        }
     }
 }
 
@@ -1,3 +1,4 @@
+use crate::comp::drop::DropOrigin;
 use crate::comp::monster::Monster;
 use crate::comp::pos::Position;
 use crate::comp::GameEntity;
@@ -48,6 +49,7 @@ pub(crate) fn drop_gold(
                 },
                 relative_position: pos.location(),
                 owner: event.killer,
+                source: DropOrigin::Monster(game_entity.unique_id),
             });
         }
     }
@@ -1,14 +1,21 @@
+use crate::comp::drop::DropOrigin;
+use crate::comp::exp::Leveled;
 use crate::comp::inventory::PlayerInventory;
 use crate::comp::net::Client;
-use crate::comp::player::Player;
+use crate::comp::player::{Buffed, Player};
 use crate::comp::pos::Position;
+use crate::comp::GameEntity;
 use crate::game::drop::SpawnDrop;
 use crate::game::gold::get_gold_ref_id;
+use crate::game::shop::{create_shop_item, Shops};
+use crate::game::stats::stats_message;
 use crate::input::PlayerInput;
+use crate::world::WorldData;
 use bevy_ecs::prelude::*;
+use bevy_ecs::query::WorldQuery;
 use silkroad_definitions::type_id::{
     ObjectClothingPart, ObjectClothingType, ObjectConsumable, ObjectConsumableAmmo, ObjectEquippable, ObjectItem,
-    ObjectJewelryType, ObjectRace, ObjectType, ObjectWeaponType,
+    ObjectJewelryType, ObjectType, ObjectWeaponType,
 };
 use silkroad_game_base::{Inventory, Item, ItemTypeData, MoveError, Race};
 use silkroad_protocol::inventory::{
@@ -17,11 +24,35 @@ use silkroad_protocol::inventory::{
 use silkroad_protocol::world::CharacterPointsUpdate;
 use std::cmp::max;
 
+#[derive(WorldQuery)]
+#[world_query(mutable)]
+pub(crate) struct InventoryHolder {
+    client: &'static Client,
+    game_entity: &'static GameEntity,
+    player: &'static Player,
+    input: &'static PlayerInput,
+    inventory: &'static mut PlayerInventory,
+    position: &'static Position,
+    level: &'static Leveled,
+    buffs: &'static Buffed,
+}
+
 pub(crate) fn handle_inventory_input(
-    mut query: Query<(&Client, &Player, &PlayerInput, &mut PlayerInventory, &Position)>,
+    mut query: Query<InventoryHolder>,
     mut item_spawn: EventWriter<SpawnDrop>,
+    shops: Shops,
 ) {
-    for (client, player, input, mut inventory, position) in query.iter_mut() {
+    for InventoryHolderItem {
+        client,
+        game_entity,
+        player,
+        input,
+        mut inventory,
+        position,
+        level,
+        buffs,
+    } in query.iter_mut()
+    {
         if let Some(ref action) = input.inventory {
             match action.data {
                 InventoryOperationRequest::DropGold { amount } => {
@@ -45,6 +76,7 @@ pub(crate) fn handle_inventory_input(
                         },
                         position.location(),
                         None,
+                        DropOrigin::Player(game_entity.unique_id),
                     ));
 
                     client.send(InventoryOperationResult::Success(
@@ -85,6 +117,9 @@ pub(crate) fn handle_inventory_input(
                                 client.send(InventoryOperationResult::Success(
                                     InventoryOperationResponseData::move_item(source, target, amount_moved),
                                 ));
+                                if Inventory::is_equipment_slot(source) || Inventory::is_equipment_slot(target) {
+                                    client.send(stats_message(player, &inventory, buffs, level.current_level()));
+                                }
                             },
                         }
                     } else {
@@ -92,6 +127,125 @@ pub(crate) fn handle_inventory_input(
                     }
                 },
                 InventoryOperationRequest::DropItem { .. } => {},
+                // Offers only change the exchange, which is handled by the exchange itself.
+                InventoryOperationRequest::OfferExchangeItem { .. }
+                | InventoryOperationRequest::RetractExchangeItem { .. }
+                | InventoryOperationRequest::OfferExchangeGold { .. } => {},
+                InventoryOperationRequest::BuyItem { tab, slot, amount, npc } => {
+                    let Some(good) = shops
+                        .shop_near(npc, position)
+                        .and_then(|tabs| tabs.get(tab as usize))
+                        .and_then(|tab| tab.good_at(slot))
+                    else {
+                        client.send(InventoryOperationResult::Error(InventoryOperationError::InvalidTarget));
+                        continue;
+                    };
+                    let Some(reference) = WorldData::items().find_code(&good.item) else {
+                        client.send(InventoryOperationResult::Error(InventoryOperationError::InvalidTarget));
+                        continue;
+                    };
+
+                    let amount = amount.clamp(1, max(1, reference.max_stack_size));
+                    let price = reference.price * amount as u64;
+                    if price > inventory.gold {
+                        client.send(InventoryOperationResult::Error(InventoryOperationError::NotEnoughGold));
+                        continue;
+                    }
+
+                    if !inventory.has_free_slot() {
+                        client.send(InventoryOperationResult::Error(InventoryOperationError::InventoryFull));
+                        continue;
+                    }
+
+                    let Some(target) = inventory.add_item(create_shop_item(reference, good, amount)) else {
+                        client.send(InventoryOperationResult::Error(InventoryOperationError::InventoryFull));
+                        continue;
+                    };
+
+                    inventory.gold -= price;
+                    client.send(InventoryOperationResult::Success(
+                        InventoryOperationResponseData::BuyItem {
+                            tab,
+                            slot,
+                            target_slots: vec![target],
+                            amount,
+                        },
+                    ));
+                    client.send(CharacterPointsUpdate::Gold {
+                        amount: inventory.gold,
+                        display: false,
+                    });
+                },
+                InventoryOperationRequest::SellItem { slot, amount, npc } => {
+                    if shops.shop_near(npc, position).is_none() || Inventory::is_equipment_slot(slot) {
+                        client.send(InventoryOperationResult::Error(InventoryOperationError::InvalidTarget));
+                        continue;
+                    }
+
+                    let Ok(item) = inventory.take_from(slot, amount) else {
+                        client.send(InventoryOperationResult::Error(InventoryOperationError::InvalidTarget));
+                        continue;
+                    };
+
+                    let sold = item.stack_size();
+                    inventory.gold += item.reference.sell_price * sold as u64;
+                    let buyback_slot = inventory.add_buyback(item);
+                    client.send(InventoryOperationResult::Success(
+                        InventoryOperationResponseData::SellItem {
+                            slot,
+                            amount: sold,
+                            npc,
+                            buyback_slot,
+                        },
+                    ));
+                    client.send(CharacterPointsUpdate::Gold {
+                        amount: inventory.gold,
+                        display: false,
+                    });
+                },
+                InventoryOperationRequest::BuybackItem { npc, slot, .. } => {
+                    if shops.shop_near(npc, position).is_none() {
+                        client.send(InventoryOperationResult::Error(InventoryOperationError::InvalidTarget));
+                        continue;
+                    }
+
+                    let Some(item) = inventory.buyback_item(slot) else {
+                        client.send(InventoryOperationResult::Error(InventoryOperationError::InvalidTarget));
+                        continue;
+                    };
+
+                    // Items can only be bought back as a whole, for the same price they were sold for.
+                    let amount = item.stack_size();
+                    let price = item.reference.sell_price * amount as u64;
+                    if price > inventory.gold {
+                        client.send(InventoryOperationResult::Error(InventoryOperationError::NotEnoughGold));
+                        continue;
+                    }
+
+                    if !inventory.has_free_slot() {
+                        client.send(InventoryOperationResult::Error(InventoryOperationError::InventoryFull));
+                        continue;
+                    }
+
+                    let item = inventory
+                        .take_buyback(slot)
+                        .expect("Buyback item should still exist just after checking");
+                    let target_slot = inventory
+                        .add_item(item)
+                        .expect("Item should fit into the inventory with a free slot");
+                    inventory.gold -= price;
+                    client.send(InventoryOperationResult::Success(
+                        InventoryOperationResponseData::BuybackItem {
+                            target_slot,
+                            buyback_slot: slot,
+                            amount,
+                        },
+                    ));
+                    client.send(CharacterPointsUpdate::Gold {
+                        amount: inventory.gold,
+                        display: false,
+                    });
+                },
             }
         }
     }
@@ -112,10 +266,7 @@ fn does_object_type_match_race(user_race: Race, obj_type: ObjectType) -> bool {
                     }
                 },
                 ObjectEquippable::Shield(race) | ObjectEquippable::Jewelry(race, _) => {
-                    return match race {
-                        ObjectRace::Chinese => user_race == Race::Chinese,
-                        ObjectRace::European => user_race == Race::European,
-                    }
+                    return Race::from(race) == user_race;
                 },
                 ObjectEquippable::Weapon(kind) => {
                     return match kind {
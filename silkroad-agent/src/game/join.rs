@@ -1,31 +1,62 @@
+use crate::comp::cooldown::{ItemCooldowns, SkillCooldowns};
+use crate::comp::exp::Leveled;
+use crate::comp::inventory::PlayerInventory;
 use crate::comp::net::Client;
-use crate::comp::player::Player;
+use crate::comp::player::{Buffed, Player};
 use crate::comp::GameEntity;
 use crate::config::GameConfig;
 use crate::event::LoadingFinishedEvent;
+use crate::friend::component::FriendList;
 use crate::game::daylight::DaylightCycle;
+use crate::game::stats::stats_message;
+use crate::world::EntityLookup;
 use bevy_ecs::prelude::*;
-use silkroad_game_base::{Character, SpawningState};
-use silkroad_protocol::character::CharacterStatsMessage;
+use bevy_ecs::query::WorldQuery;
+use silkroad_game_base::SpawningState;
 use silkroad_protocol::chat::{ChatSource, ChatUpdate, TextCharacterInitialization};
-use silkroad_protocol::world::{CelestialUpdate, CharacterFinished, FriendListGroup, FriendListInfo};
+use silkroad_protocol::world::{CelestialUpdate, CharacterFinished};
 use tracing::debug;
 
+#[derive(WorldQuery)]
+#[world_query(mutable)]
+pub(crate) struct JoiningPlayer {
+    client: &'static Client,
+    game_entity: &'static GameEntity,
+    player: &'static mut Player,
+    inventory: &'static PlayerInventory,
+    buffs: &'static Buffed,
+    level: &'static Leveled,
+    friends: &'static FriendList,
+    item_cooldowns: &'static ItemCooldowns,
+    skill_cooldowns: &'static SkillCooldowns,
+}
+
 pub(crate) fn load_finished(
     mut reader: EventReader<LoadingFinishedEvent>,
     settings: Res<GameConfig>,
     daycycle: Res<DaylightCycle>,
-    mut query: Query<(&Client, &GameEntity, &mut Player)>,
+    lookup: Res<EntityLookup>,
+    mut query: Query<JoiningPlayer>,
 ) {
     for event in reader.iter() {
-        let (client, game_entity, mut player) = match query.get_mut(event.0) {
-            Ok(data) => data,
-            _ => continue,
+        let Ok(JoiningPlayerItem {
+            client,
+            game_entity,
+            mut player,
+            inventory,
+            buffs,
+            level,
+            friends,
+            item_cooldowns,
+            skill_cooldowns,
+        }) = query.get_mut(event.0)
+        else {
+            continue;
         };
 
         debug!(id = ?client.0.id(), "Finished loading.");
         player.character.state = SpawningState::Finished;
-        send_character_stats(client, &player.character);
+        client.send(stats_message(&player, inventory, buffs, level.current_level()));
         send_text_initialization(client);
         let (hour, minute) = daycycle.time();
         client.send(CelestialUpdate {
@@ -34,11 +65,11 @@ pub(crate) fn load_finished(
             hour,
             minute,
         });
-        client.send(CharacterFinished::default());
-        client.send(FriendListInfo {
-            groups: vec![FriendListGroup::not_assigned()],
-            friends: vec![],
+        client.send(CharacterFinished {
+            item_cooldowns: item_cooldowns.as_protocol(),
+            skill_cooldowns: skill_cooldowns.as_protocol(player.character.skills.iter().copied()),
         });
+        client.send(friends.as_protocol(|friend| lookup.get_entity_for_name(&friend.name).is_some()));
 
         if let Some(notice) = &settings.join_notice {
             client.send(ChatUpdate::new(ChatSource::Notice, notice.clone()));
@@ -46,23 +77,6 @@ pub(crate) fn load_finished(
     }
 }
 
-fn send_character_stats(client: &Client, character: &Character) {
-    client.send(CharacterStatsMessage::new(
-        100,
-        100,
-        100,
-        100,
-        100,
-        100,
-        100,
-        100,
-        character.max_hp(),
-        character.max_mp(),
-        character.stats.strength(),
-        character.stats.intelligence(),
-    ));
-}
-
 fn send_text_initialization(client: &Client) {
     let mut characters = Vec::new();
     for i in 0x1d..0x8cu64 {
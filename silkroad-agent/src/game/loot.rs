@@ -0,0 +1,72 @@
+use crate::comp::damage::DamageReceiver;
+use crate::comp::drop::DropOrigin;
+use crate::comp::monster::Monster;
+use crate::comp::pos::Position;
+use crate::comp::{EntityReference, GameEntity};
+use crate::config::GameConfig;
+use crate::event::EntityDeath;
+use crate::game::drop::SpawnDrop;
+use crate::world::{EntityLookup, WorldData};
+use bevy_ecs::prelude::*;
+use rand::thread_rng;
+use silkroad_data::itemdata::RefItemData;
+use silkroad_definitions::type_id::{ObjectItem, ObjectType};
+use silkroad_game_base::{roll_loot, DropRolls, Item, ItemTypeData};
+
+fn create_item(reference: &'static RefItemData, amount: u16) -> Item {
+    let type_data = match ObjectType::from_type_id(&reference.common.type_id) {
+        Some(ObjectType::Item(ObjectItem::Equippable(_))) => ItemTypeData::Equipment { upgrade_level: 0 },
+        Some(ObjectType::Item(ObjectItem::Pet(_))) => ItemTypeData::COS,
+        _ => ItemTypeData::Consumable {
+            amount: amount.clamp(1, reference.max_stack_size.max(1)),
+        },
+    };
+    Item {
+        reference,
+        variance: None,
+        type_data,
+    }
+}
+
+pub(crate) fn drop_loot(
+    mut death_events: EventReader<EntityDeath>,
+    query: Query<(&GameEntity, &Position, &Monster, &DamageReceiver)>,
+    owner_query: Query<&GameEntity>,
+    lookup: Res<EntityLookup>,
+    config: Res<GameConfig>,
+    mut drop_events: EventWriter<SpawnDrop>,
+) {
+    let items = WorldData::items();
+    let rolls: DropRolls = (&config.drop_rolls).into();
+    for event in death_events.iter() {
+        let Ok((game_entity, position, monster, damage)) = query.get(event.died.0) else {
+            continue;
+        };
+
+        let owner = damage.top_attacker().and_then(|attacker| {
+            let entity = lookup.get_entity_for_id(attacker)?;
+            let owner = owner_query.get(entity).ok()?;
+            Some(EntityReference(entity, *owner))
+        });
+
+        let loot = roll_loot(
+            &mut thread_rng(),
+            WorldData::drops(),
+            &rolls,
+            game_entity.ref_id,
+            monster.rarity,
+            config.rates.drop,
+        );
+        for loot in loot {
+            let Some(reference) = items.find_id(loot.ref_id) else {
+                continue;
+            };
+            drop_events.send(SpawnDrop {
+                item: create_item(reference, loot.amount),
+                relative_position: position.location(),
+                owner,
+                source: DropOrigin::Monster(game_entity.unique_id),
+            });
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::comp::exp::SP;
 use crate::comp::net::Client;
 use crate::comp::player::Player;
 use crate::config::GameConfig;
@@ -6,15 +7,14 @@ use crate::world::WorldData;
 use bevy_ecs::prelude::*;
 use silkroad_game_base::Race;
 use silkroad_protocol::skill::{LearnSkillResponse, LevelUpMasteryError, LevelUpMasteryResponse};
-use silkroad_protocol::world::CharacterPointsUpdate;
 
 pub(crate) fn handle_mastery_levelup(
-    mut query: Query<(&Client, &mut Player, &mut PlayerInput)>,
+    mut query: Query<(&Client, &mut Player, &mut PlayerInput, &mut SP)>,
     config: Res<GameConfig>,
 ) {
     let masteries = WorldData::masteries();
     let levels = WorldData::levels();
-    for (client, mut player, mut input) in query.iter_mut() {
+    for (client, mut player, mut input, mut sp) in query.iter_mut() {
         if let Some(mastery_levelup) = input.mastery.take() {
             let Some(mastery) = masteries.find_id(mastery_levelup.mastery) else {
                 client.send(LevelUpMasteryResponse::Error(LevelUpMasteryError::InsufficientSP)); // TODO
@@ -49,19 +49,11 @@ pub(crate) fn handle_mastery_levelup(
 
             let required_sp = levels.get_mastery_sp_for_level(current_level).unwrap_or(0);
 
-            if player.character.sp < required_sp {
+            if !sp.spend(required_sp) {
                 client.send(LevelUpMasteryResponse::Error(LevelUpMasteryError::InsufficientSP));
                 continue;
             }
 
-            if required_sp > 0 {
-                player.character.sp -= required_sp;
-                client.send(CharacterPointsUpdate::SP {
-                    amount: player.character.sp,
-                    display: false,
-                });
-            }
-
             let next_level = current_level.checked_add(1).unwrap_or(u8::MAX);
             if let Some(position) = player
                 .character
@@ -81,8 +73,8 @@ pub(crate) fn handle_mastery_levelup(
     }
 }
 
-pub(crate) fn learn_skill(mut query: Query<(&Client, &mut Player, &mut PlayerInput)>) {
-    for (client, mut player, mut input) in query.iter_mut() {
+pub(crate) fn learn_skill(mut query: Query<(&Client, &mut Player, &mut PlayerInput, &mut SP)>) {
+    for (client, mut player, mut input, mut sp) in query.iter_mut() {
         if let Some(learn) = input.skill_add.take() {
             let Some(skill) = WorldData::skills().find_id(learn.0) else {
                 client.send(LearnSkillResponse::Error(LevelUpMasteryError::InsufficientSP)); // TODO
@@ -94,7 +86,7 @@ pub(crate) fn learn_skill(mut query: Query<(&Client, &mut Player, &mut PlayerInp
                 continue;
             }
 
-            if skill.sp > player.character.sp {
+            if skill.sp > sp.current() {
                 client.send(LearnSkillResponse::Error(LevelUpMasteryError::InsufficientSP)); // TODO
                 continue;
             }
@@ -131,11 +123,7 @@ pub(crate) fn learn_skill(mut query: Query<(&Client, &mut Player, &mut PlayerInp
                 }
             }
 
-            player.character.sp -= skill.sp;
-            client.send(CharacterPointsUpdate::SP {
-                amount: player.character.sp,
-                display: false,
-            });
+            sp.spend(skill.sp);
 
             if let Some(pos) = player
                 .character
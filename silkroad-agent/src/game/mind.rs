@@ -1,42 +1,62 @@
-use crate::agent::states::{Dead, Idle, MovementGoal, Moving, Pickup, StateTransitionQueue};
+use crate::agent::states::{ActionTarget, Dead, Idle, MovementGoal, Moving, Pickup, StateTransitionQueue};
+use crate::comp::cooldown::SkillCooldowns;
 use crate::comp::inventory::PlayerInventory;
 use crate::comp::net::Client;
 use crate::comp::pos::Position;
-use crate::comp::{EntityReference, GameEntity};
+use crate::comp::{EntityReference, GameEntity, Mana};
 use crate::ext::Navmesh;
-use crate::game::attack::{Attack, AttackProcess};
+use crate::game::attack::{Attack, AttackProcess, Caster};
 use crate::world::WorldData;
 use bevy_app::{App, Plugin, PostUpdate, PreUpdate};
 use bevy_ecs::prelude::*;
 use bevy_ecs_macros::Component;
 use bevy_time::common_conditions::on_timer;
+use cgmath::MetricSpace;
 use silkroad_data::skilldata::RefSkillData;
-use silkroad_definitions::inventory::EquipmentSlot;
-use silkroad_game_base::AttackSkillError;
-use silkroad_protocol::combat::{DoActionResponseCode, PerformActionError, PerformActionResponse};
+use silkroad_game_base::{GlobalLocation, GlobalPosition};
+use silkroad_protocol::combat::{DoActionResponseCode, PerformActionResponse};
 use std::time::Duration;
 use tracing::warn;
 
+/// Distance the target of an attack may move away from where it was when the path towards it was found, before a new
+/// path needs to be found.
+const REPATH_DISTANCE: f32 = 5.0;
+
 #[derive(Component, Default)]
 pub struct Mind {
     current_goal: Option<Goal>,
+    /// Where the target of the current goal was when the path towards it was found.
+    path_target: Option<GlobalLocation>,
 }
 
 impl Mind {
     pub fn attack(&mut self, target: EntityReference) {
-        self.current_goal = Some(Goal::Attack(target))
+        self.set_goal(Goal::Attack(target))
     }
 
-    pub fn attack_with(&mut self, target: EntityReference, skill: &'static RefSkillData) {
-        self.current_goal = Some(Goal::ExecuteSkill(target, skill))
+    pub fn attack_with(&mut self, target: SkillTarget, skill: &'static RefSkillData) {
+        self.set_goal(Goal::ExecuteSkill(target, skill))
     }
 
     pub fn cancel(&mut self) {
         self.current_goal = None;
+        self.path_target = None;
     }
 
     pub fn pickup(&mut self, target: EntityReference) {
-        self.current_goal = Some(Goal::PickUp(target));
+        self.set_goal(Goal::PickUp(target));
+    }
+
+    fn set_goal(&mut self, goal: Goal) {
+        self.current_goal = Some(goal);
+        self.path_target = None;
+    }
+
+    /// Checks if the path found towards the target still leads to it, because the target didn't move too far away
+    /// from where it was when the path was found.
+    fn has_path_towards(&self, target: GlobalLocation) -> bool {
+        self.path_target
+            .is_some_and(|location| location.0.distance2(target.0) <= REPATH_DISTANCE.powi(2))
     }
 
     pub fn has_goal(&self) -> bool {
@@ -47,10 +67,38 @@ impl Mind {
 #[derive(Copy, Clone)]
 pub enum Goal {
     Attack(EntityReference),
-    ExecuteSkill(EntityReference, &'static RefSkillData),
+    ExecuteSkill(SkillTarget, &'static RefSkillData),
     PickUp(EntityReference),
 }
 
+/// What a skill should be executed on, which is either another entity, a spot on the ground or the entity using the
+/// skill itself.
+#[derive(Copy, Clone)]
+pub enum SkillTarget {
+    Entity(EntityReference),
+    Location(GlobalPosition),
+    Own,
+}
+
+impl SkillTarget {
+    /// Provides the target for the action as well as the position of the target, unless the targeted entity is no
+    /// longer available.
+    fn resolve(
+        &self,
+        own_position: &Position,
+        target_query: &Query<&Position, Without<Dead>>,
+    ) -> Option<(ActionTarget, GlobalPosition)> {
+        match self {
+            SkillTarget::Own => Some((ActionTarget::Own, own_position.position())),
+            SkillTarget::Entity(target) => target_query
+                .get(target.0)
+                .ok()
+                .map(|position| (ActionTarget::Entity(target.0), position.position())),
+            SkillTarget::Location(position) => Some((ActionTarget::Location(position.to_location()), *position)),
+        }
+    }
+}
+
 fn enqueue_action(
     mut query: Query<
         (
@@ -60,13 +108,15 @@ fn enqueue_action(
             &Position,
             &mut StateTransitionQueue,
             Option<&PlayerInventory>,
+            Option<&Mana>,
+            Option<&SkillCooldowns>,
         ),
         With<Idle>,
     >,
     target_query: Query<&Position, Without<Dead>>,
     navmesh: Res<Navmesh>,
 ) {
-    for (entity, client, mut mind, position, mut state, inventory) in query.iter_mut() {
+    for (entity, client, mut mind, position, mut state, inventory, mana, cooldowns) in query.iter_mut() {
         if let Some(goal) = mind.current_goal.as_ref() {
             if matches!(goal, Goal::PickUp(_)) {
                 let Goal::PickUp(target) = goal else {
@@ -100,37 +150,42 @@ fn enqueue_action(
                     let target_movement_pos = my_location.point_in_line_with_range(target_pos.location(), range);
 
                     let target_height = navmesh.height_for(target_movement_pos).unwrap_or(position.position().y);
-                    state.request_transition(Moving(MovementGoal::Location(
+                    match MovementGoal::path_to(
+                        &navmesh,
+                        position.position(),
                         target_movement_pos.with_y(target_height),
-                    )));
+                    ) {
+                        Some(goal) => state.request_transition(Moving(goal)),
+                        None => {
+                            mind.cancel();
+                            state.request_transition(Idle);
+                        },
+                    }
                 }
             } else {
                 let (target, skill) = match goal {
                     Goal::Attack(target) => (
-                        target,
+                        SkillTarget::Entity(*target),
                         match inventory {
                             Some(inv) => Attack::find_attack_for_player(inv).unwrap(),
                             None => Attack::find_attack_for_monster(*entity).unwrap(),
                         },
                     ),
-                    Goal::ExecuteSkill(target, skill) => (target, *skill),
+                    Goal::ExecuteSkill(target, skill) => (*target, *skill),
                     _ => continue,
                 };
 
-                let target_pos = match target_query.get(target.0) {
-                    Ok(target_position) => target_position,
-                    Err(_) => {
-                        // Target most likely died.
-                        mind.cancel();
-                        continue;
-                    },
+                let Some((target, target_pos)) = target.resolve(position, &target_query) else {
+                    // Target most likely died.
+                    mind.cancel();
+                    continue;
                 };
 
                 let mut process = AttackProcess::new(
                     &mut state,
                     position,
                     skill,
-                    inventory.and_then(|inv| inv.get_equipment_item(EquipmentSlot::Weapon)),
+                    Caster::new(inventory, mana, cooldowns),
                     target,
                     target_pos,
                     &navmesh,
@@ -138,6 +193,7 @@ fn enqueue_action(
 
                 match process.try_attack() {
                     Ok(_) => {
+                        mind.path_target = Some(target_pos.to_location());
                         if let Some(client) = client {
                             client.send(PerformActionResponse::Do(DoActionResponseCode::Success));
                         }
@@ -145,14 +201,7 @@ fn enqueue_action(
                     Err(err) => {
                         mind.cancel();
                         if let Some(client) = client {
-                            match err {
-                                AttackSkillError::NotAWeapon | AttackSkillError::UnknownWeapon => {
-                                    client.send(PerformActionResponse::Stop(PerformActionError::InvalidWeapon));
-                                },
-                                AttackSkillError::SkillNotFound => {
-                                    client.send(PerformActionResponse::Stop(PerformActionError::NotLearned));
-                                },
-                            }
+                            client.send(PerformActionResponse::Stop(err.as_protocol()));
                         } else {
                             warn!("Couldn't execute attack for monster");
                         }
@@ -172,6 +221,8 @@ pub(crate) fn update_action(
             &Position,
             &mut StateTransitionQueue,
             Option<&PlayerInventory>,
+            Option<&Mana>,
+            Option<&SkillCooldowns>,
             &Moving,
         ),
         Without<Idle>,
@@ -179,7 +230,7 @@ pub(crate) fn update_action(
     target_query: Query<&Position, Without<Dead>>,
     navmesh: Res<Navmesh>,
 ) {
-    for (entity, client, mut mind, position, mut state, inventory, moving) in query.iter_mut() {
+    for (entity, client, mut mind, position, mut state, inventory, mana, cooldowns, moving) in query.iter_mut() {
         let Some(goal) = mind.current_goal.as_ref() else {
             continue;
         };
@@ -191,31 +242,31 @@ pub(crate) fn update_action(
                 continue;
             }
 
-            if !matches!(moving.0, MovementGoal::Location(_)) {
+            if !matches!(moving.0, MovementGoal::Location(_) | MovementGoal::Path(_)) {
                 mind.cancel();
                 continue;
             }
         } else {
             let (target, skill) = match goal {
                 Goal::Attack(target) => (
-                    target,
+                    SkillTarget::Entity(*target),
                     match inventory {
                         Some(inv) => Attack::find_attack_for_player(inv).unwrap(),
                         None => Attack::find_attack_for_monster(*entity).unwrap(),
                     },
                 ),
-                Goal::ExecuteSkill(target, skill) => (target, *skill),
+                Goal::ExecuteSkill(target, skill) => (*target, *skill),
                 _ => continue,
             };
 
-            if !matches!(moving.0, MovementGoal::Location(_)) {
+            if !matches!(moving.0, MovementGoal::Location(_) | MovementGoal::Path(_)) {
                 // If we aren't moving to a specific location, we aren't moving to a target.
                 // Thus, we probably cancelled the attack.
                 mind.cancel();
                 continue;
             }
 
-            let Ok(entity_location) = target_query.get(target.0) else {
+            let Some((target, target_pos)) = target.resolve(position, &target_query) else {
                 // Target probably died. We might need to send some "invalid target" response here?
                 mind.cancel();
                 state.request_transition(Idle);
@@ -226,14 +277,21 @@ pub(crate) fn update_action(
                 &mut state,
                 position,
                 skill,
-                inventory.and_then(|inv| inv.get_equipment_item(EquipmentSlot::Weapon)),
+                Caster::new(inventory, mana, cooldowns),
                 target,
-                entity_location,
+                target_pos,
                 &navmesh,
             );
 
+            // Finding a path is expensive, so we keep following the current one until we can attack, unless the
+            // target moved too far away from it.
+            if !process.can_execute() && mind.has_path_towards(target_pos.to_location()) {
+                continue;
+            }
+
             match process.try_attack() {
                 Ok(_) => {
+                    mind.path_target = Some(target_pos.to_location());
                     if let Some(client) = client {
                         client.send(PerformActionResponse::Do(DoActionResponseCode::Success));
                     }
@@ -241,14 +299,7 @@ pub(crate) fn update_action(
                 Err(err) => {
                     mind.cancel();
                     if let Some(client) = client {
-                        match err {
-                            AttackSkillError::NotAWeapon | AttackSkillError::UnknownWeapon => {
-                                client.send(PerformActionResponse::Stop(PerformActionError::InvalidWeapon));
-                            },
-                            AttackSkillError::SkillNotFound => {
-                                client.send(PerformActionResponse::Stop(PerformActionError::NotLearned));
-                            },
-                        }
+                        client.send(PerformActionResponse::Stop(err.as_protocol()));
                     } else {
                         warn!("Couldn't execute attack for monster");
                     }
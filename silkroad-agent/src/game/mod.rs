@@ -1,21 +1,31 @@
 use crate::agent::AgentSet;
 use crate::chat::ChatPlugin;
-use crate::event::{DamageReceiveEvent, EntityDeath, LoadingFinishedEvent, PlayerLevelUp, UniqueKilledEvent};
+use crate::event::{
+    BuffApplyEvent, DamageReceiveEvent, EntityDeath, LoadingFinishedEvent, PlayerLevelUp, UniqueKilledEvent,
+};
 use crate::game::action::handle_action;
-use crate::game::attack::AttackInstanceCounter;
+use crate::game::alchemy::handle_reinforce;
+use crate::game::attack::{tick_skill_cooldowns, AttackInstanceCounter};
+use crate::game::buff::{apply_buffs, tick_buffs, update_buffed_speed, BuffTokenCounter};
+use crate::game::consumable::{cast_return_scroll, interrupt_return_scroll, tick_item_cooldowns, use_consumable};
 use crate::game::damage::{attack_player, handle_damage};
 use crate::game::daylight::{advance_daylight, DaylightCycle};
-use crate::game::drop::{create_drops, tick_drop, SpawnDrop};
+use crate::game::death::{handle_player_death, resurrect};
+use crate::game::drop::{create_drops, tick_drop, tick_drop_protection, SpawnDrop};
 use crate::game::exp::{distribute_experience, receive_experience, reset_health_mana_on_level, ReceiveExperienceEvent};
 use crate::game::gold::drop_gold;
 use crate::game::inventory::handle_inventory_input;
 use crate::game::join::load_finished;
 use crate::game::logout::{handle_logout, tick_logout};
+use crate::game::loot::drop_loot;
 use crate::game::mastery::{handle_mastery_levelup, learn_skill};
 use crate::game::mind::MindPlugin;
 use crate::game::movement::movement_monster;
+use crate::game::persistence::{autosave_characters, AutosaveTimer, CharacterSaves};
 use crate::game::player_activity::{update_player_activity, PlayerActivity};
-use crate::game::stats::increase_stats;
+use crate::game::regen::{enter_combat, leave_combat, regenerate, RegenerationTimer};
+use crate::game::shop::handle_npc_talk;
+use crate::game::stats::{grant_stat_points, increase_stats, reset_stats, ResetStatsEvent};
 use crate::game::target::{deselect_despawned, player_update_target};
 use crate::game::unique::{unique_killed, unique_spawned};
 use crate::game::visibility::{clear_visibility, player_visibility_update, visibility_update};
@@ -24,20 +34,28 @@ use bevy_app::{App, Last, Plugin, PostUpdate, PreUpdate, Update};
 use bevy_ecs::prelude::*;
 
 mod action;
+mod alchemy;
 pub(crate) mod attack;
-mod damage;
+mod buff;
+mod consumable;
+pub(crate) mod damage;
 mod daylight;
+mod death;
 pub(crate) mod drop;
 pub(crate) mod exp;
 mod gold;
 pub(crate) mod inventory;
 mod join;
 pub(crate) mod logout;
+mod loot;
 mod mastery;
 pub(crate) mod mind;
 mod movement;
+pub(crate) mod persistence;
 pub(crate) mod player_activity;
-mod stats;
+mod regen;
+pub(crate) mod shop;
+pub(crate) mod stats;
 pub(crate) mod target;
 mod unique;
 mod visibility;
@@ -51,14 +69,45 @@ impl Plugin for GamePlugin {
             .insert_resource(PlayerActivity::default())
             .insert_resource(DaylightCycle::official())
             .insert_resource(AttackInstanceCounter::default())
+            .init_resource::<BuffTokenCounter>()
+            .init_resource::<AutosaveTimer>()
+            .init_resource::<CharacterSaves>()
+            .init_resource::<RegenerationTimer>()
             .add_event::<PlayerLevelUp>()
             .add_event::<LoadingFinishedEvent>()
             .add_event::<UniqueKilledEvent>()
             .add_event::<SpawnDrop>()
             .add_event::<DamageReceiveEvent>()
+            .add_event::<BuffApplyEvent>()
             .add_event::<EntityDeath>()
             .add_event::<ReceiveExperienceEvent>()
+            .add_event::<ResetStatsEvent>()
             .add_systems(PreUpdate, update_player_activity)
+            .add_systems(
+                Update,
+                (
+                    use_consumable,
+                    reset_stats.after(use_consumable),
+                    grant_stat_points.after(receive_experience),
+                    handle_player_death.after(handle_damage),
+                    resurrect,
+                    enter_combat,
+                    leave_combat,
+                    regenerate.after(leave_combat),
+                    tick_item_cooldowns.before(use_consumable),
+                    tick_skill_cooldowns,
+                    cast_return_scroll,
+                    interrupt_return_scroll,
+                    handle_npc_talk,
+                    handle_reinforce,
+                    apply_buffs,
+                    tick_buffs,
+                    update_buffed_speed
+                        .after(apply_buffs)
+                        .after(tick_buffs)
+                        .after(handle_action),
+                ),
+            )
             .add_systems(
                 Update,
                 (
@@ -67,6 +116,7 @@ impl Plugin for GamePlugin {
                     visibility_update,
                     movement_monster,
                     tick_drop,
+                    tick_drop_protection,
                     handle_logout,
                     handle_action,
                     tick_logout,
@@ -76,6 +126,7 @@ impl Plugin for GamePlugin {
                     attack_player,
                     distribute_experience.after(handle_damage),
                     drop_gold.after(handle_damage),
+                    drop_loot.after(handle_damage),
                     receive_experience.after(distribute_experience),
                     reset_health_mana_on_level.after(receive_experience),
                     handle_mastery_levelup,
@@ -91,6 +142,7 @@ impl Plugin for GamePlugin {
                     unique_killed,
                     advance_daylight,
                     create_drops,
+                    autosave_characters,
                 ),
             )
             .add_systems(Last, clear_visibility);
@@ -17,7 +17,9 @@ pub(crate) fn movement_monster(
         if stroll.check_timer.finished() && random::<f32>() <= 0.1 {
             let new_location = GlobalLocation(stroll.origin.0.random_in_radius(stroll.radius));
             let new_y = navmesh.height_for(new_location).unwrap_or(pos.position().0.y);
-            transition.request_transition(Moving(MovementGoal::Location(new_location.with_y(new_y))));
+            if let Some(goal) = MovementGoal::path_to(&navmesh, pos.position(), new_location.with_y(new_y)) {
+                transition.request_transition(Moving(goal));
+            }
             stroll.check_timer.reset();
         } else {
             stroll.check_timer.tick(delta);
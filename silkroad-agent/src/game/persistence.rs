@@ -0,0 +1,174 @@
+use crate::comp::exp::{Experienced, Leveled, SP};
+use crate::comp::inventory::PlayerInventory;
+use crate::comp::player::Player;
+use crate::comp::pos::Position;
+use crate::comp::{Health, Mana};
+use crate::config::GameConfig;
+use crate::db::persistence::{CharacterSnapshot, ItemSnapshot};
+use crate::ext::DbPool;
+use crate::tasks::TaskCreator;
+use bevy_ecs::prelude::*;
+use bevy_ecs::query::WorldQuery;
+use bevy_time::{Time, Timer, TimerMode};
+use sqlx::PgPool;
+use std::cmp::max;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, error};
+
+/// All the components of a player that make up the state that gets persisted.
+#[derive(WorldQuery)]
+pub(crate) struct PersistedCharacter {
+    player: &'static Player,
+    position: &'static Position,
+    inventory: &'static PlayerInventory,
+    level: &'static Leveled,
+    exp: &'static Experienced,
+    sp: &'static SP,
+    health: &'static Health,
+    mana: &'static Mana,
+}
+
+impl PersistedCharacterItem<'_> {
+    pub(crate) fn snapshot(&self) -> CharacterSnapshot {
+        let character = &self.player.character;
+        let local_position = self.position.position().to_local();
+        let level = self.level.current_level();
+        CharacterSnapshot {
+            id: character.id,
+            level,
+            max_level: max(character.max_level, level),
+            exp: self.exp.experience(),
+            sp: self.sp.current(),
+            sp_exp: self.exp.sp_experience() as u32,
+            strength: character.stats.strength(),
+            intelligence: character.stats.intelligence(),
+            stat_points: character.stat_points,
+            current_hp: self.health.current_health,
+            current_mp: self.mana.current_mana,
+            berserk_points: character.berserk_points,
+            gold: self.inventory.gold,
+            beginner_mark: character.beginner_mark,
+            region: local_position.0.id(),
+            x: local_position.1.x,
+            y: local_position.1.y,
+            z: local_position.1.z,
+            rotation: self.position.rotation().into(),
+            items: self
+                .inventory
+                .items()
+                .map(|(slot, item)| ItemSnapshot {
+                    slot: *slot,
+                    ref_id: item.reference.common.ref_id,
+                    upgrade_level: item.upgrade_level(),
+                    variance: item.variance,
+                    amount: item.stack_size(),
+                })
+                .collect(),
+            masteries: character
+                .masteries
+                .iter()
+                .map(|(mastery, level)| (mastery.ref_id as u32, *level))
+                .collect(),
+            skills: character.skills.iter().map(|skill| skill.ref_id).collect(),
+        }
+    }
+}
+
+/// The generation of the snapshot last written for a character, which is locked while a save of it is written.
+type LastWritten = Arc<tokio::sync::Mutex<u64>>;
+
+/// Orders the saves of each character. Saves are written asynchronously, so a snapshot taken earlier, e.g. by an
+/// autosave, could otherwise finish after a later one, e.g. from logging out, and overwrite it with outdated data.
+/// Every snapshot gets a generation when it is taken and the saves of a character are written one after another,
+/// skipping every snapshot that is older than the one last written. A character is only tracked while it has saves
+/// in flight, such that characters that logged out don't stick around.
+#[derive(Resource, Default)]
+pub(crate) struct CharacterSaves {
+    next_generation: AtomicU64,
+    written: Arc<Mutex<HashMap<u32, LastWritten>>>,
+}
+
+impl CharacterSaves {
+    /// Creates the task writing the snapshot. The snapshot is taken to be newer than all snapshots saved before it.
+    pub(crate) fn save(&self, snapshot: CharacterSnapshot, pool: PgPool) -> impl Future<Output = ()> + Send + 'static {
+        let generation = self.next_generation.fetch_add(1, Ordering::Relaxed) + 1;
+        let last_written = Arc::clone(
+            self.written
+                .lock()
+                .expect("Character saves should not be poisoned")
+                .entry(snapshot.id)
+                .or_default(),
+        );
+        let written = Arc::clone(&self.written);
+        async move {
+            let id = snapshot.id;
+            {
+                let mut guard = last_written.lock().await;
+                if *guard > generation {
+                    debug!(id, "Skipping outdated character snapshot.");
+                } else {
+                    save_character(snapshot, pool).await;
+                    *guard = generation;
+                }
+            }
+            Self::forget_if_idle(&written, id, last_written);
+        }
+    }
+
+    /// Stops tracking the character if no other save of it is still pending. Any snapshot taken afterwards gets a
+    /// newer generation than all the ones written so far, so there is nothing left to order it against.
+    fn forget_if_idle(written: &Mutex<HashMap<u32, LastWritten>>, id: u32, own: LastWritten) {
+        let mut written = written.lock().expect("Character saves should not be poisoned");
+        let idle = written
+            .get(&id)
+            .is_some_and(|entry| Arc::ptr_eq(entry, &own) && Arc::strong_count(entry) == 2);
+        if idle {
+            written.remove(&id);
+        }
+    }
+}
+
+#[derive(Resource)]
+pub(crate) struct AutosaveTimer(Option<Timer>);
+
+impl FromWorld for AutosaveTimer {
+    fn from_world(world: &mut World) -> Self {
+        let interval = world.resource::<GameConfig>().autosave_interval;
+        if interval == 0 {
+            return AutosaveTimer(None);
+        }
+        AutosaveTimer(Some(Timer::new(Duration::from_secs(interval), TimerMode::Repeating)))
+    }
+}
+
+pub(crate) fn autosave_characters(
+    mut timer: ResMut<AutosaveTimer>,
+    time: Res<Time>,
+    query: Query<PersistedCharacter>,
+    task_creator: Res<TaskCreator>,
+    pool: Res<DbPool>,
+    saves: Res<CharacterSaves>,
+) {
+    let Some(timer) = timer.0.as_mut() else {
+        return;
+    };
+
+    if !timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    debug!(players = query.iter().count(), "Autosaving characters.");
+    for character in query.iter() {
+        task_creator.spawn(saves.save(character.snapshot(), PgPool::clone(&pool)));
+    }
+}
+
+async fn save_character(snapshot: CharacterSnapshot, pool: PgPool) {
+    if let Err(e) = snapshot.save(&pool).await {
+        error!(id = snapshot.id, "Could not save character: {:?}", e);
+    }
+}
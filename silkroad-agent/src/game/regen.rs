@@ -0,0 +1,82 @@
+use crate::agent::states::{Dead, Sitting};
+use crate::comp::damage::InCombat;
+use crate::comp::{Health, Mana};
+use crate::config::GameConfig;
+use crate::event::DamageReceiveEvent;
+use bevy_ecs::prelude::*;
+use bevy_ecs::query::{Has, WorldQuery};
+use bevy_time::{Time, Timer, TimerMode};
+use std::time::Duration;
+
+#[derive(Resource)]
+pub(crate) struct RegenerationTimer(Timer);
+
+impl FromWorld for RegenerationTimer {
+    fn from_world(world: &mut World) -> Self {
+        let interval = world.resource::<GameConfig>().regeneration.interval;
+        RegenerationTimer(Timer::new(Duration::from_secs(interval), TimerMode::Repeating))
+    }
+}
+
+pub(crate) fn enter_combat(
+    mut damage_events: EventReader<DamageReceiveEvent>,
+    config: Res<GameConfig>,
+    mut cmd: Commands,
+) {
+    let duration = Duration::from_secs(config.regeneration.combat_duration);
+    for event in damage_events.iter() {
+        for entity in [event.source.0, event.target.0] {
+            if let Some(mut entity) = cmd.get_entity(entity) {
+                entity.insert(InCombat::for_duration(duration));
+            }
+        }
+    }
+}
+
+pub(crate) fn leave_combat(mut query: Query<(Entity, &mut InCombat)>, time: Res<Time>, mut cmd: Commands) {
+    let delta = time.delta();
+    for (entity, mut combat) in query.iter_mut() {
+        if combat.0.tick(delta).finished() {
+            cmd.entity(entity).remove::<InCombat>();
+        }
+    }
+}
+
+#[derive(WorldQuery)]
+#[world_query(mutable)]
+pub(crate) struct Regenerating {
+    health: &'static mut Health,
+    mana: Option<&'static mut Mana>,
+    sitting: Has<Sitting>,
+}
+
+pub(crate) fn regenerate(
+    mut timer: ResMut<RegenerationTimer>,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    mut query: Query<Regenerating, (Without<Dead>, Without<InCombat>)>,
+) {
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let settings = &config.regeneration;
+    for mut entity in query.iter_mut() {
+        let share = if entity.sitting {
+            settings.share * settings.sitting_multiplier
+        } else {
+            settings.share
+        };
+
+        let health = &mut entity.health;
+        if health.current_health < health.max_health {
+            let amount = (health.max_health as f32 * share).ceil() as u32;
+            health.regenerate(amount);
+        }
+
+        if let Some(mana) = entity.mana.as_mut().filter(|mana| mana.current_mana < mana.max_mana) {
+            let amount = (mana.max_mana as f32 * share).ceil() as u32;
+            mana.regenerate(amount);
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use crate::comp::net::Client;
+use crate::comp::npc::NPC;
+use crate::comp::pos::Position;
+use crate::comp::GameEntity;
+use crate::input::PlayerInput;
+use crate::world::{EntityLookup, WorldData};
+use bevy_ecs::prelude::*;
+use bevy_ecs::system::SystemParam;
+use silkroad_data::itemdata::RefItemData;
+use silkroad_data::shop::{RefShopGoods, RefShopTab};
+use silkroad_definitions::type_id::{ObjectItem, ObjectType};
+use silkroad_game_base::{Item, ItemTypeData};
+use silkroad_protocol::world::{OpenNpcTalkResponse, TalkOption};
+
+/// The maximum (squared) distance a player may be away from an NPC to trade with it.
+const MAX_TRADE_DISTANCE: f32 = 100.0 * 100.0;
+
+/// Provides the tabs of the shop the given NPC owns, if any.
+pub(crate) fn shop_of(npc: &GameEntity) -> Option<&'static [RefShopTab]> {
+    let character = WorldData::characters().find_id(npc.ref_id)?;
+    WorldData::shops().shop_of(&character.common.id)
+}
+
+/// Creates the item as bought from a shop, with the given amount if the item is stackable.
+pub(crate) fn create_shop_item(reference: &'static RefItemData, good: &RefShopGoods, amount: u16) -> Item {
+    let type_data = match ObjectType::from_type_id(&reference.common.type_id) {
+        Some(ObjectType::Item(ObjectItem::Equippable(_))) => ItemTypeData::Equipment {
+            upgrade_level: good.upgrade_level,
+        },
+        Some(ObjectType::Item(ObjectItem::Pet(_))) => ItemTypeData::COS,
+        _ => ItemTypeData::Consumable { amount },
+    };
+    Item {
+        reference,
+        variance: None,
+        type_data,
+    }
+}
+
+/// Finds the shops of NPCs close to players.
+#[derive(SystemParam)]
+pub(crate) struct Shops<'w, 's> {
+    lookup: Res<'w, EntityLookup>,
+    npcs: Query<'w, 's, (&'static GameEntity, &'static Position), With<NPC>>,
+}
+
+impl Shops<'_, '_> {
+    /// Provides the shop of the NPC with the given unique id, if the NPC is close enough to the given position.
+    pub(crate) fn shop_near(&self, npc_id: u32, position: &Position) -> Option<&'static [RefShopTab]> {
+        let entity = self.lookup.get_entity_for_id(npc_id)?;
+        let (npc, npc_position) = self.npcs.get(entity).ok()?;
+        if npc_position.distance_to(position) > MAX_TRADE_DISTANCE {
+            return None;
+        }
+        shop_of(npc)
+    }
+}
+
+pub(crate) fn handle_npc_talk(query: Query<(&Client, &PlayerInput, &Position)>, shops: Shops) {
+    for (client, input, position) in query.iter() {
+        let Some(ref talk) = input.npc_talk else {
+            continue;
+        };
+
+        let response = match talk.option {
+            TalkOption::Store if shops.shop_near(talk.unique_id, position).is_some() => {
+                OpenNpcTalkResponse::Success(talk.option)
+            },
+            _ => OpenNpcTalkResponse::Error,
+        };
+        client.send(response);
+    }
+}
@@ -1,14 +1,70 @@
+use crate::comp::exp::Leveled;
+use crate::comp::inventory::PlayerInventory;
 use crate::comp::net::Client;
-use crate::comp::player::Player;
+use crate::comp::player::{Buffed, Player};
+use crate::config::GameConfig;
 use crate::input::PlayerInput;
 use bevy_ecs::prelude::*;
-use silkroad_game_base::StatType;
+use silkroad_game_base::{CharacterStats, StatType};
 use silkroad_protocol::character::CharacterStatsMessage;
-use silkroad_protocol::world::{IncreaseIntResponse, IncreaseStrResponse};
+use silkroad_protocol::world::{CharacterPointsUpdate, IncreaseIntResponse, IncreaseStrResponse};
 use std::mem::take;
 
-pub(crate) fn increase_stats(mut query: Query<(&mut PlayerInput, &mut Player, &Client)>) {
-    for (mut input, mut player, client) in query.iter_mut() {
+/// Requests the allocated stat points of the player to be refunded, e.g. through a GM command or a reset scroll.
+#[derive(Event)]
+pub(crate) struct ResetStatsEvent(pub Entity);
+
+/// Calculates the current combat stats of the player, based on their level, their stats, their equipment and the
+/// buffs currently active on them.
+pub(crate) fn calculate_stats(
+    player: &Player,
+    inventory: &PlayerInventory,
+    buffs: &Buffed,
+    level: u8,
+) -> CharacterStats {
+    CharacterStats::calculate(
+        level,
+        &player.character.stats,
+        inventory.equipment_items().map(|(_, item)| item),
+    )
+    .with_buffs(&buffs.effect())
+}
+
+pub(crate) fn stats_message(
+    player: &Player,
+    inventory: &PlayerInventory,
+    buffs: &Buffed,
+    level: u8,
+) -> CharacterStatsMessage {
+    let stats = calculate_stats(player, inventory, buffs, level);
+    let character = &player.character;
+    CharacterStatsMessage {
+        phys_attack_min: stats.phys_attack.min,
+        phys_attack_max: stats.phys_attack.max,
+        mag_attack_min: stats.mag_attack.min,
+        mag_attack_max: stats.mag_attack.max,
+        phys_defense: stats.phys_defense.min(u16::MAX as u32) as u16,
+        mag_defense: stats.mag_defense.min(u16::MAX as u32) as u16,
+        hit_rate: stats.hit_rate.min(u16::MAX as u32) as u16,
+        parry_rate: stats.parry_rate.min(u16::MAX as u32) as u16,
+        max_hp: character.stats.max_health(level),
+        max_mp: character.stats.max_mana(level),
+        strength: character.stats.strength(),
+        intelligence: character.stats.intelligence(),
+    }
+}
+
+pub(crate) fn increase_stats(
+    mut query: Query<(
+        &mut PlayerInput,
+        &mut Player,
+        &PlayerInventory,
+        &Buffed,
+        &Leveled,
+        &Client,
+    )>,
+) {
+    for (mut input, mut player, inventory, buffs, level, client) in query.iter_mut() {
         for stat_increase in take(&mut input.increase_stats) {
             if player.character.stat_points == 0 {
                 match stat_increase {
@@ -31,20 +87,45 @@ pub(crate) fn increase_stats(mut query: Query<(&mut PlayerInput, &mut Player, &C
                 },
             }
 
-            client.send(CharacterStatsMessage {
-                phys_attack_min: 100,
-                phys_attack_max: 100,
-                mag_attack_min: 100,
-                mag_attack_max: 100,
-                phys_defense: 100,
-                mag_defense: 100,
-                hit_rate: 100,
-                parry_rate: 100,
-                max_hp: player.character.stats.max_health(player.character.level),
-                max_mp: player.character.stats.max_mana(player.character.level),
-                strength: player.character.stats.strength(),
-                intelligence: player.character.stats.intelligence(),
-            });
+            client.send(stats_message(&player, inventory, buffs, level.current_level()));
         }
     }
 }
+
+pub(crate) fn grant_stat_points(
+    mut query: Query<(&Client, &mut Player, &Leveled), Changed<Leveled>>,
+    config: Res<GameConfig>,
+) {
+    for (client, mut player, level) in query.iter_mut() {
+        // Only levels that are reached for the first time grant stat points, such that losing a level through dying and
+        // gaining it again cannot be exploited.
+        let new_levels = level.current_level().saturating_sub(player.character.max_level);
+        if !level.did_level() || new_levels == 0 {
+            continue;
+        }
+
+        let character = &mut player.character;
+        character.max_level = level.current_level();
+        character.stat_points = character
+            .stat_points
+            .saturating_add(u16::from(new_levels) * config.stat_points_per_level);
+        client.send(CharacterPointsUpdate::StatPoints(character.stat_points));
+    }
+}
+
+pub(crate) fn reset_stats(
+    mut events: EventReader<ResetStatsEvent>,
+    mut query: Query<(&Client, &mut Player, &PlayerInventory, &Buffed, &Leveled)>,
+) {
+    for event in events.iter() {
+        let Ok((client, mut player, inventory, buffs, level)) = query.get_mut(event.0) else {
+            continue;
+        };
+
+        let character = &mut player.character;
+        let refunded = character.stats.reset();
+        character.stat_points = character.stat_points.saturating_add(refunded);
+        client.send(CharacterPointsUpdate::StatPoints(character.stat_points));
+        client.send(stats_message(&player, inventory, buffs, level.current_level()));
+    }
+}
@@ -3,13 +3,16 @@ use crate::comp::net::Client;
 use crate::comp::npc::NPC;
 use crate::comp::player::Player;
 use crate::comp::pos::Position;
-use crate::comp::Health;
+use crate::comp::{GameEntity, Health};
+use crate::game::shop::shop_of;
 use crate::input::PlayerInput;
 use crate::world::EntityLookup;
 use bevy_ecs::prelude::*;
 use cgmath::MetricSpace;
 use derive_more::Deref;
-use silkroad_protocol::world::{TargetEntityError, TargetEntityResponse, TargetEntityResult, UnTargetEntityResponse};
+use silkroad_protocol::world::{
+    TalkOption, TargetEntityError, TargetEntityResponse, TargetEntityResult, UnTargetEntityResponse,
+};
 
 const MAX_TARGET_DISTANCE: f32 = 500. * 500.;
 
@@ -28,6 +31,7 @@ pub(crate) fn player_update_target(
     mut cmd: Commands,
     lookup: Res<EntityLookup>,
     target_lookup: Query<(
+        &GameEntity,
         &Position,
         Option<&Health>,
         Option<&Monster>,
@@ -39,7 +43,9 @@ pub(crate) fn player_update_target(
         'target: {
             if let Some(ref target) = input.target {
                 if let Some(target_entity) = lookup.get_entity_for_id(target.unique_id) {
-                    if let Ok((target_pos, health, monster, npc, player)) = target_lookup.get(target_entity) {
+                    if let Ok((game_entity, target_pos, health, monster, npc, player)) =
+                        target_lookup.get(target_entity)
+                    {
                         let distance = target_pos.position().distance2(pos.position().0);
                         if distance >= MAX_TARGET_DISTANCE {
                             // Is this an adequate response?
@@ -57,8 +63,13 @@ pub(crate) fn player_update_target(
                                 )));
                             },
                             (_, _, Some(_), _) => {
+                                let talk_options = match shop_of(game_entity) {
+                                    Some(_) => vec![TalkOption::Store.into()],
+                                    None => vec![],
+                                };
                                 client.send(TargetEntityResponse::new(TargetEntityResult::success_npc(
                                     target.unique_id,
+                                    talk_options,
                                 )));
                             },
                             (Some(health), _, _, Some(player)) => {},
@@ -1,15 +1,18 @@
+use crate::agent::states::Dead;
 use crate::agent::Agent;
 use crate::comp::drop::Drop;
 use crate::comp::inventory::PlayerInventory;
 use crate::comp::monster::Monster;
 use crate::comp::net::Client;
 use crate::comp::npc::NPC;
-use crate::comp::player::Player;
+use crate::comp::player::{Buffed, Player};
 use crate::comp::pos::Position;
 use crate::comp::visibility::{Invisible, Visibility};
 use crate::comp::{EntityReference, GameEntity};
 use crate::game::player_activity::PlayerActivity;
+use crate::guild::component::GuildMembership;
 use bevy_ecs::prelude::*;
+use bevy_ecs::query::Has;
 use cgmath::num_traits::Pow;
 use silkroad_data::DataEntry;
 use silkroad_definitions::Region;
@@ -17,7 +20,7 @@ use silkroad_game_base::ItemTypeData;
 use silkroad_navmesh::region::GridRegion;
 use silkroad_protocol::inventory::CharacterSpawnItemData;
 use silkroad_protocol::world::{
-    ActionState, ActiveScroll, AliveState, BodyState, DroppedItemSource, EntityState, EntityTypeSpawnData,
+    ActionState, ActiveBuffData, ActiveScroll, AliveState, BodyState, EntityState, EntityTypeSpawnData,
     GroupEntitySpawnData, GroupEntitySpawnEnd, GroupEntitySpawnStart, GroupSpawnDataContent, GroupSpawnType,
     GuildInformation, InteractOptions, ItemSpawnData, JobType, PlayerKillState, PvpCape,
 };
@@ -93,6 +96,9 @@ pub(crate) fn player_visibility_update(
             Option<&Monster>,
             Option<&Drop>,
             Option<&NPC>,
+            Option<&GuildMembership>,
+            Option<&Buffed>,
+            Has<Dead>,
         ),
         Without<Invisible>,
     >,
@@ -102,7 +108,19 @@ pub(crate) fn player_visibility_update(
         for reference in visibility.added_entities.iter() {
             let added = reference.0;
             let entity = reference.1;
-            if let Ok((pos, inventory_opt, agent_opt, player_opt, monster_opt, item_opt, npc_opt)) = lookup.get(added) {
+            if let Ok((
+                pos,
+                inventory_opt,
+                agent_opt,
+                player_opt,
+                monster_opt,
+                item_opt,
+                npc_opt,
+                guild_opt,
+                buffs_opt,
+                dead,
+            )) = lookup.get(added)
+            {
                 if let Some(player) = player_opt {
                     let agent = agent_opt.unwrap();
                     let items = inventory_opt
@@ -131,7 +149,7 @@ pub(crate) fn player_visibility_update(
                             mask: None,
                             position: pos.as_protocol(),
                             movement: pos.as_standing(),
-                            entity_state: entity_state_from_agent(agent),
+                            entity_state: entity_state_from_agent(agent, dead, buffs_opt),
                             name: player.character.name.clone(),
                             job_type: JobType::None,
                             pk_state: PlayerKillState::None,
@@ -140,8 +158,8 @@ pub(crate) fn player_visibility_update(
                             active_scroll: ActiveScroll::None,
                             unknown2: 0,
                             guild: GuildInformation {
-                                name: "".to_string(),
-                                id: 0,
+                                name: guild_opt.map(|guild| guild.name.clone()).unwrap_or_default(),
+                                id: guild_opt.map(|guild| guild.guild_id).unwrap_or(0),
                                 member: "".to_string(),
                                 last_icon_rev: 0,
                                 union_id: 0,
@@ -162,7 +180,7 @@ pub(crate) fn player_visibility_update(
                             unique_id: entity.unique_id,
                             position: pos.as_protocol(),
                             movement: pos.as_movement(),
-                            entity_state: entity_state_from_agent(agent),
+                            entity_state: entity_state_from_agent(agent, dead, buffs_opt),
                             interaction_options: InteractOptions::talk(vec![5]),
                             rarity: monster.rarity,
                             unknown: 0,
@@ -182,7 +200,7 @@ pub(crate) fn player_visibility_update(
                             unique_id: entity.unique_id,
                             position: pos.as_protocol(),
                             movement: pos.as_standing(),
-                            entity_state: entity_state_from_agent(agent),
+                            entity_state: entity_state_from_agent(agent, dead, buffs_opt),
                             interaction_options: InteractOptions::None,
                         },
                     ));
@@ -204,9 +222,9 @@ pub(crate) fn player_visibility_update(
     }
 }
 
-fn entity_state_from_agent(agent: &Agent) -> EntityState {
+fn entity_state_from_agent(agent: &Agent, dead: bool, buffs: Option<&Buffed>) -> EntityState {
     EntityState {
-        alive: AliveState::Alive,
+        alive: if dead { AliveState::Dead } else { AliveState::Alive },
         unknown1: 0,
         action_state: ActionState::None,
         body_state: BodyState::None,
@@ -214,11 +232,20 @@ fn entity_state_from_agent(agent: &Agent) -> EntityState {
         walk_speed: agent.walking_speed,
         run_speed: agent.running_speed,
         berserk_speed: agent.berserk_speed,
-        active_buffs: vec![],
+        active_buffs: buffs
+            .map(|buffed| {
+                buffed
+                    .buffs
+                    .iter()
+                    .map(|buff| ActiveBuffData::new(buff.skill.ref_id, buff.token))
+                    .collect()
+            })
+            .unwrap_or_default(),
     }
 }
 
 fn spawndata_from_item(entity: GameEntity, pos: &Position, drop: &Drop, for_player: &GameEntity) -> ItemSpawnData {
+    let (source, source_id) = drop.source.as_protocol();
     match drop.item.type_data {
         ItemTypeData::Equipment { upgrade_level } => ItemSpawnData::Equipment {
             upgrade: upgrade_level,
@@ -229,8 +256,8 @@ fn spawndata_from_item(entity: GameEntity, pos: &Position, drop: &Drop, for_play
                 .map(|owner| owner.1.unique_id)
                 .filter(|id| *id != for_player.unique_id),
             rarity: 0,
-            source: DroppedItemSource::None,
-            source_id: 0,
+            source,
+            source_id,
         },
         ItemTypeData::COS | ItemTypeData::Consumable { .. } => ItemSpawnData::Consumable {
             unique_id: entity.unique_id,
@@ -240,8 +267,8 @@ fn spawndata_from_item(entity: GameEntity, pos: &Position, drop: &Drop, for_play
                 .map(|owner| owner.1.unique_id)
                 .filter(|id| *id != for_player.unique_id),
             rarity: 0,
-            source: DroppedItemSource::None,
-            source_id: 0,
+            source,
+            source_id,
         },
         ItemTypeData::Gold { amount } => ItemSpawnData::Gold {
             amount,
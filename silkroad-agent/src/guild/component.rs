@@ -0,0 +1,193 @@
+use crate::db::guild::DbGuild;
+use bevy_ecs::prelude::*;
+use bevy_time::{Timer, TimerMode};
+use silkroad_protocol::guild::{GuildInfo, GuildMemberData, GuildRank};
+use std::collections::HashMap;
+use tokio::sync::oneshot::Receiver;
+
+pub(crate) const MAX_GUILD_MEMBERS: usize = 50;
+const INVITATION_TIMEOUT_SECONDS: f32 = 10.0;
+
+pub(crate) fn rank_from_db(rank: i16) -> GuildRank {
+    match rank {
+        0 => GuildRank::Master,
+        1 => GuildRank::Officer,
+        _ => GuildRank::Member,
+    }
+}
+
+pub(crate) fn rank_to_db(rank: GuildRank) -> i16 {
+    match rank {
+        GuildRank::Master => 0,
+        GuildRank::Officer => 1,
+        GuildRank::Member => 2,
+    }
+}
+
+/// Checks if a member of the given rank may kick a member of the `target` rank. The master may kick anyone, officers
+/// may only kick regular members.
+pub(crate) fn may_kick(rank: GuildRank, target: GuildRank) -> bool {
+    match rank {
+        GuildRank::Master => target != GuildRank::Master,
+        GuildRank::Officer => target == GuildRank::Member,
+        GuildRank::Member => false,
+    }
+}
+
+pub(crate) fn may_manage(rank: GuildRank) -> bool {
+    matches!(rank, GuildRank::Master | GuildRank::Officer)
+}
+
+#[derive(Clone)]
+pub(crate) struct GuildMember {
+    pub(crate) character_id: u32,
+    pub(crate) name: String,
+    pub(crate) level: u8,
+    pub(crate) rank: GuildRank,
+}
+
+impl GuildMember {
+    pub(crate) fn as_protocol(&self, online: bool) -> GuildMemberData {
+        GuildMemberData {
+            character_id: self.character_id,
+            name: self.name.clone(),
+            rank: self.rank,
+            level: self.level,
+            online,
+        }
+    }
+}
+
+pub(crate) struct Guild {
+    pub(crate) id: u32,
+    pub(crate) name: String,
+    pub(crate) notice_title: String,
+    pub(crate) notice: String,
+    pub(crate) members: Vec<GuildMember>,
+}
+
+impl Guild {
+    fn from_db(guild: &DbGuild) -> Self {
+        Guild {
+            id: guild.guild.id as u32,
+            name: guild.guild.name.clone(),
+            notice_title: guild.guild.notice_title.clone(),
+            notice: guild.guild.notice.clone(),
+            members: guild
+                .members
+                .iter()
+                .map(|member| GuildMember {
+                    character_id: member.character_id as u32,
+                    name: member.name.clone(),
+                    level: member.level as u8,
+                    rank: rank_from_db(member.rank),
+                })
+                .collect(),
+        }
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        self.members.len() >= MAX_GUILD_MEMBERS
+    }
+
+    pub(crate) fn member(&self, character_id: u32) -> Option<&GuildMember> {
+        self.members.iter().find(|member| member.character_id == character_id)
+    }
+
+    pub(crate) fn member_mut(&mut self, character_id: u32) -> Option<&mut GuildMember> {
+        self.members
+            .iter_mut()
+            .find(|member| member.character_id == character_id)
+    }
+
+    pub(crate) fn remove(&mut self, character_id: u32) -> Option<GuildMember> {
+        let index = self
+            .members
+            .iter()
+            .position(|member| member.character_id == character_id)?;
+        Some(self.members.remove(index))
+    }
+
+    /// Creates the guild information as shown to the client, where `is_online` decides whether a member is shown as
+    /// online.
+    pub(crate) fn as_protocol<F: Fn(&GuildMember) -> bool>(&self, is_online: F) -> GuildInfo {
+        GuildInfo {
+            id: self.id,
+            name: self.name.clone(),
+            notice_title: self.notice_title.clone(),
+            notice: self.notice.clone(),
+            members: self
+                .members
+                .iter()
+                .map(|member| member.as_protocol(is_online(member)))
+                .collect(),
+        }
+    }
+}
+
+/// All guilds that have at least been loaded once by one of its members joining the game.
+#[derive(Resource, Default)]
+pub(crate) struct Guilds(HashMap<u32, Guild>);
+
+impl Guilds {
+    /// Makes the loaded guild known, unless it was already loaded before, in which case the existing state is kept as
+    /// it is more up to date than what was loaded from the database.
+    pub(crate) fn load(&mut self, guild: &DbGuild) -> &Guild {
+        self.0
+            .entry(guild.guild.id as u32)
+            .or_insert_with(|| Guild::from_db(guild))
+    }
+
+    pub(crate) fn insert(&mut self, guild: Guild) {
+        self.0.insert(guild.id, guild);
+    }
+
+    pub(crate) fn get(&self, id: u32) -> Option<&Guild> {
+        self.0.get(&id)
+    }
+
+    pub(crate) fn get_mut(&mut self, id: u32) -> Option<&mut Guild> {
+        self.0.get_mut(&id)
+    }
+
+    pub(crate) fn has_name(&self, name: &str) -> bool {
+        self.0.values().any(|guild| guild.name.eq_ignore_ascii_case(name))
+    }
+
+    pub(crate) fn disband(&mut self, id: u32) -> Option<Guild> {
+        self.0.remove(&id)
+    }
+}
+
+/// Marks a player as being a member of a guild.
+#[derive(Component, Clone)]
+pub(crate) struct GuildMembership {
+    pub(crate) guild_id: u32,
+    pub(crate) name: String,
+    pub(crate) rank: GuildRank,
+}
+
+/// An invitation into a guild a player has not yet responded to.
+#[derive(Component)]
+pub(crate) struct PendingGuildInvitation {
+    pub(crate) inviter: Entity,
+    pub(crate) guild_id: u32,
+    pub(crate) timeout: Timer,
+}
+
+impl PendingGuildInvitation {
+    pub(crate) fn new(inviter: Entity, guild_id: u32) -> Self {
+        PendingGuildInvitation {
+            inviter,
+            guild_id,
+            timeout: Timer::from_seconds(INVITATION_TIMEOUT_SECONDS, TimerMode::Once),
+        }
+    }
+}
+
+#[derive(Component)]
+#[component(storage = "SparseSet")]
+pub(crate) struct GuildCreation {
+    pub(crate) name: String,
+    pub(crate) task: Receiver<Result<i32, sqlx::Error>>,
+}
@@ -0,0 +1,28 @@
+pub(crate) mod component;
+mod system;
+
+use crate::guild::component::Guilds;
+use crate::guild::system::{
+    expire_guild_invitations, finish_guild_creation, handle_guild_requests, notify_guild_offline, send_guild_on_join,
+    update_guild_names,
+};
+use bevy_app::{App, Plugin, PostUpdate, PreUpdate, Update};
+
+pub(crate) struct GuildPlugin;
+
+impl Plugin for GuildPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Guilds>()
+            .add_systems(PreUpdate, notify_guild_offline)
+            .add_systems(
+                Update,
+                (
+                    handle_guild_requests,
+                    finish_guild_creation,
+                    expire_guild_invitations,
+                    send_guild_on_join,
+                ),
+            )
+            .add_systems(PostUpdate, update_guild_names);
+    }
+}
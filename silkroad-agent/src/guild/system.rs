@@ -0,0 +1,557 @@
+use crate::comp::exp::Leveled;
+use crate::comp::net::Client;
+use crate::comp::player::Player;
+use crate::comp::visibility::Visibility;
+use crate::comp::GameEntity;
+use crate::db::guild::DbGuild;
+use crate::event::{ClientDisconnectedEvent, LoadingFinishedEvent};
+use crate::ext::DbPool;
+use crate::guild::component::{
+    may_kick, may_manage, rank_to_db, Guild, GuildCreation, GuildMember, GuildMembership, Guilds,
+    PendingGuildInvitation,
+};
+use crate::input::{GuildRequest, PlayerInput};
+use crate::server_plugin::ServerId;
+use crate::tasks::TaskCreator;
+use crate::world::EntityLookup;
+use bevy_ecs::prelude::*;
+use bevy_ecs::query::WorldQuery;
+use bevy_ecs::system::SystemParam;
+use bevy_time::Time;
+use silkroad_protocol::guild::{
+    GuildCreateResponse, GuildDisbandResponse, GuildError, GuildInvitation, GuildInviteResponse, GuildKickResponse,
+    GuildLeaveReason, GuildLeaveResponse, GuildNameUpdate, GuildNoticeResponse, GuildRank, GuildRankResponse,
+    GuildResponse, GuildUpdate,
+};
+use silkroad_protocol::ServerPacket;
+use sqlx::PgPool;
+use std::future::Future;
+use tokio::sync::oneshot::error::TryRecvError;
+use tracing::warn;
+
+const MAX_GUILD_NAME_LENGTH: usize = 12;
+
+#[derive(WorldQuery)]
+pub(crate) struct GuildPlayer {
+    entity: Entity,
+    client: &'static Client,
+    game_entity: &'static GameEntity,
+    player: &'static Player,
+    level: &'static Leveled,
+    membership: Option<&'static GuildMembership>,
+    invitation: Option<&'static PendingGuildInvitation>,
+    creation: Option<&'static GuildCreation>,
+}
+
+/// Everything required to persist changes of guilds in the background.
+#[derive(SystemParam)]
+pub(crate) struct GuildDatabase<'w> {
+    task_creator: Res<'w, TaskCreator>,
+    pool: Res<'w, DbPool>,
+    server_id: Res<'w, ServerId>,
+}
+
+impl GuildDatabase<'_> {
+    fn update<F, U>(&self, update: U)
+    where
+        U: FnOnce(PgPool) -> F,
+        F: Future<Output = Result<(), sqlx::Error>> + Send + 'static,
+    {
+        let update = update(PgPool::clone(&self.pool));
+        self.task_creator.spawn(async move {
+            if let Err(e) = update.await {
+                warn!("Could not update guild. {:?}", e);
+            }
+        });
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().count() <= MAX_GUILD_NAME_LENGTH
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn broadcast<T: Into<ServerPacket> + Clone>(players: &Query<GuildPlayer>, guild_id: u32, packet: T) {
+    for player in players.iter() {
+        if player
+            .membership
+            .is_some_and(|membership| membership.guild_id == guild_id)
+        {
+            player.client.send(packet.clone());
+        }
+    }
+}
+
+fn is_online(lookup: &EntityLookup, member: &GuildMember) -> bool {
+    lookup.get_entity_for_name(&member.name).is_some()
+}
+
+fn membership_of(guild: &Guild, rank: GuildRank) -> GuildMembership {
+    GuildMembership {
+        guild_id: guild.id,
+        name: guild.name.clone(),
+        rank,
+    }
+}
+
+pub(crate) fn handle_guild_requests(
+    inputs: Query<(Entity, &PlayerInput)>,
+    players: Query<GuildPlayer>,
+    mut guilds: ResMut<Guilds>,
+    lookup: Res<EntityLookup>,
+    db: GuildDatabase,
+    mut cmd: Commands,
+) {
+    // Guilds that are still being created aren't part of `guilds` yet, but their names and masters are already taken.
+    let mut pending_creations: Vec<(Entity, String)> = players
+        .iter()
+        .filter_map(|player| Some((player.entity, player.creation?.name.clone())))
+        .collect();
+    for (entity, input) in inputs.iter() {
+        let Ok(player) = players.get(entity) else {
+            continue;
+        };
+        let character_id = player.player.character.id;
+        for request in input.guild.iter() {
+            match request {
+                GuildRequest::Create(create) => {
+                    let result = if player.membership.is_some()
+                        || pending_creations.iter().any(|(creator, _)| *creator == entity)
+                    {
+                        Err(GuildError::AlreadyInGuild)
+                    } else if !is_valid_name(&create.name) {
+                        Err(GuildError::InvalidName)
+                    } else if guilds.has_name(&create.name)
+                        || pending_creations
+                            .iter()
+                            .any(|(_, name)| name.eq_ignore_ascii_case(&create.name))
+                    {
+                        Err(GuildError::NameTaken)
+                    } else {
+                        Ok(())
+                    };
+                    if let Err(error) = result {
+                        player.client.send(GuildCreateResponse(GuildResponse::Error(error)));
+                        continue;
+                    }
+
+                    let task = db.task_creator.create_task(DbGuild::create(
+                        db.server_id.0,
+                        create.name.clone(),
+                        character_id,
+                        rank_to_db(GuildRank::Master),
+                        PgPool::clone(&db.pool),
+                    ));
+                    cmd.entity(entity).insert(GuildCreation {
+                        name: create.name.clone(),
+                        task,
+                    });
+                    pending_creations.push((entity, create.name.clone()));
+                },
+                GuildRequest::Disband => {
+                    let Some(membership) = player.membership else {
+                        player
+                            .client
+                            .send(GuildDisbandResponse(GuildResponse::Error(GuildError::NotInGuild)));
+                        continue;
+                    };
+                    if membership.rank != GuildRank::Master {
+                        player
+                            .client
+                            .send(GuildDisbandResponse(GuildResponse::Error(GuildError::InsufficientRank)));
+                        continue;
+                    }
+
+                    broadcast(&players, membership.guild_id, GuildUpdate::Disbanded);
+                    for member in players.iter() {
+                        if member
+                            .membership
+                            .is_some_and(|other| other.guild_id == membership.guild_id)
+                        {
+                            cmd.entity(member.entity).remove::<GuildMembership>();
+                        }
+                    }
+                    guilds.disband(membership.guild_id);
+                    let guild_id = membership.guild_id;
+                    db.update(|pool| DbGuild::disband(guild_id, pool));
+                    player.client.send(GuildDisbandResponse(GuildResponse::Success));
+                },
+                GuildRequest::Leave => {
+                    let Some((membership, guild)) = player
+                        .membership
+                        .and_then(|membership| Some((membership, guilds.get_mut(membership.guild_id)?)))
+                    else {
+                        player
+                            .client
+                            .send(GuildLeaveResponse(GuildResponse::Error(GuildError::NotInGuild)));
+                        continue;
+                    };
+                    if membership.rank == GuildRank::Master {
+                        player
+                            .client
+                            .send(GuildLeaveResponse(GuildResponse::Error(GuildError::MasterCannotLeave)));
+                        continue;
+                    }
+
+                    guild.remove(character_id);
+                    broadcast(
+                        &players,
+                        guild.id,
+                        GuildUpdate::MemberLeft {
+                            character_id,
+                            reason: GuildLeaveReason::Left,
+                        },
+                    );
+                    cmd.entity(entity).remove::<GuildMembership>();
+                    db.update(|pool| DbGuild::remove_member(character_id, pool));
+                    player.client.send(GuildLeaveResponse(GuildResponse::Success));
+                },
+                GuildRequest::Invite(invite) => {
+                    let Some(guild) = player.membership.and_then(|membership| guilds.get(membership.guild_id)) else {
+                        player
+                            .client
+                            .send(GuildInviteResponse(GuildResponse::Error(GuildError::NotInGuild)));
+                        continue;
+                    };
+
+                    let target = lookup
+                        .get_entity_for_id(invite.target)
+                        .filter(|target| *target != entity)
+                        .and_then(|target| players.get(target).ok());
+                    let result = match target {
+                        _ if !player.membership.is_some_and(|membership| may_manage(membership.rank)) => {
+                            Err(GuildError::InsufficientRank)
+                        },
+                        _ if guild.is_full() => Err(GuildError::GuildFull),
+                        None => Err(GuildError::InvalidTarget),
+                        Some(target) if target.membership.is_some() => Err(GuildError::AlreadyInGuild),
+                        Some(target) if target.invitation.is_some() => Err(GuildError::InvitationPending),
+                        Some(target) => {
+                            target.client.send(GuildInvitation {
+                                inviter: player.game_entity.unique_id,
+                                guild_name: guild.name.clone(),
+                            });
+                            cmd.entity(target.entity)
+                                .insert(PendingGuildInvitation::new(entity, guild.id));
+                            Ok(())
+                        },
+                    };
+                    if let Err(error) = result {
+                        player.client.send(GuildInviteResponse(GuildResponse::Error(error)));
+                    }
+                },
+                GuildRequest::Reply(reply) => {
+                    let Some(invitation) = player.invitation else {
+                        continue;
+                    };
+                    cmd.entity(entity).remove::<PendingGuildInvitation>();
+
+                    let result = match guilds.get_mut(invitation.guild_id) {
+                        _ if !reply.accepted => Err(GuildError::InvitationDeclined),
+                        _ if player.membership.is_some() => Err(GuildError::AlreadyInGuild),
+                        None => Err(GuildError::NotInGuild),
+                        Some(guild) if guild.is_full() => Err(GuildError::GuildFull),
+                        Some(guild) => {
+                            let member = GuildMember {
+                                character_id,
+                                name: player.player.character.name.clone(),
+                                level: player.level.current_level(),
+                                rank: GuildRank::Member,
+                            };
+                            broadcast(&players, guild.id, GuildUpdate::MemberJoined(member.as_protocol(true)));
+                            guild.members.push(member);
+                            cmd.entity(entity).insert(membership_of(guild, GuildRank::Member));
+                            player
+                                .client
+                                .send(guild.as_protocol(|member| is_online(&lookup, member)));
+
+                            let guild_id = guild.id;
+                            db.update(|pool| {
+                                DbGuild::add_member(guild_id, character_id, rank_to_db(GuildRank::Member), pool)
+                            });
+                            Ok(())
+                        },
+                    };
+
+                    if let Ok(inviter) = players.get(invitation.inviter) {
+                        let response = match result {
+                            Ok(_) => GuildResponse::Success,
+                            Err(error) => GuildResponse::Error(error),
+                        };
+                        inviter.client.send(GuildInviteResponse(response));
+                    }
+                },
+                GuildRequest::Kick(kick) => {
+                    let Some((membership, guild)) = player
+                        .membership
+                        .and_then(|membership| Some((membership, guilds.get_mut(membership.guild_id)?)))
+                    else {
+                        player
+                            .client
+                            .send(GuildKickResponse(GuildResponse::Error(GuildError::NotInGuild)));
+                        continue;
+                    };
+                    let Some(target) = guild
+                        .member(kick.character_id)
+                        .filter(|_| kick.character_id != character_id)
+                    else {
+                        player
+                            .client
+                            .send(GuildKickResponse(GuildResponse::Error(GuildError::InvalidTarget)));
+                        continue;
+                    };
+                    if !may_kick(membership.rank, target.rank) {
+                        player
+                            .client
+                            .send(GuildKickResponse(GuildResponse::Error(GuildError::InsufficientRank)));
+                        continue;
+                    }
+
+                    let target = guild.remove(kick.character_id).expect("Member should exist");
+                    broadcast(
+                        &players,
+                        guild.id,
+                        GuildUpdate::MemberLeft {
+                            character_id: target.character_id,
+                            reason: GuildLeaveReason::Kicked,
+                        },
+                    );
+                    if let Some(target_entity) = lookup.get_entity_for_name(&target.name) {
+                        cmd.entity(target_entity).remove::<GuildMembership>();
+                    }
+                    db.update(|pool| DbGuild::remove_member(target.character_id, pool));
+                    player.client.send(GuildKickResponse(GuildResponse::Success));
+                },
+                GuildRequest::Notice(notice) => {
+                    let Some((membership, guild)) = player
+                        .membership
+                        .and_then(|membership| Some((membership, guilds.get_mut(membership.guild_id)?)))
+                    else {
+                        player
+                            .client
+                            .send(GuildNoticeResponse(GuildResponse::Error(GuildError::NotInGuild)));
+                        continue;
+                    };
+                    if !may_manage(membership.rank) {
+                        player
+                            .client
+                            .send(GuildNoticeResponse(GuildResponse::Error(GuildError::InsufficientRank)));
+                        continue;
+                    }
+
+                    guild.notice_title = notice.title.clone();
+                    guild.notice = notice.notice.clone();
+                    broadcast(
+                        &players,
+                        guild.id,
+                        GuildUpdate::NoticeChanged {
+                            title: notice.title.clone(),
+                            notice: notice.notice.clone(),
+                        },
+                    );
+                    let (guild_id, title, text) = (guild.id, notice.title.clone(), notice.notice.clone());
+                    db.update(|pool| DbGuild::update_notice(guild_id, title, text, pool));
+                    player.client.send(GuildNoticeResponse(GuildResponse::Success));
+                },
+                GuildRequest::Rank(change) => {
+                    let Some((membership, guild)) = player
+                        .membership
+                        .and_then(|membership| Some((membership, guilds.get_mut(membership.guild_id)?)))
+                    else {
+                        player
+                            .client
+                            .send(GuildRankResponse(GuildResponse::Error(GuildError::NotInGuild)));
+                        continue;
+                    };
+                    if membership.rank != GuildRank::Master || change.rank == GuildRank::Master {
+                        player
+                            .client
+                            .send(GuildRankResponse(GuildResponse::Error(GuildError::InsufficientRank)));
+                        continue;
+                    }
+                    let Some(target) = guild
+                        .member_mut(change.character_id)
+                        .filter(|_| change.character_id != character_id)
+                    else {
+                        player
+                            .client
+                            .send(GuildRankResponse(GuildResponse::Error(GuildError::InvalidTarget)));
+                        continue;
+                    };
+
+                    target.rank = change.rank;
+                    if let Some(target_entity) = lookup.get_entity_for_name(&target.name) {
+                        cmd.entity(target_entity).insert(membership_of(guild, change.rank));
+                    }
+                    broadcast(
+                        &players,
+                        guild.id,
+                        GuildUpdate::RankChanged {
+                            character_id: change.character_id,
+                            rank: change.rank,
+                        },
+                    );
+                    let (target_id, rank) = (change.character_id, rank_to_db(change.rank));
+                    db.update(|pool| DbGuild::update_rank(target_id, rank, pool));
+                    player.client.send(GuildRankResponse(GuildResponse::Success));
+                },
+            }
+        }
+    }
+}
+
+pub(crate) fn finish_guild_creation(
+    mut query: Query<(Entity, &Client, &Player, &Leveled, &mut GuildCreation)>,
+    mut guilds: ResMut<Guilds>,
+    mut cmd: Commands,
+) {
+    for (entity, client, player, level, mut creation) in query.iter_mut() {
+        match creation.task.try_recv() {
+            Ok(Ok(id)) => {
+                let guild = Guild {
+                    id: id as u32,
+                    name: creation.name.clone(),
+                    notice_title: String::new(),
+                    notice: String::new(),
+                    members: vec![GuildMember {
+                        character_id: player.character.id,
+                        name: player.character.name.clone(),
+                        level: level.current_level(),
+                        rank: GuildRank::Master,
+                    }],
+                };
+                cmd.entity(entity).insert(membership_of(&guild, GuildRank::Master));
+                client.send(GuildCreateResponse(GuildResponse::Success));
+                client.send(guild.as_protocol(|_| true));
+                guilds.insert(guild);
+            },
+            Ok(Err(e)) => {
+                let error = match e.as_database_error() {
+                    Some(db_error) if db_error.is_unique_violation() => GuildError::NameTaken,
+                    _ => {
+                        warn!(id = ?client.0.id(), "Could not create guild. {:?}", e);
+                        GuildError::InvalidName
+                    },
+                };
+                client.send(GuildCreateResponse(GuildResponse::Error(error)));
+            },
+            Err(TryRecvError::Empty) => continue,
+            Err(e) => {
+                warn!(id = ?client.0.id(), "Error when creating guild. {:?}", e);
+            },
+        }
+        cmd.entity(entity).remove::<GuildCreation>();
+    }
+}
+
+pub(crate) fn expire_guild_invitations(
+    mut query: Query<(Entity, &mut PendingGuildInvitation)>,
+    clients: Query<&Client>,
+    time: Res<Time>,
+    mut cmd: Commands,
+) {
+    for (entity, mut invitation) in query.iter_mut() {
+        if !invitation.timeout.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        cmd.entity(entity).remove::<PendingGuildInvitation>();
+        if let Ok(inviter) = clients.get(invitation.inviter) {
+            inviter.send(GuildInviteResponse(GuildResponse::Error(
+                GuildError::InvitationDeclined,
+            )));
+        }
+    }
+}
+
+pub(crate) fn send_guild_on_join(
+    mut events: EventReader<LoadingFinishedEvent>,
+    players: Query<GuildPlayer>,
+    guilds: Res<Guilds>,
+    lookup: Res<EntityLookup>,
+) {
+    for event in events.iter() {
+        let Ok(player) = players.get(event.0) else {
+            continue;
+        };
+        let Some(guild) = player.membership.and_then(|membership| guilds.get(membership.guild_id)) else {
+            continue;
+        };
+
+        player
+            .client
+            .send(guild.as_protocol(|member| is_online(&lookup, member)));
+        broadcast(
+            &players,
+            guild.id,
+            GuildUpdate::MemberStatus {
+                character_id: player.player.character.id,
+                online: true,
+            },
+        );
+    }
+}
+
+pub(crate) fn notify_guild_offline(mut events: EventReader<ClientDisconnectedEvent>, players: Query<GuildPlayer>) {
+    for event in events.iter() {
+        let Ok(player) = players.get(event.0) else {
+            continue;
+        };
+        let Some(membership) = player.membership else {
+            continue;
+        };
+
+        broadcast(
+            &players,
+            membership.guild_id,
+            GuildUpdate::MemberStatus {
+                character_id: player.player.character.id,
+                online: false,
+            },
+        );
+    }
+}
+
+fn send_guild_name(visibility: &Visibility, clients: &Query<&Client>, update: GuildNameUpdate) {
+    for other in visibility.entities_in_radius.iter() {
+        if let Ok(client) = clients.get(other.0) {
+            client.send(update.clone());
+        }
+    }
+}
+
+/// Lets surrounding players know when a player joined or left a guild, such that the guild name above them is kept
+/// up to date.
+pub(crate) fn update_guild_names(
+    changed: Query<(&GameEntity, &Visibility, &GuildMembership), Changed<GuildMembership>>,
+    players: Query<(&GameEntity, &Visibility), Without<GuildMembership>>,
+    mut removed: RemovedComponents<GuildMembership>,
+    clients: Query<&Client>,
+) {
+    for (game_entity, visibility, membership) in changed.iter() {
+        send_guild_name(
+            visibility,
+            &clients,
+            GuildNameUpdate {
+                unique_id: game_entity.unique_id,
+                guild_id: membership.guild_id,
+                name: membership.name.clone(),
+            },
+        );
+    }
+
+    for entity in removed.iter() {
+        if let Ok((game_entity, visibility)) = players.get(entity) {
+            send_guild_name(
+                visibility,
+                &clients,
+                GuildNameUpdate {
+                    unique_id: game_entity.unique_id,
+                    guild_id: 0,
+                    name: String::new(),
+                },
+            );
+        }
+    }
+}
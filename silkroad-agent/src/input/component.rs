@@ -1,13 +1,25 @@
 use bevy_ecs::prelude::*;
 use silkroad_game_base::StatType;
+use silkroad_protocol::alchemy::AlchemyReinforceRequest;
 use silkroad_protocol::auth::{AuthRequest, LogoutRequest};
 use silkroad_protocol::character::{CharacterJoinRequest, CharacterListRequestAction};
 use silkroad_protocol::chat::ChatMessage;
 use silkroad_protocol::combat::PerformAction;
+use silkroad_protocol::exchange::{ExchangeInvitationReply, ExchangeRequest};
 use silkroad_protocol::gm::GmCommand;
-use silkroad_protocol::inventory::InventoryOperation;
+use silkroad_protocol::guild::{
+    GuildCreateRequest, GuildInvitationReply, GuildInviteRequest, GuildKickRequest, GuildNoticeRequest,
+    GuildRankRequest,
+};
+use silkroad_protocol::inventory::{InventoryOperation, UseItemRequest};
+use silkroad_protocol::party::{
+    PartyCreateRequest, PartyInvitationReply, PartyInviteRequest, PartyKickRequest, PartyLeaderTransferRequest,
+};
 use silkroad_protocol::skill::{LearnSkill, LevelUpMastery};
-use silkroad_protocol::world::{MovementTarget, Rotation, TargetEntity, UnTargetEntity};
+use silkroad_protocol::world::{
+    AddFriend, CreateFriendGroup, DeleteFriend, FriendRequestReply, MovementTarget, OpenNpcTalk, ResurrectKind,
+    Rotation, TargetEntity, UnTargetEntity,
+};
 use std::mem;
 
 #[derive(Component, Default)]
@@ -15,15 +27,23 @@ pub(crate) struct PlayerInput {
     pub logout: Option<LogoutRequest>,
     pub target: Option<TargetEntity>,
     pub untarget: Option<UnTargetEntity>,
+    pub npc_talk: Option<OpenNpcTalk>,
     pub chat: Vec<ChatMessage>,
     pub action: Option<PerformAction>,
     pub movement: Option<MovementTarget>,
     pub rotation: Option<Rotation>,
     pub inventory: Option<InventoryOperation>,
+    pub use_item: Option<UseItemRequest>,
+    pub alchemy: Option<AlchemyReinforceRequest>,
     pub gm: Option<GmCommand>,
     pub mastery: Option<LevelUpMastery>,
     pub skill_add: Option<LearnSkill>,
     pub increase_stats: Vec<StatType>,
+    pub resurrect: Option<ResurrectKind>,
+    pub party: Vec<PartyRequest>,
+    pub exchange: Vec<ExchangeAction>,
+    pub friend: Vec<FriendAction>,
+    pub guild: Vec<GuildRequest>,
 }
 
 impl PlayerInput {
@@ -32,6 +52,41 @@ impl PlayerInput {
     }
 }
 
+pub(crate) enum PartyRequest {
+    Create(PartyCreateRequest),
+    Invite(PartyInviteRequest),
+    Leave,
+    Kick(PartyKickRequest),
+    TransferLeader(PartyLeaderTransferRequest),
+    Reply(PartyInvitationReply),
+}
+
+pub(crate) enum ExchangeAction {
+    Request(ExchangeRequest),
+    Reply(ExchangeInvitationReply),
+    Confirm,
+    Approve,
+    Cancel,
+}
+
+pub(crate) enum FriendAction {
+    Add(AddFriend),
+    Reply(FriendRequestReply),
+    CreateGroup(CreateFriendGroup),
+    Delete(DeleteFriend),
+}
+
+pub(crate) enum GuildRequest {
+    Create(GuildCreateRequest),
+    Disband,
+    Leave,
+    Invite(GuildInviteRequest),
+    Kick(GuildKickRequest),
+    Reply(GuildInvitationReply),
+    Notice(GuildNoticeRequest),
+    Rank(GuildRankRequest),
+}
+
 #[derive(Component, Default)]
 pub(crate) struct LoginInput {
     pub list: Vec<CharacterListRequestAction>,
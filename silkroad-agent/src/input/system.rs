@@ -1,7 +1,7 @@
 use crate::comp::net::{Client, LastAction};
 use crate::config::GameConfig;
 use crate::event::{ClientDisconnectedEvent, LoadingFinishedEvent};
-use crate::input::{LoginInput, PlayerInput};
+use crate::input::{ExchangeAction, FriendAction, GuildRequest, LoginInput, PartyRequest, PlayerInput};
 use crate::mall::event::MallOpenRequestEvent;
 use bevy_ecs::prelude::*;
 use bevy_time::Time;
@@ -58,6 +58,7 @@ pub(crate) fn receive_game_inputs(
                         ClientPacket::UnTargetEntity(untarget) => {
                             input.untarget = Some(untarget);
                         },
+                        ClientPacket::OpenNpcTalk(talk) => input.npc_talk = Some(talk),
                         ClientPacket::PerformAction(action) => {
                             input.action = Some(action);
                         },
@@ -70,12 +71,15 @@ pub(crate) fn receive_game_inputs(
                         ClientPacket::InventoryOperation(inventory) => {
                             input.inventory = Some(inventory);
                         },
+                        ClientPacket::UseItemRequest(use_item) => input.use_item = Some(use_item),
+                        ClientPacket::AlchemyReinforceRequest(reinforce) => input.alchemy = Some(reinforce),
                         ClientPacket::ConsignmentList(_) => {
                             client.send(ConsignmentResponse::success_empty());
                         },
-                        ClientPacket::AddFriend(_) => {},
-                        ClientPacket::CreateFriendGroup(_) => {},
-                        ClientPacket::DeleteFriend(_) => {},
+                        ClientPacket::AddFriend(add) => input.friend.push(FriendAction::Add(add)),
+                        ClientPacket::CreateFriendGroup(create) => input.friend.push(FriendAction::CreateGroup(create)),
+                        ClientPacket::DeleteFriend(delete) => input.friend.push(FriendAction::Delete(delete)),
+                        ClientPacket::FriendRequestReply(reply) => input.friend.push(FriendAction::Reply(reply)),
                         ClientPacket::UpdateGameGuide(UpdateGameGuide(val)) => {
                             client.send(GameGuideResponse::Success(val));
                         },
@@ -88,6 +92,30 @@ pub(crate) fn receive_game_inputs(
                         ClientPacket::LearnSkill(skill) => input.skill_add = Some(skill),
                         ClientPacket::IncreaseStr(_) => input.increase_stats.push(StatType::STR),
                         ClientPacket::IncreaseInt(_) => input.increase_stats.push(StatType::INT),
+                        ClientPacket::ResurrectRequest(request) => input.resurrect = Some(request.0),
+                        ClientPacket::PartyCreateRequest(create) => input.party.push(PartyRequest::Create(create)),
+                        ClientPacket::PartyInviteRequest(invite) => input.party.push(PartyRequest::Invite(invite)),
+                        ClientPacket::PartyLeaveRequest(_) => input.party.push(PartyRequest::Leave),
+                        ClientPacket::PartyKickRequest(kick) => input.party.push(PartyRequest::Kick(kick)),
+                        ClientPacket::PartyLeaderTransferRequest(transfer) => {
+                            input.party.push(PartyRequest::TransferLeader(transfer))
+                        },
+                        ClientPacket::PartyInvitationReply(reply) => input.party.push(PartyRequest::Reply(reply)),
+                        ClientPacket::ExchangeRequest(request) => input.exchange.push(ExchangeAction::Request(request)),
+                        ClientPacket::ExchangeInvitationReply(reply) => {
+                            input.exchange.push(ExchangeAction::Reply(reply))
+                        },
+                        ClientPacket::ExchangeConfirmRequest(_) => input.exchange.push(ExchangeAction::Confirm),
+                        ClientPacket::ExchangeApproveRequest(_) => input.exchange.push(ExchangeAction::Approve),
+                        ClientPacket::ExchangeCancelRequest(_) => input.exchange.push(ExchangeAction::Cancel),
+                        ClientPacket::GuildCreateRequest(create) => input.guild.push(GuildRequest::Create(create)),
+                        ClientPacket::GuildDisbandRequest(_) => input.guild.push(GuildRequest::Disband),
+                        ClientPacket::GuildLeaveRequest(_) => input.guild.push(GuildRequest::Leave),
+                        ClientPacket::GuildInviteRequest(invite) => input.guild.push(GuildRequest::Invite(invite)),
+                        ClientPacket::GuildKickRequest(kick) => input.guild.push(GuildRequest::Kick(kick)),
+                        ClientPacket::GuildInvitationReply(reply) => input.guild.push(GuildRequest::Reply(reply)),
+                        ClientPacket::GuildNoticeRequest(notice) => input.guild.push(GuildRequest::Notice(notice)),
+                        ClientPacket::GuildRankRequest(rank) => input.guild.push(GuildRequest::Rank(rank)),
                         _ => {},
                     }
                 },
@@ -1,4 +1,6 @@
-use crate::db::character::{CharacterData, CharacterItem, CharacterMastery};
+use crate::db::character::{CharacterData, CharacterItem, CharacterMastery, CharacterSkill};
+use crate::db::friend::{Friend, FriendGroup};
+use crate::db::guild::DbGuild;
 use itertools::Itertools;
 use sqlx::PgPool;
 use std::borrow::Borrow;
@@ -9,6 +11,10 @@ pub struct DbCharacter {
     pub(crate) character_data: CharacterData,
     pub(crate) items: Vec<CharacterItem>,
     pub(crate) masteries: Vec<CharacterMastery>,
+    pub(crate) skills: Vec<CharacterSkill>,
+    pub(crate) friends: Vec<Friend>,
+    pub(crate) friend_groups: Vec<FriendGroup>,
+    pub(crate) guild: Option<DbGuild>,
 }
 
 impl DbCharacter {
@@ -26,17 +32,39 @@ impl DbCharacter {
             .unwrap()
             .into_iter()
             .into_group_map_by(|r| r.character_id);
+        let mut character_skills = CharacterSkill::fetch_for_characters(&character_ids, pool.borrow())
+            .await
+            .unwrap()
+            .into_iter()
+            .into_group_map_by(|r| r.character_id);
+        let mut character_friends = Friend::fetch_for_characters(&character_ids, pool.borrow())
+            .await
+            .unwrap();
+        let mut character_friend_groups = FriendGroup::fetch_for_characters(&character_ids, pool.borrow())
+            .await
+            .unwrap();
+        let mut character_guilds = DbGuild::fetch_for_characters(&character_ids, pool.borrow())
+            .await
+            .unwrap();
 
         let mut all_characters = Vec::new();
 
         for character in characters {
             let items = character_items.remove(&character.id).unwrap_or_default();
             let masteries = character_masteries.remove(&character.id).unwrap_or_default();
+            let skills = character_skills.remove(&character.id).unwrap_or_default();
+            let friends = character_friends.remove(&character.id).unwrap_or_default();
+            let friend_groups = character_friend_groups.remove(&character.id).unwrap_or_default();
+            let guild = character_guilds.remove(&character.id);
 
             all_characters.push(DbCharacter {
                 character_data: character,
                 items,
                 masteries,
+                skills,
+                friends,
+                friend_groups,
+                guild,
             });
         }
 
@@ -116,11 +144,24 @@ impl DbCharacter {
         let id: i32 = result.id;
         for item in character.items.iter() {
             sqlx::query!(
-                "INSERT INTO character_items(character_id, item_obj_id, upgrade_level, slot) VALUES($1, $2, $3, $4)",
+                "INSERT INTO character_items(character_id, item_obj_id, upgrade_level, slot, amount) VALUES($1, $2, $3, $4, $5)",
                 id,
                 item.item_obj_id,
                 item.upgrade_level,
-                item.slot
+                item.slot,
+                item.amount
+            )
+            .execute(pool.borrow())
+            .await
+            .unwrap();
+        }
+
+        for mastery in character.masteries.iter() {
+            sqlx::query!(
+                "INSERT INTO character_masteries(character_id, mastery_id, level) VALUES($1, $2, $3)",
+                id,
+                mastery.mastery_id,
+                mastery.level
             )
             .execute(pool.borrow())
             .await
@@ -5,9 +5,11 @@ use crate::comp::player::{Player, PlayerBundle};
 use crate::comp::pos::Position;
 use crate::comp::visibility::Visibility;
 use crate::comp::{GameEntity, Playing};
-use crate::config::GameConfig;
-use crate::db::character::{CharacterData, CharacterItem};
+use crate::config::{GameConfig, StartingSetup};
+use crate::db::character::{CharacterData, CharacterItem, CharacterMastery};
 use crate::ext::{DbPool, EntityIdPool};
+use crate::friend::component::{FriendList, FriendRequests};
+use crate::guild::component::{GuildMembership, Guilds};
 use crate::input::LoginInput;
 use crate::login::character_loader::DbCharacter;
 use crate::login::job_distribution::JobDistribution;
@@ -21,8 +23,9 @@ use crate::world::WorldData;
 use bevy_ecs::prelude::*;
 use cgmath::Vector3;
 use chrono::{TimeZone, Utc};
+use silkroad_data::characterdata::RefCharacterData;
 use silkroad_data::DataEntry;
-use silkroad_game_base::{Heading, LocalPosition};
+use silkroad_game_base::{Heading, LocalPosition, Race};
 use silkroad_protocol::auth::{AuthResponse, AuthResult, AuthResultError};
 use silkroad_protocol::character::{
     CharacterJoinResponse, CharacterListAction, CharacterListContent, CharacterListError, CharacterListRequestAction,
@@ -31,7 +34,7 @@ use silkroad_protocol::character::{
 use silkroad_protocol::inventory::{InventoryItemBindingData, InventoryItemContentData, InventoryItemData, RentInfo};
 use silkroad_protocol::world::{
     ActionState, AliveState, BodyState, CharacterSpawn, CharacterSpawnEnd, CharacterSpawnStart, EntityState, JobType,
-    MasteryData,
+    MasteryData, SkillData,
 };
 use silkroad_protocol::SilkroadTime;
 use tracing::debug;
@@ -65,16 +68,23 @@ pub(crate) fn handle_list_request(
                         ));
                     }
 
+                    let Some(race) = WorldData::characters().find_id(*ref_id).and_then(race_of) else {
+                        debug!(id = ?client.0.id(), ref_id, "Tried to create character with invalid character type.");
+                        client.send(CharacterListResponse::new(
+                            CharacterListAction::Create,
+                            CharacterListResult::error(CharacterListError::InvalidCharacterData),
+                        ));
+                        continue;
+                    };
+
                     let character = create_character_from(
                         playing.0.id,
                         server_id.0,
                         character_name.clone(),
                         *ref_id,
                         *scale,
-                        *chest,
-                        *pants,
-                        *boots,
-                        *weapon,
+                        [*chest, *pants, *boots, *weapon],
+                        settings.starting.for_race(race),
                     );
                     let task = task_creator.create_task(DbCharacter::create_character(character, pool.clone()));
                     cmd.entity(entity).insert(CharacterCreate(task));
@@ -146,6 +156,7 @@ pub(crate) fn handle_join(
     query: Query<(Entity, &Client, &LoginInput, &CharacterSelect, &Playing)>,
     mut cmd: Commands,
     mut allocator: ResMut<EntityIdPool>,
+    mut guilds: ResMut<Guilds>,
     settings: Res<GameConfig>,
 ) {
     for (entity, client, input, character_list, playing) in query.iter() {
@@ -157,8 +168,9 @@ pub(crate) fn handle_join(
                         .find(|char| char.character_data.charname == join.character_name)
                         .unwrap();
 
-                    let Some(character_data) =
-                        WorldData::characters().find_id(character.character_data.character_type as u32)
+                    let Some((character_data, race)) = WorldData::characters()
+                        .find_id(character.character_data.character_type as u32)
+                        .and_then(|character_data| Some((character_data, race_of(character_data)?)))
                     else {
                         client.send(CharacterJoinResponse::error(CharacterListError::FailedToJoinWorld));
                         continue;
@@ -169,7 +181,7 @@ pub(crate) fn handle_join(
                         continue;
                     }
 
-                    let mut player = Player::from_db_data(playing.0.clone(), &character.character_data);
+                    let mut player = Player::from_db_data(playing.0.clone(), &character.character_data, race);
                     let inventory =
                         PlayerInventory::from_db(&character.items, 45, character.character_data.gold as u64);
 
@@ -185,6 +197,15 @@ pub(crate) fn handle_join(
                             )
                         })
                         .collect::<Vec<_>>();
+                    player.character.skills = character
+                        .skills
+                        .iter()
+                        .map(|skill| {
+                            WorldData::skills()
+                                .find_id(skill.skill_id as u32)
+                                .expect("Skill should exist")
+                        })
+                        .collect::<Vec<_>>();
 
                     let data = &character.character_data;
 
@@ -214,8 +235,23 @@ pub(crate) fn handle_join(
                             position.clone(),
                             Visibility::with_radius(500.),
                         ))
+                        .insert((
+                            FriendList::from_db(&character.friends, &character.friend_groups),
+                            FriendRequests::default(),
+                        ))
                         .remove::<CharacterSelect>()
                         .remove::<LoginInput>();
+
+                    if let Some(db_guild) = &character.guild {
+                        let guild = guilds.load(db_guild);
+                        if let Some(member) = guild.member(data.id as u32) {
+                            cmd.entity(entity).insert(GuildMembership {
+                                guild_id: guild.id,
+                                name: guild.name.clone(),
+                                rank: member.rank,
+                            });
+                        }
+                    }
                 },
                 None => {
                     // TODO
@@ -369,7 +405,12 @@ fn send_spawn(
                 level: *level,
             })
             .collect(),
-        Vec::new(),
+        player
+            .character
+            .skills
+            .iter()
+            .map(|skill| SkillData::new(skill.ref_id, true))
+            .collect(),
         Vec::new(),
         Vec::new(),
         entity.unique_id,
@@ -401,16 +442,24 @@ fn send_spawn(
     client.send(CharacterSpawnEnd);
 }
 
+/// Determines the race of a player character from its reference data.
+fn race_of(character: &RefCharacterData) -> Option<Race> {
+    character.common.country.race().map(Race::from)
+}
+
+/// The inventory slots of the chest, pants, boots and weapon a new character selected, in that order.
+const STARTING_EQUIPMENT_SLOTS: [i16; 4] = [1, 4, 5, 6];
+/// The first inventory slot that isn't an equipment slot.
+const FIRST_INVENTORY_SLOT: i16 = 13;
+
 pub(crate) fn create_character_from(
     user_id: i32,
     server_id: u16,
     character_name: String,
     ref_id: u32,
     scale: u8,
-    chest: u32,
-    pants: u32,
-    boots: u32,
-    weapon: u32,
+    equipment: [u32; 4],
+    setup: &StartingSetup,
 ) -> DbCharacter {
     let character = CharacterData {
         id: 0,
@@ -430,60 +479,55 @@ pub(crate) fn create_character_from(
         current_hp: 200,
         current_mp: 200,
         deletion_end: None,
-        x: 739.,
-        y: 37.4519,
-        z: 1757.,
+        x: setup.x,
+        y: setup.y,
+        z: setup.z,
         rotation: 0,
-        region: 24998,
+        region: setup.region as i16,
         berserk_points: 0,
-        gold: 5000000,
+        gold: setup.gold as i64,
         beginner_mark: true,
         gm: false,
         last_logout: None,
     };
 
-    let items = vec![
-        CharacterItem {
-            id: 0,
-            character_id: 0,
-            item_obj_id: chest as i32,
-            upgrade_level: 0,
-            variance: None,
-            slot: 1,
-            amount: 1,
-        },
-        CharacterItem {
-            id: 0,
-            character_id: 0,
-            item_obj_id: pants as i32,
-            upgrade_level: 0,
-            variance: None,
-            slot: 4,
-            amount: 1,
-        },
-        CharacterItem {
+    let equipment = STARTING_EQUIPMENT_SLOTS
+        .into_iter()
+        .zip(equipment)
+        .map(|(slot, ref_id)| (slot, ref_id, 1));
+    let additional_items = (FIRST_INVENTORY_SLOT..)
+        .zip(setup.items.iter())
+        .map(|(slot, item)| (slot, item.ref_id, item.amount as i16));
+    let items = equipment
+        .chain(additional_items)
+        .map(|(slot, ref_id, amount)| CharacterItem {
             id: 0,
             character_id: 0,
-            item_obj_id: boots as i32,
+            item_obj_id: ref_id as i32,
             upgrade_level: 0,
             variance: None,
-            slot: 5,
-            amount: 1,
-        },
-        CharacterItem {
-            id: 0,
+            slot,
+            amount,
+        })
+        .collect();
+
+    let masteries = setup
+        .masteries
+        .iter()
+        .map(|mastery| CharacterMastery {
             character_id: 0,
-            item_obj_id: weapon as i32,
-            upgrade_level: 0,
-            variance: None,
-            slot: 6,
-            amount: 1,
-        },
-    ];
-    // TODO: properly pick masteries
+            mastery_id: *mastery as i32,
+            level: 0,
+        })
+        .collect();
+
     DbCharacter {
         character_data: character,
         items,
-        masteries: vec![],
+        masteries,
+        skills: vec![],
+        friends: vec![],
+        friend_groups: vec![],
+        guild: None,
     }
 }
@@ -4,14 +4,19 @@ mod comp;
 mod config;
 mod db;
 mod event;
+mod exchange;
 mod ext;
+mod friend;
 mod game;
+mod guild;
 mod input;
 mod login;
 mod mall;
 mod net;
+mod party;
 mod population;
 mod server_plugin;
+mod status;
 mod sync;
 mod tasks;
 mod world;
@@ -19,14 +24,19 @@ mod world;
 use crate::agent::AgentPlugin;
 use crate::config::get_config;
 use crate::db::server::ServerRegistration;
+use crate::exchange::ExchangePlugin;
 use crate::ext::DbPool;
+use crate::friend::FriendPlugin;
 use crate::game::GamePlugin;
+use crate::guild::GuildPlugin;
 use crate::input::ReceivePlugin;
 use crate::login::LoginPlugin;
 use crate::mall::MallPlugin;
 use crate::net::NetworkPlugin;
+use crate::party::PartyPlugin;
 use crate::population::{CapacityController, LoginQueue};
 use crate::server_plugin::ServerPlugin;
+use crate::status::StatusEffectPlugin;
 use crate::sync::SynchronizationPlugin;
 use crate::tasks::TaskCreator;
 use crate::world::WorldPlugin;
@@ -116,5 +126,10 @@ fn main() {
         .add_plugins(LoginPlugin::new(queue))
         .add_plugins(GamePlugin)
         .add_plugins(MallPlugin)
+        .add_plugins(PartyPlugin)
+        .add_plugins(ExchangePlugin)
+        .add_plugins(FriendPlugin)
+        .add_plugins(GuildPlugin)
+        .add_plugins(StatusEffectPlugin)
         .run();
 }
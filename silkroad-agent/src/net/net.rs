@@ -1,12 +1,13 @@
 use crate::comp::net::{Client, LastAction};
-use crate::comp::player::Player;
 use crate::db::character::CharacterData;
 use crate::event::{ClientConnectedEvent, ClientDisconnectedEvent};
 use crate::ext::{DbPool, ServerResource};
+use crate::game::persistence::{CharacterSaves, PersistedCharacter};
 use crate::input::LoginInput;
 use crate::tasks::TaskCreator;
 use bevy_ecs::prelude::*;
 use bevy_time::Time;
+use sqlx::PgPool;
 use std::time::Instant;
 use tracing::debug;
 
@@ -36,14 +37,21 @@ pub(crate) fn disconnected(
     mut cmd: Commands,
     task_creator: Res<TaskCreator>,
     pool: Res<DbPool>,
-    query: Query<&Player>,
+    saves: Res<CharacterSaves>,
+    query: Query<PersistedCharacter>,
 ) {
     for event in events.iter() {
         let entity = event.0;
         debug!("Handling client disconnect.");
-        if let Ok(player) = query.get(event.0) {
-            let id = player.character.id;
-            task_creator.spawn(CharacterData::update_last_played_of(id, pool.clone()));
+        if let Ok(character) = query.get(event.0) {
+            let snapshot = character.snapshot();
+            let id = snapshot.id;
+            let save = saves.save(snapshot, PgPool::clone(&pool));
+            let pool = PgPool::clone(&pool);
+            task_creator.spawn(async move {
+                save.await;
+                CharacterData::update_last_played_of(id, pool).await;
+            });
         }
         cmd.entity(entity).despawn();
     }
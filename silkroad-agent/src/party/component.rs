@@ -0,0 +1,145 @@
+use bevy_ecs::prelude::*;
+use bevy_time::{Timer, TimerMode};
+use std::collections::HashMap;
+
+pub(crate) const MAX_PARTY_MEMBERS: usize = 8;
+const INVITATION_TIMEOUT_SECONDS: f32 = 10.0;
+
+/// The settings of a party, as chosen by the player creating it.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub(crate) struct PartySettings(pub u8);
+
+impl PartySettings {
+    const SHARE_EXP: u8 = 0x1;
+    const SHARE_ITEMS: u8 = 0x2;
+    const ANYONE_CAN_INVITE: u8 = 0x4;
+
+    pub(crate) fn shares_exp(&self) -> bool {
+        self.0 & Self::SHARE_EXP != 0
+    }
+
+    pub(crate) fn shares_items(&self) -> bool {
+        self.0 & Self::SHARE_ITEMS != 0
+    }
+
+    pub(crate) fn anyone_can_invite(&self) -> bool {
+        self.0 & Self::ANYONE_CAN_INVITE != 0
+    }
+}
+
+#[derive(Copy, Clone)]
+pub(crate) struct PartyMember {
+    pub(crate) entity: Entity,
+    pub(crate) unique_id: u32,
+}
+
+pub(crate) struct Party {
+    pub(crate) id: u32,
+    pub(crate) leader: u32,
+    pub(crate) settings: PartySettings,
+    pub(crate) members: Vec<PartyMember>,
+}
+
+impl Party {
+    pub(crate) fn is_full(&self) -> bool {
+        self.members.len() >= MAX_PARTY_MEMBERS
+    }
+
+    pub(crate) fn member(&self, unique_id: u32) -> Option<&PartyMember> {
+        self.members.iter().find(|member| member.unique_id == unique_id)
+    }
+
+    pub(crate) fn others(&self, unique_id: u32) -> impl Iterator<Item = &PartyMember> {
+        self.members.iter().filter(move |member| member.unique_id != unique_id)
+    }
+
+    /// Removes the member from the party and returns it. If the member was the leader, the longest standing member
+    /// becomes the new leader.
+    pub(crate) fn remove(&mut self, unique_id: u32) -> Option<PartyMember> {
+        let index = self.members.iter().position(|member| member.unique_id == unique_id)?;
+        let member = self.members.remove(index);
+        if self.leader == unique_id {
+            if let Some(next) = self.members.first() {
+                self.leader = next.unique_id;
+            }
+        }
+        Some(member)
+    }
+}
+
+/// All parties currently existing on this server.
+#[derive(Resource, Default)]
+pub(crate) struct Parties {
+    next_id: u32,
+    parties: HashMap<u32, Party>,
+}
+
+impl Parties {
+    pub(crate) fn create(&mut self, leader: PartyMember, member: PartyMember, settings: PartySettings) -> u32 {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.parties.insert(
+            id,
+            Party {
+                id,
+                leader: leader.unique_id,
+                settings,
+                members: vec![leader, member],
+            },
+        );
+        id
+    }
+
+    pub(crate) fn get(&self, id: u32) -> Option<&Party> {
+        self.parties.get(&id)
+    }
+
+    pub(crate) fn get_mut(&mut self, id: u32) -> Option<&mut Party> {
+        self.parties.get_mut(&id)
+    }
+
+    pub(crate) fn dissolve(&mut self, id: u32) -> Option<Party> {
+        self.parties.remove(&id)
+    }
+
+    /// Finds the party the given entity is a member of, together with its unique id.
+    pub(crate) fn find_member(&self, entity: Entity) -> Option<(u32, u32)> {
+        self.parties.values().find_map(|party| {
+            party
+                .members
+                .iter()
+                .find(|member| member.entity == entity)
+                .map(|member| (party.id, member.unique_id))
+        })
+    }
+}
+
+/// Marks a player as being a member of the party with the given id.
+#[derive(Component, Copy, Clone)]
+pub(crate) struct PartyMembership(pub u32);
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub(crate) enum InvitationKind {
+    Create,
+    Join,
+}
+
+/// An invitation into a party a player has not yet responded to.
+#[derive(Component)]
+pub(crate) struct PendingPartyInvitation {
+    pub(crate) inviter: Entity,
+    pub(crate) kind: InvitationKind,
+    pub(crate) settings: PartySettings,
+    pub(crate) timeout: Timer,
+}
+
+impl PendingPartyInvitation {
+    pub(crate) fn new(inviter: Entity, kind: InvitationKind, settings: PartySettings) -> Self {
+        PendingPartyInvitation {
+            inviter,
+            kind,
+            settings,
+            timeout: Timer::from_seconds(INVITATION_TIMEOUT_SECONDS, TimerMode::Once),
+        }
+    }
+}
@@ -0,0 +1,16 @@
+pub(crate) mod component;
+mod system;
+
+use crate::party::component::Parties;
+use crate::party::system::{expire_invitations, handle_party_requests, leave_party_on_despawn, update_member_bars};
+use bevy_app::{App, Plugin, PostUpdate, Update};
+
+pub(crate) struct PartyPlugin;
+
+impl Plugin for PartyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Parties>()
+            .add_systems(Update, (handle_party_requests, expire_invitations))
+            .add_systems(PostUpdate, (leave_party_on_despawn, update_member_bars));
+    }
+}
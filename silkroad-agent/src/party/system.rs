@@ -0,0 +1,468 @@
+use crate::comp::drop::LootSharing;
+use crate::comp::exp::Leveled;
+use crate::comp::net::Client;
+use crate::comp::player::Player;
+use crate::comp::pos::Position;
+use crate::comp::{GameEntity, Health, Mana};
+use crate::input::{PartyRequest, PlayerInput};
+use crate::party::component::{
+    InvitationKind, Parties, Party, PartyMember, PartyMembership, PartySettings, PendingPartyInvitation,
+};
+use crate::world::EntityLookup;
+use bevy_ecs::prelude::*;
+use bevy_ecs::query::WorldQuery;
+use bevy_time::Time;
+use silkroad_protocol::party::{
+    PartyCreateResponse, PartyData, PartyError, PartyInvitation, PartyInviteResponse, PartyKickResponse,
+    PartyLeaderTransferResponse, PartyLeaveReason, PartyLeaveResponse, PartyMemberData, PartyResponse, PartyUpdate,
+};
+
+#[derive(WorldQuery)]
+pub(crate) struct MemberInfo {
+    game_entity: &'static GameEntity,
+    player: &'static Player,
+    level: &'static Leveled,
+    health: &'static Health,
+    mana: &'static Mana,
+    position: &'static Position,
+}
+
+impl MemberInfoItem<'_> {
+    fn as_member(&self, entity: Entity) -> PartyMember {
+        PartyMember {
+            entity,
+            unique_id: self.game_entity.unique_id,
+        }
+    }
+
+    fn as_protocol(&self) -> PartyMemberData {
+        PartyMemberData {
+            unique_id: self.game_entity.unique_id,
+            name: self.player.character.name.clone(),
+            ref_id: self.game_entity.ref_id,
+            level: self.level.current_level(),
+            hp_mp: hp_mp_fraction(self.health, self.mana),
+            position: self.position.as_protocol(),
+        }
+    }
+}
+
+fn tenths(current: u32, max: u32) -> u8 {
+    if max == 0 {
+        return 0;
+    }
+    (current as u64 * 10 / max as u64).min(10) as u8
+}
+
+fn hp_mp_fraction(health: &Health, mana: &Mana) -> u8 {
+    (tenths(health.current_health, health.max_health) << 4) | tenths(mana.current_mana, mana.max_mana)
+}
+
+fn send_invitation_result(client: &Client, kind: InvitationKind, response: PartyResponse) {
+    match kind {
+        InvitationKind::Create => client.send(PartyCreateResponse(response)),
+        InvitationKind::Join => client.send(PartyInviteResponse(response)),
+    }
+}
+
+fn broadcast(party: &Party, clients: &Query<&Client>, update: PartyUpdate) {
+    for member in party.members.iter() {
+        if let Ok(client) = clients.get(member.entity) {
+            client.send(update.clone());
+        }
+    }
+}
+
+fn party_data(party: &Party, info: &Query<MemberInfo>) -> PartyData {
+    PartyData {
+        party_id: party.id,
+        leader: party.leader,
+        settings: party.settings.0,
+        members: party
+            .members
+            .iter()
+            .filter_map(|member| info.get(member.entity).ok())
+            .map(|member| member.as_protocol())
+            .collect(),
+    }
+}
+
+fn update_loot_sharing(party: &Party, cmd: &mut Commands) {
+    if !party.settings.shares_items() {
+        return;
+    }
+
+    for member in party.members.iter() {
+        cmd.entity(member.entity).insert(LootSharing {
+            members: party.others(member.unique_id).map(|other| other.unique_id).collect(),
+        });
+    }
+}
+
+fn remove_from_party(member: Entity, cmd: &mut Commands) {
+    if let Some(mut entity) = cmd.get_entity(member) {
+        entity.remove::<(PartyMembership, LootSharing)>();
+    }
+}
+
+/// Removes the member from the party, informing everyone involved. If too few members remain, the party is dissolved.
+fn leave_party(
+    parties: &mut Parties,
+    party_id: u32,
+    unique_id: u32,
+    reason: PartyLeaveReason,
+    clients: &Query<&Client>,
+    cmd: &mut Commands,
+) {
+    let Some(party) = parties.get_mut(party_id) else {
+        return;
+    };
+    let previous_leader = party.leader;
+    let Some(member) = party.remove(unique_id) else {
+        return;
+    };
+
+    let update = PartyUpdate::MemberLeft { unique_id, reason };
+    if let Ok(client) = clients.get(member.entity) {
+        client.send(update.clone());
+    }
+    remove_from_party(member.entity, cmd);
+
+    if party.members.len() < 2 {
+        broadcast(party, clients, PartyUpdate::Dismissed);
+        for remaining in party.members.iter() {
+            remove_from_party(remaining.entity, cmd);
+        }
+        parties.dissolve(party_id);
+        return;
+    }
+
+    broadcast(party, clients, update);
+    if party.leader != previous_leader {
+        broadcast(
+            party,
+            clients,
+            PartyUpdate::LeaderChanged {
+                unique_id: party.leader,
+            },
+        );
+    }
+    update_loot_sharing(party, cmd);
+}
+
+type Candidates<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Client,
+        Option<&'static PartyMembership>,
+        Option<&'static PendingPartyInvitation>,
+    ),
+    With<Player>,
+>;
+
+fn invite(
+    inviter: Entity,
+    inviter_id: u32,
+    target: Option<Entity>,
+    kind: InvitationKind,
+    settings: PartySettings,
+    candidates: &Candidates,
+    cmd: &mut Commands,
+) -> Result<(), PartyError> {
+    let target = target
+        .filter(|target| *target != inviter)
+        .ok_or(PartyError::InvalidTarget)?;
+    let (client, membership, pending) = candidates.get(target).map_err(|_| PartyError::InvalidTarget)?;
+    if membership.is_some() {
+        return Err(PartyError::AlreadyInParty);
+    }
+    if pending.is_some() {
+        return Err(PartyError::InvitationPending);
+    }
+
+    client.send(PartyInvitation {
+        inviter: inviter_id,
+        settings: settings.0,
+    });
+    cmd.entity(target)
+        .insert(PendingPartyInvitation::new(inviter, kind, settings));
+    Ok(())
+}
+
+/// Lets the invited player join the inviter's party, or creates a new party for the two if the inviter is not yet in
+/// one. Memberships are looked up in the parties themselves instead of the [PartyMembership] components, as the
+/// latter only get updated once the commands of this tick have been applied. Otherwise, a player accepting several
+/// invitations in the same tick could end up in multiple parties.
+fn join_party(
+    invitee: Entity,
+    invitation: &PendingPartyInvitation,
+    parties: &mut Parties,
+    info: &Query<MemberInfo>,
+    clients: &Query<&Client>,
+    cmd: &mut Commands,
+) -> Result<(), PartyError> {
+    if parties.find_member(invitee).is_some() {
+        return Err(PartyError::AlreadyInParty);
+    }
+
+    let inviter = info.get(invitation.inviter).map_err(|_| PartyError::InvalidTarget)?;
+    let joining = info.get(invitee).map_err(|_| PartyError::InvalidTarget)?;
+    let new_member = joining.as_member(invitee);
+
+    let party = match parties.find_member(invitation.inviter) {
+        Some((party_id, _)) => {
+            let party = parties.get_mut(party_id).ok_or(PartyError::NotInParty)?;
+            if party.is_full() {
+                return Err(PartyError::PartyFull);
+            }
+
+            broadcast(party, clients, PartyUpdate::MemberJoined(joining.as_protocol()));
+            party.members.push(new_member);
+            party
+        },
+        None => {
+            if invitation.kind == InvitationKind::Join {
+                return Err(PartyError::NotInParty);
+            }
+
+            let id = parties.create(inviter.as_member(invitation.inviter), new_member, invitation.settings);
+            cmd.entity(invitation.inviter).insert(PartyMembership(id));
+            let party = parties.get_mut(id).expect("Party should exist after being created");
+            if let Ok(client) = clients.get(invitation.inviter) {
+                client.send(party_data(party, info));
+            }
+            party
+        },
+    };
+
+    cmd.entity(invitee).insert(PartyMembership(party.id));
+    if let Ok(client) = clients.get(invitee) {
+        client.send(party_data(party, info));
+    }
+    update_loot_sharing(party, cmd);
+    Ok(())
+}
+
+pub(crate) fn handle_party_requests(
+    query: Query<(Entity, &Client, &GameEntity, &PlayerInput, Option<&PartyMembership>)>,
+    candidates: Candidates,
+    info: Query<MemberInfo>,
+    clients: Query<&Client>,
+    lookup: Res<EntityLookup>,
+    mut parties: ResMut<Parties>,
+    mut cmd: Commands,
+) {
+    for (entity, client, game_entity, input, membership) in query.iter() {
+        for request in input.party.iter() {
+            match request {
+                PartyRequest::Create(create) => {
+                    if membership.is_some() {
+                        client.send(PartyCreateResponse(PartyResponse::Error(PartyError::AlreadyInParty)));
+                        continue;
+                    }
+
+                    if let Err(error) = invite(
+                        entity,
+                        game_entity.unique_id,
+                        lookup.get_entity_for_id(create.target),
+                        InvitationKind::Create,
+                        PartySettings(create.settings),
+                        &candidates,
+                        &mut cmd,
+                    ) {
+                        client.send(PartyCreateResponse(PartyResponse::Error(error)));
+                    }
+                },
+                PartyRequest::Invite(request) => {
+                    let Some(party) = membership.and_then(|membership| parties.get(membership.0)) else {
+                        client.send(PartyInviteResponse(PartyResponse::Error(PartyError::NotInParty)));
+                        continue;
+                    };
+
+                    let result = if party.leader != game_entity.unique_id && !party.settings.anyone_can_invite() {
+                        Err(PartyError::NotLeader)
+                    } else if party.is_full() {
+                        Err(PartyError::PartyFull)
+                    } else {
+                        invite(
+                            entity,
+                            game_entity.unique_id,
+                            lookup.get_entity_for_id(request.target),
+                            InvitationKind::Join,
+                            party.settings,
+                            &candidates,
+                            &mut cmd,
+                        )
+                    };
+                    if let Err(error) = result {
+                        client.send(PartyInviteResponse(PartyResponse::Error(error)));
+                    }
+                },
+                PartyRequest::Leave => {
+                    let Some(membership) = membership else {
+                        client.send(PartyLeaveResponse(PartyResponse::Error(PartyError::NotInParty)));
+                        continue;
+                    };
+
+                    leave_party(
+                        &mut parties,
+                        membership.0,
+                        game_entity.unique_id,
+                        PartyLeaveReason::Left,
+                        &clients,
+                        &mut cmd,
+                    );
+                    client.send(PartyLeaveResponse(PartyResponse::Success));
+                },
+                PartyRequest::Kick(kick) => {
+                    let Some((party_id, party)) =
+                        membership.and_then(|membership| Some((membership.0, parties.get(membership.0)?)))
+                    else {
+                        client.send(PartyKickResponse(PartyResponse::Error(PartyError::NotInParty)));
+                        continue;
+                    };
+                    if party.leader != game_entity.unique_id {
+                        client.send(PartyKickResponse(PartyResponse::Error(PartyError::NotLeader)));
+                        continue;
+                    }
+                    if kick.member == game_entity.unique_id || party.member(kick.member).is_none() {
+                        client.send(PartyKickResponse(PartyResponse::Error(PartyError::InvalidTarget)));
+                        continue;
+                    }
+
+                    leave_party(
+                        &mut parties,
+                        party_id,
+                        kick.member,
+                        PartyLeaveReason::Kicked,
+                        &clients,
+                        &mut cmd,
+                    );
+                    client.send(PartyKickResponse(PartyResponse::Success));
+                },
+                PartyRequest::TransferLeader(transfer) => {
+                    let Some(party) = membership.and_then(|membership| parties.get_mut(membership.0)) else {
+                        client.send(PartyLeaderTransferResponse(PartyResponse::Error(
+                            PartyError::NotInParty,
+                        )));
+                        continue;
+                    };
+                    if party.leader != game_entity.unique_id {
+                        client.send(PartyLeaderTransferResponse(PartyResponse::Error(PartyError::NotLeader)));
+                        continue;
+                    }
+                    if party.member(transfer.member).is_none() {
+                        client.send(PartyLeaderTransferResponse(PartyResponse::Error(
+                            PartyError::InvalidTarget,
+                        )));
+                        continue;
+                    }
+
+                    party.leader = transfer.member;
+                    broadcast(
+                        party,
+                        &clients,
+                        PartyUpdate::LeaderChanged {
+                            unique_id: transfer.member,
+                        },
+                    );
+                    client.send(PartyLeaderTransferResponse(PartyResponse::Success));
+                },
+                PartyRequest::Reply(reply) => {
+                    let Some(invitation) = candidates.get(entity).ok().and_then(|(_, _, invitation)| invitation) else {
+                        continue;
+                    };
+                    cmd.entity(entity).remove::<PendingPartyInvitation>();
+
+                    let result = if reply.accepted {
+                        join_party(entity, invitation, &mut parties, &info, &clients, &mut cmd)
+                    } else {
+                        Err(PartyError::InvitationDeclined)
+                    };
+
+                    if let Ok(inviter) = clients.get(invitation.inviter) {
+                        let response = match result {
+                            Ok(_) => PartyResponse::Success,
+                            Err(error) => PartyResponse::Error(error),
+                        };
+                        send_invitation_result(inviter, invitation.kind, response);
+                    }
+                },
+            }
+        }
+    }
+}
+
+pub(crate) fn expire_invitations(
+    mut query: Query<(Entity, &mut PendingPartyInvitation)>,
+    clients: Query<&Client>,
+    time: Res<Time>,
+    mut cmd: Commands,
+) {
+    for (entity, mut invitation) in query.iter_mut() {
+        if !invitation.timeout.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        cmd.entity(entity).remove::<PendingPartyInvitation>();
+        if let Ok(inviter) = clients.get(invitation.inviter) {
+            send_invitation_result(
+                inviter,
+                invitation.kind,
+                PartyResponse::Error(PartyError::InvitationDeclined),
+            );
+        }
+    }
+}
+
+/// Removes players from their party when they despawn without having left it, e.g. when disconnecting.
+pub(crate) fn leave_party_on_despawn(
+    mut removed: RemovedComponents<PartyMembership>,
+    existing: Query<()>,
+    clients: Query<&Client>,
+    mut parties: ResMut<Parties>,
+    mut cmd: Commands,
+) {
+    for entity in removed.iter() {
+        // The membership is also removed when leaving normally, after which the player may already have joined
+        // another party, so only players that are gone need to be removed.
+        if existing.contains(entity) {
+            continue;
+        }
+        if let Some((party_id, unique_id)) = parties.find_member(entity) {
+            leave_party(
+                &mut parties,
+                party_id,
+                unique_id,
+                PartyLeaveReason::Disconnected,
+                &clients,
+                &mut cmd,
+            );
+        }
+    }
+}
+
+type BarsChanged = Or<(Changed<Health>, Changed<Mana>)>;
+
+pub(crate) fn update_member_bars(
+    query: Query<(&GameEntity, &Health, &Mana, &PartyMembership), BarsChanged>,
+    clients: Query<&Client>,
+    parties: Res<Parties>,
+) {
+    for (game_entity, health, mana, membership) in query.iter() {
+        let Some(party) = parties.get(membership.0) else {
+            continue;
+        };
+
+        let update = PartyUpdate::MemberBars {
+            unique_id: game_entity.unique_id,
+            hp_mp: hp_mp_fraction(health, mana),
+        };
+        for member in party.others(game_entity.unique_id) {
+            if let Ok(client) = clients.get(member.entity) {
+                client.send(update.clone());
+            }
+        }
+    }
+}
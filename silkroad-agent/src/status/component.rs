@@ -0,0 +1,127 @@
+use crate::comp::EntityReference;
+use crate::sync::Reset;
+use bevy_ecs::prelude::*;
+use bevy_time::{Timer, TimerMode};
+use silkroad_data::skilldata::RefSkillData;
+use silkroad_game_base::{AbnormalState, AttackPower, DAMAGE_TICK_INTERVAL};
+use std::time::Duration;
+
+/// Damage an abnormal state deals periodically, as well as who caused it.
+#[derive(Copy, Clone)]
+pub(crate) struct DamageOverTime {
+    pub(crate) source: EntityReference,
+    pub(crate) skill: &'static RefSkillData,
+    pub(crate) power: AttackPower,
+}
+
+pub(crate) struct ActiveAbnormalState {
+    pub(crate) state: AbnormalState,
+    pub(crate) level: u8,
+    remaining: Timer,
+    damage: Option<(DamageOverTime, Timer)>,
+}
+
+#[derive(Component, Default)]
+pub(crate) struct StatusEffects {
+    active: Vec<ActiveAbnormalState>,
+    changed: bool,
+}
+
+impl Reset for StatusEffects {
+    fn reset(&mut self) {
+        self.changed = false;
+    }
+}
+
+impl StatusEffects {
+    /// Applies the given state. An already active state of the same kind is only replaced if the new one has at least
+    /// the same level; otherwise the new state is resisted.
+    pub(crate) fn apply(
+        &mut self,
+        state: AbnormalState,
+        level: u8,
+        duration: Duration,
+        damage: Option<DamageOverTime>,
+    ) -> bool {
+        if let Some(existing) = self.active.iter().position(|active| active.state == state) {
+            if self.active[existing].level > level {
+                return false;
+            }
+            self.active.remove(existing);
+        }
+
+        self.active.push(ActiveAbnormalState {
+            state,
+            level,
+            remaining: Timer::new(duration, TimerMode::Once),
+            damage: damage.map(|damage| (damage, Timer::new(DAMAGE_TICK_INTERVAL, TimerMode::Repeating))),
+        });
+        self.changed = true;
+        true
+    }
+
+    pub(crate) fn has(&self, state: AbnormalState) -> bool {
+        self.active.iter().any(|active| active.state == state)
+    }
+
+    pub(crate) fn is_incapacitated(&self) -> bool {
+        self.active.iter().any(|active| active.state.is_incapacitating())
+    }
+
+    /// Removes all states whose flag is contained in the given mask and provides whether any state was removed.
+    pub(crate) fn cure(&mut self, mask: u32) -> bool {
+        let before = self.active.len();
+        self.active.retain(|active| active.state.flag() & mask == 0);
+        let cured = self.active.len() != before;
+        self.changed |= cured;
+        cured
+    }
+
+    pub(crate) fn clear(&mut self) {
+        if !self.active.is_empty() {
+            self.active.clear();
+            self.changed = true;
+        }
+    }
+
+    /// Advances all states, removing the expired ones, and provides the damage dealt by the states in this tick.
+    pub(crate) fn tick(&mut self, delta: Duration) -> Vec<DamageOverTime> {
+        let mut damage = Vec::new();
+        for active in self.active.iter_mut() {
+            active.remaining.tick(delta);
+            if let Some((dot, timer)) = active.damage.as_mut() {
+                for _ in 0..timer.tick(delta).times_finished_this_tick() {
+                    damage.push(*dot);
+                }
+            }
+        }
+
+        let before = self.active.len();
+        self.active.retain(|active| !active.remaining.finished());
+        self.changed |= self.active.len() != before;
+        damage
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// Whether the active states changed since the last synchronization.
+    pub(crate) fn did_change(&self) -> bool {
+        self.changed
+    }
+
+    /// Provides the flags of all active states, together with the levels of those states that carry one, in the form
+    /// the client expects them.
+    pub(crate) fn as_flags(&self) -> (u32, Vec<u8>) {
+        let mut active = self.active.iter().collect::<Vec<_>>();
+        active.sort_by_key(|active| active.state.flag());
+        let flags = active.iter().fold(0, |flags, active| flags | active.state.flag());
+        let levels = active
+            .iter()
+            .filter(|active| active.state.has_level())
+            .map(|active| active.level)
+            .collect();
+        (flags, levels)
+    }
+}
@@ -0,0 +1,22 @@
+pub(crate) mod component;
+mod system;
+
+use crate::game::damage::handle_damage;
+use crate::status::system::{apply_status_effects, release_incapacitated, tick_status_effects};
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::prelude::*;
+
+pub(crate) struct StatusEffectPlugin;
+
+impl Plugin for StatusEffectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                apply_status_effects.after(handle_damage),
+                tick_status_effects,
+                release_incapacitated.after(apply_status_effects),
+            ),
+        );
+    }
+}
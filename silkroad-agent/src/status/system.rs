@@ -0,0 +1,180 @@
+use crate::agent::states::{Dead, KnockedDown, Moving, StateTransitionQueue, Stunned};
+use crate::comp::pos::Position;
+use crate::comp::{EntityReference, GameEntity, Health};
+use crate::event::{AttackDefinition, DamageReceiveEvent};
+use crate::ext::Navmesh;
+use crate::game::damage::CombatStats;
+use crate::status::component::{DamageOverTime, StatusEffects};
+use bevy_ecs::prelude::*;
+use bevy_ecs::query::{Has, WorldQuery};
+use bevy_time::Time;
+use cgmath::{Deg, InnerSpace, Quaternion, Rotation3, Vector3};
+use rand::Rng;
+use silkroad_game_base::{
+    roll_effect, status_effects, AbnormalState, DamageOutcome, GlobalLocation, StatusEffect, Vector3Ext,
+    KNOCKDOWN_DURATION,
+};
+
+/// Pushes the entity back by the given distance, away from the location of its attacker. If the attacker is gone or
+/// stands right on top of the entity, it is pushed away from the direction it is facing instead.
+fn knock_back(navmesh: &Navmesh, position: &mut Position, source: Option<GlobalLocation>, distance: u16) {
+    let direction = source
+        .map(|source| position.location().0 - source.0)
+        .filter(|away| away.magnitude2() > f32::EPSILON)
+        .unwrap_or_else(|| {
+            let facing = Quaternion::from_angle_y(Deg(position.rotation().0)) * Vector3::unit_x();
+            -facing.to_flat_vec2()
+        })
+        .normalize();
+    let target = GlobalLocation(position.location().0 + direction * f32::from(distance));
+    let target = navmesh.raycast(position.location(), target).unwrap_or(target);
+    let height = navmesh.height_for(target).unwrap_or(position.position().0.y);
+    position.move_to(target.with_y(height));
+}
+
+#[derive(WorldQuery)]
+#[world_query(mutable)]
+pub(crate) struct AffectedEntity {
+    health: &'static Health,
+    status: &'static mut StatusEffects,
+    transitions: &'static mut StateTransitionQueue,
+    position: &'static mut Position,
+    moving: Option<&'static mut Moving>,
+}
+
+pub(crate) fn apply_status_effects(
+    mut events: EventReader<DamageReceiveEvent>,
+    combat_query: Query<CombatStats>,
+    mut query: Query<AffectedEntity>,
+    navmesh: Res<Navmesh>,
+) {
+    let mut rng = rand::thread_rng();
+    for event in events.iter() {
+        // Damage over time cannot cause any further effects.
+        if event.attack.instance.is_none() || event.damage.is_avoided() {
+            continue;
+        }
+
+        // Status effects are only rolled once per attack, even if the skill hits multiple times.
+        if !event.attack.first_hit {
+            continue;
+        }
+
+        let source_location = query.get(event.source.0).ok().map(|source| source.position.location());
+        let Ok(AffectedEntityItem {
+            health,
+            mut status,
+            mut transitions,
+            mut position,
+            mut moving,
+        }) = query.get_mut(event.target.0)
+        else {
+            continue;
+        };
+
+        if health.is_dead() {
+            continue;
+        }
+
+        if status.has(AbnormalState::Sleeping) {
+            // Getting hit wakes the entity up again.
+            status.cure(AbnormalState::Sleeping.flag());
+        }
+
+        let caster_level = combat_query
+            .get(event.source.0)
+            .map(|stats| stats.entity_level())
+            .unwrap_or(1);
+        let target_level = combat_query
+            .get(event.target.0)
+            .map(|stats| stats.entity_level())
+            .unwrap_or(1);
+
+        for possible in status_effects(&event.attack.skill.params, caster_level) {
+            if !roll_effect(&mut rng, &possible, caster_level, target_level) {
+                continue;
+            }
+
+            match possible.effect {
+                StatusEffect::Abnormal {
+                    state,
+                    level,
+                    duration,
+                    damage,
+                } => {
+                    // An entity that is already incapacitated cannot be kept down by chaining effects.
+                    if state.is_incapacitating() && status.is_incapacitated() {
+                        continue;
+                    }
+
+                    let damage = damage.map(|power| DamageOverTime {
+                        source: event.source,
+                        skill: event.attack.skill,
+                        power,
+                    });
+                    if status.apply(state, level, duration, damage) && state.is_incapacitating() {
+                        transitions.request_transition(Stunned::new(duration));
+                    }
+                },
+                StatusEffect::Knockdown => transitions.request_transition(KnockedDown::new(KNOCKDOWN_DURATION)),
+                StatusEffect::Knockback { distance } => {
+                    knock_back(&navmesh, &mut position, source_location, distance);
+                    // A moving entity only has its movement synchronized when its goal changes, so it needs to
+                    // restart moving from where it got pushed to.
+                    if let Some(moving) = moving.as_mut() {
+                        moving.set_changed();
+                    }
+                },
+            }
+        }
+    }
+}
+
+pub(crate) fn tick_status_effects(
+    mut query: Query<(Entity, &GameEntity, &mut StatusEffects, Has<Dead>)>,
+    time: Res<Time>,
+    mut damage_events: EventWriter<DamageReceiveEvent>,
+) {
+    let delta = time.delta();
+    let mut rng = rand::thread_rng();
+    for (entity, game_entity, mut status, dead) in query.iter_mut() {
+        if status.is_empty() {
+            continue;
+        }
+
+        if dead {
+            status.clear();
+            continue;
+        }
+
+        // Ticking the effects happens every frame, so we only want to flag actual changes to the effects.
+        for damage in status.bypass_change_detection().tick(delta) {
+            damage_events.send(DamageReceiveEvent {
+                source: damage.source,
+                target: EntityReference(entity, *game_entity),
+                attack: AttackDefinition {
+                    skill: damage.skill,
+                    instance: None,
+                    first_hit: false,
+                },
+                damage: DamageOutcome::Normal(rng.gen_range(damage.power.min..=damage.power.max)),
+                later_hits: Vec::new(),
+            });
+        }
+
+        if status.did_change() {
+            status.set_changed();
+        }
+    }
+}
+
+type StunnedChanged = (With<Stunned>, Changed<StatusEffects>);
+
+/// Lets entities recover early if the states that kept them incapacitated were removed, e.g. because they were cured.
+pub(crate) fn release_incapacitated(query: Query<(Entity, &StatusEffects), StunnedChanged>, mut cmd: Commands) {
+    for (entity, status) in query.iter() {
+        if !status.is_incapacitated() {
+            cmd.entity(entity).remove::<Stunned>();
+        }
+    }
+}
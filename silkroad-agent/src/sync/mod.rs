@@ -1,7 +1,7 @@
 use crate::sync::system::{
-    collect_alives, collect_body_states, collect_deaths, collect_movement_speed_change, collect_movement_update,
-    collect_pickup_animation, synchronize_updates, system_collect_bars_update, system_collect_exp_update,
-    system_collect_level_up, system_collect_sp_update,
+    collect_alives, collect_body_states, collect_buff_changes, collect_deaths, collect_movement_speed_change,
+    collect_movement_update, collect_pickup_animation, collect_status_changes, synchronize_updates,
+    system_collect_bars_update, system_collect_exp_update, system_collect_level_up, system_collect_sp_update,
 };
 use bevy_app::{App, Last, Plugin, PostUpdate};
 use bevy_ecs::prelude::*;
@@ -78,6 +78,8 @@ impl Plugin for SynchronizationPlugin {
                     collect_deaths,
                     collect_alives,
                     collect_body_states,
+                    collect_buff_changes,
+                    collect_status_changes,
                 )
                     .in_set(SynchronizationStage::Collection),
             )
@@ -1,6 +1,8 @@
 use crate::comp::exp::{Experienced, Leveled};
+use crate::comp::player::Buffed;
 use crate::comp::pos::Position;
 use crate::comp::{Health, Mana};
+use crate::status::component::StatusEffects;
 use bevy_ecs::change_detection::DetectChangesMut;
 use bevy_ecs::prelude::Query;
 
@@ -15,9 +17,11 @@ pub(crate) fn reset_tracked_entities(
         Option<&mut Mana>,
         Option<&mut Experienced>,
         Option<&mut Leveled>,
+        Option<&mut Buffed>,
+        Option<&mut StatusEffects>,
     )>,
 ) {
-    for ((mut pos, mut health, mut mana, mut exp, mut level)) in query.iter_mut() {
+    for ((mut pos, mut health, mut mana, mut exp, mut level, mut buffed, mut status)) in query.iter_mut() {
         if let Some(pos) = &mut pos {
             pos.bypass_change_detection().reset();
         }
@@ -37,5 +41,13 @@ pub(crate) fn reset_tracked_entities(
         if let Some(leveled) = &mut level {
             leveled.bypass_change_detection().reset();
         }
+
+        if let Some(buffed) = &mut buffed {
+            buffed.bypass_change_detection().reset();
+        }
+
+        if let Some(status) = &mut status {
+            status.bypass_change_detection().reset();
+        }
     }
 }
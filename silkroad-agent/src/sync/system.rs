@@ -1,33 +1,38 @@
 use crate::agent::states::{Dead, Idle, MovementGoal, Moving, Pickup};
-use crate::agent::MovementState;
+use crate::agent::{Agent, MovementState};
 use crate::comp::damage::Invincible;
 use crate::comp::exp::{Experienced, Leveled, SP};
+use crate::comp::inventory::PlayerInventory;
 use crate::comp::net::Client;
-use crate::comp::player::Player;
+use crate::comp::player::{BuffChange, Buffed, Player};
 use crate::comp::pos::Position;
 use crate::comp::visibility::{Invisible, Visibility};
 use crate::comp::{GameEntity, Health, Mana};
 use crate::event::LoadingFinishedEvent;
+use crate::game::stats::stats_message;
+use crate::status::component::StatusEffects;
 use crate::sync::{SynchronizationCollector, Update};
 use bevy_ecs::prelude::*;
-use silkroad_game_base::{Heading, LocalPosition, MovementSpeed};
-use silkroad_protocol::character::CharacterStatsMessage;
+use bevy_ecs::query::WorldQuery;
+use silkroad_game_base::{BuffEffect, Heading, LocalPosition, MovementSpeed};
+use silkroad_protocol::combat::{BuffAdded, BuffRemoved};
 use silkroad_protocol::world::{
-    AliveState, BodyState, CharacterPointsUpdate, EntityBarUpdateSource, EntityBarUpdates, EntityBarsUpdate,
-    EntityMovementInterrupt, EntityUpdateState, LevelUpEffect, MovementDestination, MovementSource, MovementType,
-    PlayerMovementResponse, PlayerPickupAnimation, ReceiveExperience, UpdatedState,
+    AliveState, BodyState, ChangeSpeed, CharacterPointsUpdate, EntityBarUpdateSource, EntityBarUpdates,
+    EntityBarsUpdate, EntityMovementInterrupt, EntityUpdateState, LevelUpEffect, MovementDestination, MovementSource,
+    MovementType, PlayerMovementResponse, PlayerPickupAnimation, ReceiveExperience, UpdatedState,
 };
+use silkroad_protocol::ServerPacket;
 use std::ops::Deref;
 use tracing::event;
 
 pub(crate) fn synchronize_updates(
     mut update_collector: ResMut<SynchronizationCollector>,
-    source_query: Query<(&Client, &Visibility)>,
+    source_query: Query<(Option<&Client>, &Visibility)>,
     others: Query<&Client>,
 ) {
     for update in update_collector.collect_updates() {
         if let Ok((client, visibility)) = source_query.get(update.source) {
-            if let Some(self_packet) = update.change_self {
+            if let (Some(client), Some(self_packet)) = (client, update.change_self) {
                 client.send(self_packet);
             }
 
@@ -152,12 +157,39 @@ pub(crate) fn system_collect_exp_update(
                 change_others: None,
             })
         }
+
+        if exp.experience_loss() > 0 {
+            // The client reads the experience as a signed value, showing negative amounts as lost experience.
+            let lost = -(exp.experience_loss() as i64);
+            collector.send_update(Update {
+                source: entity,
+                change_self: Some(
+                    ReceiveExperience {
+                        exp_origin: 0,
+                        experience: lost as u64,
+                        sp: 0,
+                        unknown: 0,
+                        new_level: None,
+                    }
+                    .into(),
+                ),
+                change_others: None,
+            })
+        }
     }
 }
 
+/// Everything required to calculate the stats of a player.
+#[derive(WorldQuery)]
+pub(crate) struct PlayerStats {
+    player: &'static Player,
+    inventory: &'static PlayerInventory,
+    buffs: &'static Buffed,
+}
+
 pub(crate) fn system_collect_level_up(
     collector: Res<SynchronizationCollector>,
-    mut query: Query<(Entity, &GameEntity, Option<&Player>, &Leveled), Changed<Leveled>>,
+    mut query: Query<(Entity, &GameEntity, Option<PlayerStats>, &Leveled), Changed<Leveled>>,
 ) {
     for (entity, game_entity, maybe_player, level) in query.iter_mut() {
         if level.did_level() {
@@ -171,21 +203,8 @@ pub(crate) fn system_collect_level_up(
                 change_others: Some(animation.into()),
             });
 
-            if let Some(player) = maybe_player {
-                let update = CharacterStatsMessage {
-                    phys_attack_min: 100,
-                    phys_attack_max: 100,
-                    mag_attack_min: 100,
-                    mag_attack_max: 100,
-                    phys_defense: 100,
-                    mag_defense: 100,
-                    hit_rate: 100,
-                    parry_rate: 100,
-                    max_hp: player.character.stats.max_health(player.character.level),
-                    max_mp: player.character.stats.max_mana(player.character.level),
-                    strength: player.character.stats.strength(),
-                    intelligence: player.character.stats.intelligence(),
-                };
+            if let Some(stats) = maybe_player {
+                let update = stats_message(stats.player, stats.inventory, stats.buffs, level.current_level());
 
                 collector.send_update(Update {
                     source: entity,
@@ -211,12 +230,13 @@ pub(crate) fn collect_movement_update(
                     continue;
                 }
 
-                match moving.0 {
-                    MovementGoal::Location(dest) | MovementGoal::Entity(_, dest, _) => {
-                        MovementUpdate::StartMove(pos.position().to_local(), dest.to_local())
-                    },
+                match &moving.0 {
                     MovementGoal::Direction(direction) => {
-                        MovementUpdate::StartMoveTowards(pos.position().to_local(), direction)
+                        MovementUpdate::StartMoveTowards(pos.position().to_local(), *direction)
+                    },
+                    goal => match goal.next_position() {
+                        Some(dest) => MovementUpdate::StartMove(pos.position().to_local(), dest.to_local()),
+                        None => continue,
                     },
                 }
             },
@@ -330,6 +350,82 @@ pub(crate) fn collect_movement_speed_change(
     }
 }
 
+pub(crate) fn collect_buff_changes(
+    collector: Res<SynchronizationCollector>,
+    query: Query<(Entity, &GameEntity, &Agent, PlayerStats, &Leveled), Changed<Buffed>>,
+) {
+    for (entity, game_entity, agent, stats, level) in query.iter() {
+        let buffed = stats.buffs;
+        if buffed.changes().is_empty() {
+            continue;
+        }
+
+        for change in buffed.changes() {
+            let update: ServerPacket = match *change {
+                BuffChange::Added(skill, token) => BuffAdded {
+                    target: game_entity.unique_id,
+                    skill: skill.ref_id,
+                    token,
+                }
+                .into(),
+                BuffChange::Removed(_, token) => BuffRemoved::new(token).into(),
+            };
+            collector.send_update(Update {
+                source: entity,
+                change_self: Some(update.clone()),
+                change_others: Some(update),
+            });
+        }
+
+        if buffed
+            .changes()
+            .iter()
+            .any(|change| BuffEffect::from_skill(change.skill()).speed > 0)
+        {
+            let update = ChangeSpeed {
+                entity: game_entity.unique_id,
+                walk_speed: agent.get_speed_value(MovementSpeed::Walking),
+                running_speed: agent.get_speed_value(MovementSpeed::Running),
+            };
+            collector.send_update(Update {
+                source: entity,
+                change_self: Some(update.into()),
+                change_others: Some(update.into()),
+            });
+        }
+
+        let update = stats_message(stats.player, stats.inventory, buffed, level.current_level());
+        collector.send_update(Update {
+            source: entity,
+            change_self: Some(update.into()),
+            change_others: None,
+        });
+    }
+}
+
+pub(crate) fn collect_status_changes(
+    collector: Res<SynchronizationCollector>,
+    query: Query<(Entity, &GameEntity, &StatusEffects), Changed<StatusEffects>>,
+) {
+    for (entity, game_entity, status) in query.iter() {
+        if !status.did_change() {
+            continue;
+        }
+
+        let (effects, levels) = status.as_flags();
+        let update = EntityBarsUpdate {
+            unique_id: game_entity.unique_id,
+            source: EntityBarUpdateSource::Damage,
+            updates: EntityBarUpdates::Status { effects, levels },
+        };
+        collector.send_update(Update {
+            source: entity,
+            change_self: Some(update.clone().into()),
+            change_others: Some(update.into()),
+        });
+    }
+}
+
 pub(crate) fn collect_pickup_animation(
     collector: Res<SynchronizationCollector>,
     query: Query<(Entity, &GameEntity, &Position, &Pickup), Or<(Added<Pickup>, Changed<Pickup>)>>,
@@ -369,15 +465,17 @@ pub(crate) fn collect_deaths(
 pub(crate) fn collect_alives(
     collector: Res<SynchronizationCollector>,
     mut reader: EventReader<LoadingFinishedEvent>,
+    mut resurrected: RemovedComponents<Dead>,
     query: Query<&GameEntity>,
 ) {
-    for event in reader.iter() {
-        let Ok(game_entity) = query.get(event.0) else {
+    // Entities that are despawned after dying, like monsters, will no longer be found in the query.
+    for entity in reader.iter().map(|event| event.0).chain(resurrected.iter()) {
+        let Ok(game_entity) = query.get(entity) else {
             continue;
         };
         let update = EntityUpdateState::life(game_entity.unique_id, AliveState::Alive);
         collector.send_update(Update {
-            source: event.0,
+            source: entity,
             change_self: Some(update.into()),
             change_others: Some(update.into()),
         });
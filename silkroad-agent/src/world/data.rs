@@ -2,12 +2,15 @@ use once_cell::sync::OnceCell;
 use pk2::Pk2;
 use silkroad_data::characterdata::{load_character_map, RefCharacterData};
 use silkroad_data::datamap::DataMap;
+use silkroad_data::drop::{load_drop_tables, DropTables};
 use silkroad_data::gold::{load_gold_map, GoldMap};
 use silkroad_data::itemdata::{load_item_map, RefItemData};
 use silkroad_data::level::{load_level_map, LevelMap};
 use silkroad_data::masterydata::{load_mastery_map, RefMasteryData};
+use silkroad_data::shop::{load_shop_tables, ShopTables};
 use silkroad_data::skilldata::{load_skill_map, RefSkillData};
 use silkroad_data::FileError;
+use tracing::warn;
 
 static ITEMS: OnceCell<DataMap<RefItemData>> = OnceCell::new();
 static CHARACTERS: OnceCell<DataMap<RefCharacterData>> = OnceCell::new();
@@ -15,6 +18,8 @@ static SKILLS: OnceCell<DataMap<RefSkillData>> = OnceCell::new();
 static LEVELS: OnceCell<LevelMap> = OnceCell::new();
 static GOLD: OnceCell<GoldMap> = OnceCell::new();
 static MASTERIES: OnceCell<DataMap<RefMasteryData>> = OnceCell::new();
+static DROPS: OnceCell<DropTables> = OnceCell::new();
+static SHOPS: OnceCell<ShopTables> = OnceCell::new();
 
 pub struct WorldData;
 
@@ -26,12 +31,23 @@ impl WorldData {
         let items = load_item_map(media_pk2)?;
         let skills = load_skill_map(media_pk2)?;
         let masteries = load_mastery_map(media_pk2)?;
+        // Not every client ships with the server side drop tables, in which case monsters only drop gold.
+        let drops = load_drop_tables(media_pk2).unwrap_or_else(|e| {
+            warn!("Could not load drop tables, monsters won't drop any items: {}", e);
+            DropTables::default()
+        });
+        let shops = load_shop_tables(media_pk2).unwrap_or_else(|e| {
+            warn!("Could not load shop tables, NPCs won't sell any items: {}", e);
+            ShopTables::default()
+        });
         let _ = LEVELS.set(levels);
         let _ = GOLD.set(gold);
         let _ = CHARACTERS.set(characters);
         let _ = ITEMS.set(items);
         let _ = SKILLS.set(skills);
         let _ = MASTERIES.set(masteries);
+        let _ = DROPS.set(drops);
+        let _ = SHOPS.set(shops);
         Ok(())
     }
 
@@ -58,4 +74,12 @@ impl WorldData {
     pub fn masteries() -> &'static DataMap<RefMasteryData> {
         MASTERIES.get().expect("Masteries should have been set")
     }
+
+    pub fn drops() -> &'static DropTables {
+        DROPS.get().expect("Drops should have been set")
+    }
+
+    pub fn shops() -> &'static ShopTables {
+        SHOPS.get().expect("Shops should have been set")
+    }
 }
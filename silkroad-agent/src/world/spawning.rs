@@ -11,6 +11,7 @@ use crate::config::GameConfig;
 use crate::ext::{EntityIdPool, Navmesh, NpcPositionList};
 use crate::game::mind::Mind;
 use crate::game::player_activity::PlayerActivity;
+use crate::status::component::StatusEffects;
 use crate::world::WorldData;
 use bevy_ecs::prelude::*;
 use bevy_time::Time;
@@ -211,6 +212,7 @@ fn spawn_monster(
         movement_state: MovementState::default_monster(),
         damage_receiver: DamageReceiver::default(),
         mind: Mind::default(),
+        status: StatusEffects::default(),
     }
 }
 
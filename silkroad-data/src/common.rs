@@ -1,5 +1,6 @@
 use crate::ParseError;
 use num_enum_derive::TryFromPrimitive;
+use silkroad_definitions::type_id::ObjectRace;
 use silkroad_definitions::TypeId;
 use std::str::FromStr;
 use std::time::Duration;
@@ -38,6 +39,17 @@ pub enum RefOrigin {
     General = 3,
 }
 
+impl RefOrigin {
+    /// The race this reference object belongs to, or `None` if it is usable by both races.
+    pub fn race(self) -> Option<ObjectRace> {
+        match self {
+            RefOrigin::Chinese => Some(ObjectRace::Chinese),
+            RefOrigin::European => Some(ObjectRace::European),
+            RefOrigin::General => None,
+        }
+    }
+}
+
 impl FromStr for RefOrigin {
     type Err = ParseError;
 
@@ -0,0 +1,158 @@
+use crate::{parse_file, FileError, ParseError};
+use pk2::Pk2;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+pub fn load_drop_tables(pk2: &Pk2) -> Result<DropTables, FileError> {
+    let mut file = pk2.open_file("/server_dep/silkroad/textdata/refdropitemgroup.txt")?;
+    let group_items: Vec<RefDropGroupItem> = parse_file(&mut file)?;
+    let mut file = pk2.open_file("/server_dep/silkroad/textdata/refmonster_assigneditemdrop.txt")?;
+    let item_drops: Vec<RefAssignedItemDrop> = parse_file(&mut file)?;
+    let mut file = pk2.open_file("/server_dep/silkroad/textdata/refmonster_assigneditemrnddrop.txt")?;
+    let group_drops: Vec<RefAssignedGroupDrop> = parse_file(&mut file)?;
+
+    let mut tables = DropTables::default();
+    for item in group_items.into_iter().filter(|item| item.service) {
+        tables.add_group_item(item);
+    }
+    for drop in item_drops {
+        tables.add_monster_drop(
+            drop.monster_id,
+            RefMonsterDrop {
+                source: DropSource::Item(drop.item_id),
+                min_amount: drop.min_amount,
+                max_amount: drop.max_amount,
+                ratio: drop.ratio,
+            },
+        );
+    }
+    for drop in group_drops {
+        tables.add_monster_drop(
+            drop.monster_id,
+            RefMonsterDrop {
+                source: DropSource::Group(drop.group_id),
+                min_amount: drop.min_amount,
+                max_amount: drop.max_amount,
+                ratio: drop.ratio,
+            },
+        );
+    }
+    Ok(tables)
+}
+
+/// All the items monsters may drop, combining the drops assigned directly to a monster with the drops assigned
+/// through item groups.
+#[derive(Default)]
+pub struct DropTables {
+    groups: HashMap<u32, Vec<RefDropGroupItem>>,
+    monsters: HashMap<u32, Vec<RefMonsterDrop>>,
+}
+
+impl DropTables {
+    pub fn drops_for(&self, monster: u32) -> &[RefMonsterDrop] {
+        self.monsters
+            .get(&monster)
+            .map(|drops| drops.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn group(&self, group: u32) -> &[RefDropGroupItem] {
+        self.groups
+            .get(&group)
+            .map(|items| items.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn add_group_item(&mut self, item: RefDropGroupItem) {
+        self.groups.entry(item.group_id).or_default().push(item);
+    }
+
+    pub fn add_monster_drop(&mut self, monster: u32, drop: RefMonsterDrop) {
+        self.monsters.entry(monster).or_default().push(drop);
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DropSource {
+    Item(u32),
+    Group(u32),
+}
+
+#[derive(Copy, Clone)]
+pub struct RefMonsterDrop {
+    pub source: DropSource,
+    pub min_amount: u16,
+    pub max_amount: u16,
+    /// The chance for this drop to happen, between `0.0` and `1.0`.
+    pub ratio: f32,
+}
+
+#[derive(Copy, Clone)]
+pub struct RefDropGroupItem {
+    pub service: bool,
+    pub group_id: u32,
+    pub item_id: u32,
+    /// The weight of this item when selecting an item out of its group.
+    pub select_ratio: f32,
+}
+
+impl FromStr for RefDropGroupItem {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let elements = s.split('\t').collect::<Vec<&str>>();
+        let service: u8 = elements.get(0).ok_or(ParseError::MissingColumn(0))?.parse()?;
+        Ok(Self {
+            service: service == 1,
+            group_id: elements.get(1).ok_or(ParseError::MissingColumn(1))?.parse()?,
+            item_id: elements.get(3).ok_or(ParseError::MissingColumn(3))?.parse()?,
+            select_ratio: elements.get(4).ok_or(ParseError::MissingColumn(4))?.parse()?,
+        })
+    }
+}
+
+struct RefAssignedItemDrop {
+    monster_id: u32,
+    item_id: u32,
+    min_amount: u16,
+    max_amount: u16,
+    ratio: f32,
+}
+
+impl FromStr for RefAssignedItemDrop {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let elements = s.split('\t').collect::<Vec<&str>>();
+        Ok(Self {
+            monster_id: elements.get(0).ok_or(ParseError::MissingColumn(0))?.parse()?,
+            item_id: elements.get(1).ok_or(ParseError::MissingColumn(1))?.parse()?,
+            min_amount: elements.get(4).ok_or(ParseError::MissingColumn(4))?.parse()?,
+            max_amount: elements.get(5).ok_or(ParseError::MissingColumn(5))?.parse()?,
+            ratio: elements.get(6).ok_or(ParseError::MissingColumn(6))?.parse()?,
+        })
+    }
+}
+
+struct RefAssignedGroupDrop {
+    monster_id: u32,
+    group_id: u32,
+    min_amount: u16,
+    max_amount: u16,
+    ratio: f32,
+}
+
+impl FromStr for RefAssignedGroupDrop {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let elements = s.split('\t').collect::<Vec<&str>>();
+        Ok(Self {
+            monster_id: elements.get(0).ok_or(ParseError::MissingColumn(0))?.parse()?,
+            group_id: elements.get(1).ok_or(ParseError::MissingColumn(1))?.parse()?,
+            min_amount: elements.get(4).ok_or(ParseError::MissingColumn(4))?.parse()?,
+            max_amount: elements.get(5).ok_or(ParseError::MissingColumn(5))?.parse()?,
+            ratio: elements.get(6).ok_or(ParseError::MissingColumn(6))?.parse()?,
+        })
+    }
+}
@@ -44,11 +44,68 @@ impl FromStr for RefBiologicalType {
 pub struct RefItemData {
     pub common: RefCommon,
     pub price: u64,
+    /// The amount of gold a player receives when selling the item to an NPC.
+    pub sell_price: u64,
     pub max_stack_size: u16,
     pub range: Option<NonZeroU16>,
     pub required_level: Option<NonZeroU8>,
     pub biological_type: RefBiologicalType,
+    /// The class of the item within its kind. For equipment, every three classes make up one degree.
+    pub item_class: u8,
     pub params: [isize; 4],
+    pub stats: RefItemStats,
+}
+
+impl RefItemData {
+    /// Provides the degree of an equipment item, derived from its class.
+    pub fn degree(&self) -> u8 {
+        self.item_class.div_ceil(3)
+    }
+}
+
+/// The combat relevant base values of an item. Items of a kind roll their actual values between a lower and upper
+/// bound, we only keep the lower bound here as we don't track the variance of an item yet.
+#[derive(Copy, Clone, Default)]
+pub struct RefItemStats {
+    pub phys_defense: f32,     // column 65
+    pub phys_defense_inc: f32, // column 67
+    pub parry_rate: f32,       // column 68
+    pub parry_rate_inc: f32,   // column 70
+    pub block_ratio: f32,      // column 74
+    pub mag_defense: f32,      // column 76
+    pub mag_defense_inc: f32,  // column 78
+    pub phys_attack_min: f32,  // column 95
+    pub phys_attack_max: f32,  // column 97
+    pub phys_attack_inc: f32,  // column 99
+    pub mag_attack_min: f32,   // column 100
+    pub mag_attack_max: f32,   // column 102
+    pub mag_attack_inc: f32,   // column 104
+    pub hit_rate: f32,         // column 113
+    pub hit_rate_inc: f32,     // column 115
+    pub critical: f32,         // column 116
+}
+
+impl RefItemStats {
+    fn from_columns(elements: &[&str]) -> Result<Self, ParseError> {
+        Ok(Self {
+            phys_defense: elements.get(65).ok_or(ParseError::MissingColumn(65))?.parse()?,
+            phys_defense_inc: elements.get(67).ok_or(ParseError::MissingColumn(67))?.parse()?,
+            parry_rate: elements.get(68).ok_or(ParseError::MissingColumn(68))?.parse()?,
+            parry_rate_inc: elements.get(70).ok_or(ParseError::MissingColumn(70))?.parse()?,
+            block_ratio: elements.get(74).ok_or(ParseError::MissingColumn(74))?.parse()?,
+            mag_defense: elements.get(76).ok_or(ParseError::MissingColumn(76))?.parse()?,
+            mag_defense_inc: elements.get(78).ok_or(ParseError::MissingColumn(78))?.parse()?,
+            phys_attack_min: elements.get(95).ok_or(ParseError::MissingColumn(95))?.parse()?,
+            phys_attack_max: elements.get(97).ok_or(ParseError::MissingColumn(97))?.parse()?,
+            phys_attack_inc: elements.get(99).ok_or(ParseError::MissingColumn(99))?.parse()?,
+            mag_attack_min: elements.get(100).ok_or(ParseError::MissingColumn(100))?.parse()?,
+            mag_attack_max: elements.get(102).ok_or(ParseError::MissingColumn(102))?.parse()?,
+            mag_attack_inc: elements.get(104).ok_or(ParseError::MissingColumn(104))?.parse()?,
+            hit_rate: elements.get(113).ok_or(ParseError::MissingColumn(113))?.parse()?,
+            hit_rate_inc: elements.get(115).ok_or(ParseError::MissingColumn(115))?.parse()?,
+            critical: elements.get(116).ok_or(ParseError::MissingColumn(116))?.parse()?,
+        })
+    }
 }
 
 impl PartialEq for RefItemData {
@@ -78,6 +135,7 @@ impl FromStr for RefItemData {
         Ok(Self {
             common,
             price: elements.get(26).ok_or(ParseError::MissingColumn(26))?.parse()?,
+            sell_price: elements.get(31).ok_or(ParseError::MissingColumn(31))?.parse()?,
             params: [
                 elements.get(118).ok_or(ParseError::MissingColumn(118))?.parse()?,
                 elements.get(120).ok_or(ParseError::MissingColumn(120))?.parse()?,
@@ -87,7 +145,9 @@ impl FromStr for RefItemData {
             range: NonZeroU16::new(range),
             required_level: NonZeroU8::new(required_level),
             biological_type: elements.get(58).ok_or(ParseError::MissingColumn(58))?.parse()?,
+            item_class: elements.get(61).ok_or(ParseError::MissingColumn(61))?.parse()?,
             max_stack_size: elements.get(57).ok_or(ParseError::MissingColumn(57))?.parse()?,
+            stats: RefItemStats::from_columns(&elements)?,
         })
     }
 }
@@ -5,11 +5,13 @@
 pub mod characterdata;
 pub mod common;
 pub mod datamap;
+pub mod drop;
 pub mod gold;
 pub mod itemdata;
 pub mod level;
 pub mod masterydata;
 pub mod npc_pos;
+pub mod shop;
 pub mod skilldata;
 
 pub use datamap::*;
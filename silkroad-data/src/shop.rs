@@ -0,0 +1,206 @@
+use crate::{parse_file, FileError, ParseError};
+use pk2::Pk2;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Loads the shops of all NPCs. The shop data is spread over multiple files, which reference each other by their code
+/// names: an NPC has a shop group, which maps to shops, which contain tab groups, which consist of tabs, which finally
+/// contain the goods. Each good then references a package, defining the actual item that is sold.
+pub fn load_shop_tables(pk2: &Pk2) -> Result<ShopTables, FileError> {
+    let mut file = pk2.open_file("/server_dep/silkroad/textdata/refshopgroup.txt")?;
+    let groups: Vec<RefShopGroup> = parse_file(&mut file)?;
+    let mut file = pk2.open_file("/server_dep/silkroad/textdata/refmappingshopgroup.txt")?;
+    let shops: Vec<RefCodeMapping> = parse_file(&mut file)?;
+    let mut file = pk2.open_file("/server_dep/silkroad/textdata/refmappingshopwithtab.txt")?;
+    let tab_groups: Vec<RefCodeMapping> = parse_file(&mut file)?;
+    let mut file = pk2.open_file("/server_dep/silkroad/textdata/refshoptab.txt")?;
+    let tabs: Vec<RefShopTabRow> = parse_file(&mut file)?;
+    let mut file = pk2.open_file("/server_dep/silkroad/textdata/refshopgoods.txt")?;
+    let goods: Vec<RefShopGoodsRow> = parse_file(&mut file)?;
+    let mut file = pk2.open_file("/server_dep/silkroad/textdata/refscrapofpackageitem.txt")?;
+    let packages: Vec<RefPackageItem> = parse_file(&mut file)?;
+
+    let packages: HashMap<&str, &RefPackageItem> = packages
+        .iter()
+        .filter(|package| package.service)
+        .map(|package| (package.package.as_str(), package))
+        .collect();
+
+    let mut tables = ShopTables::default();
+    for group in groups.iter().filter(|group| group.service) {
+        let shop_tabs = shops
+            .iter()
+            .filter(|shop| shop.service && shop.from == group.group)
+            .flat_map(|shop| {
+                tab_groups
+                    .iter()
+                    .filter(move |tab_group| tab_group.service && tab_group.from == shop.to)
+            })
+            .flat_map(|tab_group| {
+                tabs.iter()
+                    .filter(move |tab| tab.service && tab.tab_group == tab_group.to)
+            })
+            .map(|tab| RefShopTab {
+                goods: goods
+                    .iter()
+                    .filter(|good| good.service && good.tab == tab.tab)
+                    .filter_map(|good| {
+                        let package = packages.get(good.package.as_str())?;
+                        Some(RefShopGoods {
+                            slot: good.slot,
+                            item: package.item.clone(),
+                            upgrade_level: package.upgrade_level,
+                        })
+                    })
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+
+        if !shop_tabs.is_empty() {
+            tables.shops.insert(group.npc.clone(), shop_tabs);
+        }
+    }
+    Ok(tables)
+}
+
+/// The shops of all NPCs, keyed by the code name of the NPC.
+#[derive(Default)]
+pub struct ShopTables {
+    shops: HashMap<String, Vec<RefShopTab>>,
+}
+
+impl ShopTables {
+    /// Provides the tabs of the shop of the given NPC, in the order they're displayed in the client.
+    pub fn shop_of(&self, npc: &str) -> Option<&[RefShopTab]> {
+        self.shops.get(npc).map(|tabs| tabs.as_slice())
+    }
+}
+
+pub struct RefShopTab {
+    pub goods: Vec<RefShopGoods>,
+}
+
+impl RefShopTab {
+    pub fn good_at(&self, slot: u8) -> Option<&RefShopGoods> {
+        self.goods.iter().find(|good| good.slot == slot)
+    }
+}
+
+pub struct RefShopGoods {
+    pub slot: u8,
+    /// Code name of the item that is being sold.
+    pub item: String,
+    pub upgrade_level: u8,
+}
+
+fn parse_service(elements: &[&str]) -> Result<bool, ParseError> {
+    let service: u8 = elements.get(0).ok_or(ParseError::MissingColumn(0))?.parse()?;
+    Ok(service == 1)
+}
+
+fn parse_code(elements: &[&str], column: u8) -> Result<String, ParseError> {
+    Ok(elements
+        .get(column as usize)
+        .ok_or(ParseError::MissingColumn(column))?
+        .to_string())
+}
+
+struct RefShopGroup {
+    service: bool,
+    group: String,
+    npc: String,
+}
+
+impl FromStr for RefShopGroup {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let elements = s.split('\t').collect::<Vec<&str>>();
+        Ok(Self {
+            service: parse_service(&elements)?,
+            group: parse_code(&elements, 3)?,
+            npc: parse_code(&elements, 4)?,
+        })
+    }
+}
+
+/// A mapping from one code name to another, like from a shop group to a shop or from a shop to a tab group.
+struct RefCodeMapping {
+    service: bool,
+    from: String,
+    to: String,
+}
+
+impl FromStr for RefCodeMapping {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let elements = s.split('\t').collect::<Vec<&str>>();
+        Ok(Self {
+            service: parse_service(&elements)?,
+            from: parse_code(&elements, 2)?,
+            to: parse_code(&elements, 3)?,
+        })
+    }
+}
+
+struct RefShopTabRow {
+    service: bool,
+    tab: String,
+    tab_group: String,
+}
+
+impl FromStr for RefShopTabRow {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let elements = s.split('\t').collect::<Vec<&str>>();
+        Ok(Self {
+            service: parse_service(&elements)?,
+            tab: parse_code(&elements, 3)?,
+            tab_group: parse_code(&elements, 4)?,
+        })
+    }
+}
+
+struct RefShopGoodsRow {
+    service: bool,
+    tab: String,
+    package: String,
+    slot: u8,
+}
+
+impl FromStr for RefShopGoodsRow {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let elements = s.split('\t').collect::<Vec<&str>>();
+        Ok(Self {
+            service: parse_service(&elements)?,
+            tab: parse_code(&elements, 2)?,
+            package: parse_code(&elements, 3)?,
+            slot: elements.get(4).ok_or(ParseError::MissingColumn(4))?.parse()?,
+        })
+    }
+}
+
+struct RefPackageItem {
+    service: bool,
+    package: String,
+    item: String,
+    upgrade_level: u8,
+}
+
+impl FromStr for RefPackageItem {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let elements = s.split('\t').collect::<Vec<&str>>();
+        Ok(Self {
+            service: parse_service(&elements)?,
+            package: parse_code(&elements, 2)?,
+            item: parse_code(&elements, 3)?,
+            upgrade_level: elements.get(4).ok_or(ParseError::MissingColumn(4))?.parse()?,
+        })
+    }
+}
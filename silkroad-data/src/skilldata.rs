@@ -112,6 +112,47 @@ impl DataEntry for RefSkillData {
     }
 }
 
+#[cfg(any(test, feature = "test-util"))]
+impl RefSkillData {
+    /// Creates an attack skill without any requirements, costs, timings or effects, which tests can then adjust to
+    /// their needs. The skill is the only one in its group.
+    pub fn test_skill(ref_id: u32) -> Self {
+        RefSkillData {
+            ref_id,
+            group: ref_id,
+            id: format!("SKILL_TEST_{}", ref_id),
+            original: None,
+            level: 1,
+            type_: SkillType::Action,
+            next_in_chain: None,
+            timings: SkillTimings {
+                preparation_time: 0,
+                cast_time: 0,
+                duration: 0,
+                cooldown: 0,
+                next_delay: 0,
+            },
+            projectile_speed: 0,
+            buff_interference: 0,
+            auto_attack: AutoAttack::No,
+            range: 0,
+            requires_target: true,
+            target: TargetOption::ENEMY_MONSTER,
+            mastery: None,
+            mastery_level: None,
+            required_skills: Vec::new(),
+            sp: 0,
+            race: 0,
+            weapon_requirements: [None, None],
+            consumed_hp: 0,
+            consumed_mp: 0,
+            usage_chance: 0,
+            usage_type: 0,
+            params: Vec::new(),
+        }
+    }
+}
+
 impl FromStr for RefSkillData {
     type Err = ParseError;
 
@@ -246,7 +287,9 @@ pub enum SkillParam {
         max: u32,
         mag: u32,
     },
-    Chain {
+    /// The number of times the skill hits its targets, spread over its execution. This is not related to the skills
+    /// following in a chain, which are linked through [RefSkillData::next_in_chain].
+    MultiHit {
         count: u32,
         unknown: u32,
     },
@@ -572,7 +615,7 @@ fn parse_param(params: &[u32]) -> Option<(&[u32], SkillParam)> {
             let (param_data, remaining) = data.split_at(2);
             Some((
                 remaining,
-                SkillParam::Chain {
+                SkillParam::MultiHit {
                     count: param_data[0],
                     unknown: param_data[1],
                 },
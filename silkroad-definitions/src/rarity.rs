@@ -25,6 +25,16 @@ pub struct EntityRarity {
     kind: EntityRarityType,
 }
 
+impl EntityRarity {
+    pub fn kind(&self) -> EntityRarityType {
+        self.kind
+    }
+
+    pub fn is_party(&self) -> bool {
+        self.party
+    }
+}
+
 impl PartialEq<EntityRarityType> for EntityRarity {
     fn eq(&self, other: &EntityRarityType) -> bool {
         self.kind == *other
@@ -413,7 +413,7 @@ pub enum ObjectConsumable {
     Campfire,
     Trade(ObjectConsumableTrade),
     Quest(ObjectConsumableQuest),
-    AlchemyUpgrade,
+    AlchemyUpgrade(ObjectConsumableAlchemy),
     AlchemyImprovement,
     Event(ObjectConsumableEvent),
     ItemMall(ObjectConsumableItemMall),
@@ -434,7 +434,7 @@ impl ObjectConsumable {
             ObjectConsumable::Campfire => (7, 1),
             ObjectConsumable::Trade(trade) => (8, trade.into()),
             ObjectConsumable::Quest(quest) => (9, quest.into()),
-            ObjectConsumable::AlchemyUpgrade => (10, 1),
+            ObjectConsumable::AlchemyUpgrade(alchemy) => (10, alchemy.into()),
             ObjectConsumable::AlchemyImprovement => (11, 1),
             ObjectConsumable::Event(event) => (12, event.into()),
             ObjectConsumable::ItemMall(mall) => (13, mall.into()),
@@ -457,7 +457,9 @@ impl ObjectConsumable {
             7 => Some(ObjectConsumable::Campfire),
             8 => Some(ObjectConsumable::Trade(ObjectConsumableTrade::try_from(t3).ok()?)),
             9 => Some(ObjectConsumable::Quest(ObjectConsumableQuest::try_from(t3).ok()?)),
-            10 => Some(ObjectConsumable::AlchemyUpgrade),
+            10 => Some(ObjectConsumable::AlchemyUpgrade(
+                ObjectConsumableAlchemy::try_from(t3).ok()?,
+            )),
             11 => Some(ObjectConsumable::AlchemyImprovement),
             12 => Some(ObjectConsumable::Event(ObjectConsumableEvent::try_from(t3).ok()?)),
             13 => Some(ObjectConsumable::ItemMall(ObjectConsumableItemMall::try_from(t3).ok()?)),
@@ -495,6 +497,13 @@ pub enum ObjectConsumableCure {
     Super,
 }
 
+#[derive(IntoPrimitive, TryFromPrimitive, Copy, Clone, Eq, PartialOrd, PartialEq)]
+#[repr(u8)]
+pub enum ObjectConsumableAlchemy {
+    Elixir = 1,
+    LuckyPowder,
+}
+
 #[derive(IntoPrimitive, TryFromPrimitive, Copy, Clone, Eq, PartialOrd, PartialEq)]
 #[repr(u8)]
 pub enum ObjectConsumableScroll {
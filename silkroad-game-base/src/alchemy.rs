@@ -0,0 +1,93 @@
+use rand::Rng;
+
+/// The highest upgrade level an item can be reinforced to.
+pub const MAX_UPGRADE_LEVEL: u8 = 12;
+
+/// Increase of the chance to succeed when a lucky powder is used alongside the elixir.
+pub const LUCKY_POWDER_BONUS: f32 = 0.1;
+
+/// Chance for a reinforcement to succeed, indexed by the upgrade level the item currently has. The client data doesn't
+/// contain the chances, so these are a stopgap default for servers not configuring their own, not the official
+/// values.
+pub const DEFAULT_SUCCESS_CHANCES: [f32; MAX_UPGRADE_LEVEL as usize] =
+    [1.0, 0.9, 0.8, 0.7, 0.6, 0.5, 0.4, 0.3, 0.25, 0.2, 0.15, 0.1];
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ReinforceOutcome {
+    Success(u8),
+    Failure(u8),
+}
+
+impl ReinforceOutcome {
+    pub fn upgrade_level(&self) -> u8 {
+        match *self {
+            ReinforceOutcome::Success(level) | ReinforceOutcome::Failure(level) => level,
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(self, ReinforceOutcome::Success(_))
+    }
+}
+
+/// Provides the chance for reinforcing an item with the given upgrade level to succeed, given the success chances
+/// indexed by upgrade level. Items with an upgrade level not covered by the chances cannot be reinforced any further.
+pub fn reinforce_chance(chances: &[f32], upgrade_level: u8, lucky: bool) -> f32 {
+    let Some(chance) = chances.get(upgrade_level as usize) else {
+        return 0.0;
+    };
+    if lucky {
+        (chance + LUCKY_POWDER_BONUS).min(1.0)
+    } else {
+        *chance
+    }
+}
+
+/// Rolls the outcome of reinforcing an item with the given upgrade level. A success increases the level by one, while
+/// a failure decreases it by one, down to a minimum of zero.
+pub fn reinforce<R: Rng>(rng: &mut R, chances: &[f32], upgrade_level: u8, lucky: bool) -> ReinforceOutcome {
+    if rng.gen::<f32>() < reinforce_chance(chances, upgrade_level, lucky) {
+        ReinforceOutcome::Success(upgrade_level + 1)
+    } else {
+        ReinforceOutcome::Failure(upgrade_level.saturating_sub(1))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::alchemy::*;
+
+    #[test]
+    fn test_chances() {
+        assert_eq!(1.0, reinforce_chance(&DEFAULT_SUCCESS_CHANCES, 0, false));
+        assert_eq!(1.0, reinforce_chance(&DEFAULT_SUCCESS_CHANCES, 0, true));
+        assert!(
+            reinforce_chance(&DEFAULT_SUCCESS_CHANCES, 5, true) > reinforce_chance(&DEFAULT_SUCCESS_CHANCES, 5, false)
+        );
+        assert_eq!(0.0, reinforce_chance(&DEFAULT_SUCCESS_CHANCES, MAX_UPGRADE_LEVEL, true));
+
+        let configured = [0.5];
+        assert_eq!(0.5, reinforce_chance(&configured, 0, false));
+        assert_eq!(0.0, reinforce_chance(&configured, 1, false));
+    }
+
+    #[test]
+    fn test_reinforce() {
+        let mut rng = rand::thread_rng();
+        assert_eq!(
+            ReinforceOutcome::Success(1),
+            reinforce(&mut rng, &DEFAULT_SUCCESS_CHANCES, 0, false)
+        );
+        assert_eq!(
+            ReinforceOutcome::Failure(MAX_UPGRADE_LEVEL - 1),
+            reinforce(&mut rng, &DEFAULT_SUCCESS_CHANCES, MAX_UPGRADE_LEVEL, false)
+        );
+
+        let outcome = reinforce(&mut rng, &DEFAULT_SUCCESS_CHANCES, 5, false);
+        if outcome.is_success() {
+            assert_eq!(6, outcome.upgrade_level());
+        } else {
+            assert_eq!(4, outcome.upgrade_level());
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use crate::GlobalLocation;
+use cgmath::{Angle, Deg, InnerSpace, MetricSpace, Vector2};
+use silkroad_data::skilldata::{RefSkillData, SkillParam};
+
+/// The amount of entities an area skill hits if the skill does not limit the amount itself.
+const DEFAULT_MAX_TARGETS: usize = 8;
+/// Half of the angle covered by area skills which only hit in front of the caster.
+const FAN_HALF_ANGLE: Deg<f32> = Deg(60.0);
+
+/// Where the area of a skill is centered.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AreaOrigin {
+    Caster,
+    Target,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AreaShape {
+    /// Covers everything within the radius around the center.
+    Circle { radius: f32 },
+    /// Covers everything within the radius in front of the center.
+    Fan { radius: f32 },
+}
+
+/// The area in which a skill hits entities besides its main target.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AreaOfEffect {
+    pub origin: AreaOrigin,
+    pub shape: AreaShape,
+    pub max_targets: usize,
+}
+
+impl AreaOfEffect {
+    pub fn from_skill(skill: &RefSkillData) -> Option<Self> {
+        Self::from_params(&skill.params)
+    }
+
+    /// Reads the area from the skill parameters, if the skill has one. The meaning of the area parameters is not fully
+    /// known yet, so this only covers the parts we're reasonably sure about.
+    pub fn from_params(params: &[SkillParam]) -> Option<Self> {
+        params.iter().find_map(|param| match *param {
+            SkillParam::AOE {
+                origin,
+                area_type,
+                area_size,
+                val1,
+                ..
+            } => {
+                let radius = f32::from(area_size);
+                Some(AreaOfEffect {
+                    origin: if origin == 0 {
+                        AreaOrigin::Caster
+                    } else {
+                        AreaOrigin::Target
+                    },
+                    shape: match area_type {
+                        2 => AreaShape::Fan { radius },
+                        _ => AreaShape::Circle { radius },
+                    },
+                    max_targets: match val1 {
+                        0 => DEFAULT_MAX_TARGETS,
+                        max => max as usize,
+                    },
+                })
+            },
+            _ => None,
+        })
+    }
+
+    pub fn radius(&self) -> f32 {
+        match self.shape {
+            AreaShape::Circle { radius } | AreaShape::Fan { radius } => radius,
+        }
+    }
+
+    /// Checks if the point lies within the area placed at the given center and facing in the given direction.
+    pub fn contains(&self, center: GlobalLocation, facing: Vector2<f32>, point: GlobalLocation) -> bool {
+        let offset = point.0 - center.0;
+        if offset.magnitude2() > self.radius().powi(2) {
+            return false;
+        }
+
+        match self.shape {
+            AreaShape::Circle { .. } => true,
+            AreaShape::Fan { .. } => {
+                if offset.magnitude2() == 0.0 || facing.magnitude2() == 0.0 {
+                    return true;
+                }
+                Deg::from(facing.angle(offset)).normalize_signed().0.abs() <= FAN_HALF_ANGLE.0
+            },
+        }
+    }
+
+    /// Selects the candidates within the area, closest to the center first, up to the maximum amount of targets of
+    /// this area.
+    pub fn select_targets<T, I: IntoIterator<Item = (T, GlobalLocation)>>(
+        &self,
+        center: GlobalLocation,
+        facing: Vector2<f32>,
+        candidates: I,
+    ) -> Vec<T> {
+        let mut in_area: Vec<(f32, T)> = candidates
+            .into_iter()
+            .filter(|(_, location)| self.contains(center, facing, *location))
+            .map(|(candidate, location)| (center.0.distance2(location.0), candidate))
+            .collect();
+        in_area.sort_by(|(first, _), (second, _)| first.total_cmp(second));
+        in_area
+            .into_iter()
+            .take(self.max_targets)
+            .map(|(_, candidate)| candidate)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn location(x: f32, y: f32) -> GlobalLocation {
+        GlobalLocation(Vector2::new(x, y))
+    }
+
+    #[test]
+    fn test_area_from_params() {
+        let area = AreaOfEffect::from_params(&[
+            SkillParam::Duration(0),
+            SkillParam::AOE {
+                origin: 1,
+                area_type: 2,
+                area_size: 30,
+                unknown_type: 3,
+                val1: 0,
+                val2: 0,
+            },
+        ]);
+        assert_eq!(
+            Some(AreaOfEffect {
+                origin: AreaOrigin::Target,
+                shape: AreaShape::Fan { radius: 30.0 },
+                max_targets: DEFAULT_MAX_TARGETS,
+            }),
+            area
+        );
+        assert_eq!(None, AreaOfEffect::from_params(&[SkillParam::Duration(0)]));
+    }
+
+    #[test]
+    fn test_area_contains() {
+        let circle = AreaOfEffect {
+            origin: AreaOrigin::Caster,
+            shape: AreaShape::Circle { radius: 10.0 },
+            max_targets: 8,
+        };
+        let facing = Vector2::unit_x();
+        assert!(circle.contains(location(0.0, 0.0), facing, location(-5.0, 5.0)));
+        assert!(!circle.contains(location(0.0, 0.0), facing, location(10.0, 1.0)));
+
+        let fan = AreaOfEffect {
+            shape: AreaShape::Fan { radius: 10.0 },
+            ..circle
+        };
+        assert!(fan.contains(location(0.0, 0.0), facing, location(5.0, 5.0)));
+        assert!(fan.contains(location(0.0, 0.0), facing, location(0.0, 0.0)));
+        assert!(!fan.contains(location(0.0, 0.0), facing, location(-5.0, 0.0)));
+        assert!(!fan.contains(location(0.0, 0.0), facing, location(0.0, -5.0)));
+    }
+
+    #[test]
+    fn test_select_closest_targets() {
+        let area = AreaOfEffect {
+            origin: AreaOrigin::Caster,
+            shape: AreaShape::Circle { radius: 10.0 },
+            max_targets: 2,
+        };
+        let targets = area.select_targets(
+            location(0.0, 0.0),
+            Vector2::unit_x(),
+            [
+                (1, location(8.0, 0.0)),
+                (2, location(20.0, 0.0)),
+                (3, location(0.0, 2.0)),
+                (4, location(-5.0, 0.0)),
+            ],
+        );
+        assert_eq!(vec![3, 4], targets);
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{AttackPower, CharacterStats};
+use silkroad_data::skilldata::{RefSkillData, SkillParam};
+use std::iter::Sum;
+use std::ops::Add;
+use std::time::Duration;
+
+/// The bonuses a buff grants to the entity it has been applied to.
+#[derive(Copy, Clone, Default, Debug, Eq, PartialEq)]
+pub struct BuffEffect {
+    pub phys_defense: u32,
+    pub mag_defense: u32,
+    pub hit_rate: u32,
+    pub parry_rate: u32,
+    /// Additional chance in percent to land a critical hit.
+    pub critical: u8,
+    /// Increase of physical attack power in percent.
+    pub phys_damage: u16,
+    /// Increase of magical attack power in percent.
+    pub mag_damage: u16,
+    /// Increase of movement speed in percent.
+    pub speed: u16,
+}
+
+impl BuffEffect {
+    pub fn from_skill(skill: &RefSkillData) -> Self {
+        Self::from_params(&skill.params)
+    }
+
+    pub fn from_params(params: &[SkillParam]) -> Self {
+        params
+            .iter()
+            .map(|param| match *param {
+                SkillParam::IncreaseDefense { phys, mag, .. } => BuffEffect {
+                    phys_defense: phys,
+                    mag_defense: mag,
+                    ..Default::default()
+                },
+                SkillParam::IncreaseHitRate { hit_rate, .. } => BuffEffect {
+                    hit_rate: hit_rate.into(),
+                    ..Default::default()
+                },
+                SkillParam::IncreaseEvasion { parry, .. } => BuffEffect {
+                    parry_rate: parry.into(),
+                    ..Default::default()
+                },
+                SkillParam::IncreaseCrit { amount, .. } => BuffEffect {
+                    critical: amount,
+                    ..Default::default()
+                },
+                SkillParam::IncreaseDamage { phys, mag } => BuffEffect {
+                    phys_damage: phys.into(),
+                    mag_damage: mag.into(),
+                    ..Default::default()
+                },
+                SkillParam::IncreaseSpeed(speed) => BuffEffect {
+                    speed: speed.into(),
+                    ..Default::default()
+                },
+                _ => BuffEffect::default(),
+            })
+            .sum()
+    }
+
+    /// Factor with which the base movement speed should be multiplied.
+    pub fn speed_multiplier(&self) -> f32 {
+        1.0 + f32::from(self.speed) / 100.0
+    }
+}
+
+impl Add for BuffEffect {
+    type Output = BuffEffect;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        BuffEffect {
+            phys_defense: self.phys_defense + rhs.phys_defense,
+            mag_defense: self.mag_defense + rhs.mag_defense,
+            hit_rate: self.hit_rate + rhs.hit_rate,
+            parry_rate: self.parry_rate + rhs.parry_rate,
+            critical: self.critical.saturating_add(rhs.critical),
+            phys_damage: self.phys_damage + rhs.phys_damage,
+            mag_damage: self.mag_damage + rhs.mag_damage,
+            speed: self.speed + rhs.speed,
+        }
+    }
+}
+
+impl Sum for BuffEffect {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(BuffEffect::default(), Add::add)
+    }
+}
+
+/// Provides how long the buff created by the given skill lasts. Skills which deal damage or don't have a duration
+/// don't create a buff.
+pub fn buff_duration(skill: &RefSkillData) -> Option<Duration> {
+    if skill
+        .params
+        .iter()
+        .any(|param| matches!(param, SkillParam::Attack { .. }))
+    {
+        return None;
+    }
+
+    skill.params.iter().find_map(|param| match *param {
+        SkillParam::Duration(millis) if millis > 0 => Some(Duration::from_millis(millis.into())),
+        _ => None,
+    })
+}
+
+/// Checks if the buffs of the two skills cannot be active at the same time. This is the case for different levels of
+/// the same skill as well as for skills that create the same kind of buff.
+pub fn buffs_interfere(first: &RefSkillData, second: &RefSkillData) -> bool {
+    first.group == second.group || (first.buff_interference & second.buff_interference) != 0
+}
+
+fn increase_by_percent(power: AttackPower, percent: u16) -> AttackPower {
+    let factor = 1.0 + f32::from(percent) / 100.0;
+    AttackPower::new((power.min as f32 * factor) as u32, (power.max as f32 * factor) as u32)
+}
+
+impl CharacterStats {
+    /// Applies the combined effect of all active buffs on top of these stats.
+    pub fn with_buffs(self, effect: &BuffEffect) -> Self {
+        CharacterStats {
+            phys_attack: increase_by_percent(self.phys_attack, effect.phys_damage),
+            mag_attack: increase_by_percent(self.mag_attack, effect.mag_damage),
+            phys_defense: self.phys_defense + effect.phys_defense,
+            mag_defense: self.mag_defense + effect.mag_defense,
+            hit_rate: self.hit_rate + effect.hit_rate,
+            parry_rate: self.parry_rate + effect.parry_rate,
+            critical: self.critical.saturating_add(effect.critical).min(100),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_effect_from_params() {
+        let effect = BuffEffect::from_params(&[
+            SkillParam::Duration(30_000),
+            SkillParam::IncreaseDefense {
+                phys: 20,
+                mag: 10,
+                unknown: 0,
+            },
+            SkillParam::IncreaseSpeed(15),
+        ]);
+        assert_eq!(
+            BuffEffect {
+                phys_defense: 20,
+                mag_defense: 10,
+                speed: 15,
+                ..Default::default()
+            },
+            effect
+        );
+        assert_eq!(1.15, effect.speed_multiplier());
+    }
+
+    #[test]
+    fn test_combine_effects() {
+        let first = BuffEffect {
+            hit_rate: 5,
+            critical: 10,
+            ..Default::default()
+        };
+        let second = BuffEffect {
+            hit_rate: 10,
+            phys_damage: 20,
+            ..Default::default()
+        };
+        let combined: BuffEffect = [first, second].into_iter().sum();
+        assert_eq!(15, combined.hit_rate);
+        assert_eq!(10, combined.critical);
+        assert_eq!(20, combined.phys_damage);
+    }
+
+    #[test]
+    fn test_stats_with_buffs() {
+        let stats = CharacterStats {
+            phys_attack: AttackPower::new(100, 200),
+            phys_defense: 50,
+            critical: 95,
+            ..Default::default()
+        };
+        let buffed = stats.with_buffs(&BuffEffect {
+            phys_defense: 25,
+            phys_damage: 50,
+            critical: 10,
+            ..Default::default()
+        });
+        assert_eq!(AttackPower::new(150, 300), buffed.phys_attack);
+        assert_eq!(75, buffed.phys_defense);
+        assert_eq!(100, buffed.critical);
+        assert_eq!(stats, stats.with_buffs(&BuffEffect::default()));
+    }
+}
@@ -0,0 +1,168 @@
+use crate::damage::as_chance;
+use crate::{AttackPower, Defense, Item, Offense, Stats};
+use silkroad_data::itemdata::RefItemStats;
+
+/// How much attack power a single point of strength (physical) or intelligence (magical) grants.
+const ATTACK_PER_STAT: f32 = 0.5;
+/// How much defense a single point of strength (physical) or intelligence (magical) grants.
+const DEFENSE_PER_STAT: f32 = 0.25;
+/// The hit and parry rate a character has without any equipment, on top of their level.
+const BASE_RATE: u32 = 10;
+
+/// The stats of a single equipped item, including the bonus gained through its upgrade level.
+fn upgraded_stats(item: &Item) -> RefItemStats {
+    let stats = item.reference.stats;
+    let upgrade = item.upgrade_level() as f32;
+    RefItemStats {
+        phys_defense: stats.phys_defense + stats.phys_defense_inc * upgrade,
+        mag_defense: stats.mag_defense + stats.mag_defense_inc * upgrade,
+        phys_attack_min: stats.phys_attack_min + stats.phys_attack_inc * upgrade,
+        phys_attack_max: stats.phys_attack_max + stats.phys_attack_inc * upgrade,
+        mag_attack_min: stats.mag_attack_min + stats.mag_attack_inc * upgrade,
+        mag_attack_max: stats.mag_attack_max + stats.mag_attack_inc * upgrade,
+        hit_rate: stats.hit_rate + stats.hit_rate_inc * upgrade,
+        parry_rate: stats.parry_rate + stats.parry_rate_inc * upgrade,
+        ..stats
+    }
+}
+
+/// The combat stats of a character, resulting from its level, its strength and intelligence as well as the items it
+/// has equipped.
+#[derive(Copy, Clone, Default, Debug, Eq, PartialEq)]
+pub struct CharacterStats {
+    pub phys_attack: AttackPower,
+    pub mag_attack: AttackPower,
+    pub phys_defense: u32,
+    pub mag_defense: u32,
+    pub hit_rate: u32,
+    pub parry_rate: u32,
+    /// Chance in percent to block a hit entirely.
+    pub block: u8,
+    /// Chance in percent to land a critical hit.
+    pub critical: u8,
+}
+
+impl CharacterStats {
+    pub fn calculate<'a>(level: u8, stats: &Stats, equipment: impl IntoIterator<Item = &'a Item>) -> Self {
+        let total = equipment
+            .into_iter()
+            .map(upgraded_stats)
+            .fold(RefItemStats::default(), |total, item| RefItemStats {
+                phys_defense: total.phys_defense + item.phys_defense,
+                block_ratio: total.block_ratio + item.block_ratio,
+                mag_defense: total.mag_defense + item.mag_defense,
+                phys_attack_min: total.phys_attack_min + item.phys_attack_min,
+                phys_attack_max: total.phys_attack_max + item.phys_attack_max,
+                mag_attack_min: total.mag_attack_min + item.mag_attack_min,
+                mag_attack_max: total.mag_attack_max + item.mag_attack_max,
+                critical: total.critical + item.critical,
+                hit_rate: total.hit_rate + item.hit_rate,
+                parry_rate: total.parry_rate + item.parry_rate,
+                ..total
+            });
+
+        let strength = stats.strength() as f32;
+        let intelligence = stats.intelligence() as f32;
+        let phys_bonus = strength * ATTACK_PER_STAT;
+        let mag_bonus = intelligence * ATTACK_PER_STAT;
+        CharacterStats {
+            phys_attack: AttackPower::new(
+                (total.phys_attack_min + phys_bonus) as u32,
+                (total.phys_attack_max + phys_bonus) as u32,
+            ),
+            mag_attack: AttackPower::new(
+                (total.mag_attack_min + mag_bonus) as u32,
+                (total.mag_attack_max + mag_bonus) as u32,
+            ),
+            phys_defense: (total.phys_defense + strength * DEFENSE_PER_STAT) as u32,
+            mag_defense: (total.mag_defense + intelligence * DEFENSE_PER_STAT) as u32,
+            hit_rate: BASE_RATE + level as u32 + total.hit_rate as u32,
+            parry_rate: BASE_RATE + level as u32 + total.parry_rate as u32,
+            block: as_chance(total.block_ratio),
+            critical: as_chance(total.critical),
+        }
+    }
+
+    pub fn offense(&self, level: u8) -> Offense {
+        Offense {
+            level,
+            phys: self.phys_attack,
+            mag: self.mag_attack,
+            critical: self.critical,
+            hit_rate: self.hit_rate,
+        }
+    }
+
+    pub fn defense(&self, level: u8) -> Defense {
+        Defense {
+            level,
+            phys: self.phys_defense,
+            mag: self.mag_defense,
+            block: self.block,
+            parry_rate: self.parry_rate,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ItemTypeData;
+    use once_cell::sync::Lazy;
+    use silkroad_data::common::{RefCommon, RefOrigin};
+    use silkroad_data::itemdata::{RefBiologicalType, RefItemData};
+    use silkroad_definitions::type_id::{ObjectEquippable, ObjectItem, ObjectType, ObjectWeaponType};
+
+    static WEAPON_DATA: Lazy<RefItemData> = Lazy::new(|| RefItemData {
+        common: RefCommon {
+            ref_id: 1,
+            id: "TestWeapon".to_string(),
+            type_id: ObjectType::Item(ObjectItem::Equippable(ObjectEquippable::Weapon(
+                ObjectWeaponType::Blade,
+            )))
+            .type_id(),
+            country: RefOrigin::Chinese,
+            despawn_time: Default::default(),
+        },
+        price: 100,
+        sell_price: 25,
+        max_stack_size: 1,
+        range: None,
+        required_level: None,
+        biological_type: RefBiologicalType::Both,
+        item_class: 1,
+        params: [0, 0, 0, 0],
+        stats: RefItemStats {
+            phys_attack_min: 20.0,
+            phys_attack_max: 30.0,
+            phys_attack_inc: 5.0,
+            hit_rate: 10.0,
+            hit_rate_inc: 2.0,
+            ..Default::default()
+        },
+    });
+
+    #[test]
+    fn test_without_equipment() {
+        let stats = CharacterStats::calculate(1, &Stats::default(), []);
+        assert_eq!(AttackPower::new(10, 10), stats.phys_attack);
+        assert_eq!(AttackPower::new(10, 10), stats.mag_attack);
+        assert_eq!(5, stats.phys_defense);
+        assert_eq!(5, stats.mag_defense);
+        assert_eq!(11, stats.hit_rate);
+        assert_eq!(11, stats.parry_rate);
+    }
+
+    #[test]
+    fn test_upgraded_weapon() {
+        let item = Item {
+            reference: &WEAPON_DATA,
+            variance: None,
+            type_data: ItemTypeData::Equipment { upgrade_level: 2 },
+        };
+        let stats = CharacterStats::calculate(1, &Stats::default(), [&item]);
+        assert_eq!(AttackPower::new(40, 50), stats.phys_attack);
+        assert_eq!(AttackPower::new(10, 10), stats.mag_attack);
+        assert_eq!(25, stats.hit_rate);
+    }
+}
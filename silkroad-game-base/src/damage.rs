@@ -0,0 +1,327 @@
+use rand::Rng;
+use silkroad_data::characterdata::RefCharacterData;
+use silkroad_data::skilldata::{RefSkillData, SkillParam};
+
+/// Fraction by which the damage is reduced for each level the attacker is below its target.
+const LEVEL_PENALTY: f32 = 0.05;
+/// The lowest fraction of damage that remains, no matter how big the level difference is.
+const MIN_LEVEL_FACTOR: f32 = 0.2;
+const CRITICAL_MULTIPLIER: u32 = 2;
+/// The lowest chance in percent for a hit to land, no matter how much higher the parry rate of the target is.
+const MIN_HIT_CHANCE: u32 = 20;
+
+pub(crate) fn as_chance(ratio: f32) -> u8 {
+    ratio.clamp(0.0, 100.0) as u8
+}
+
+#[derive(Copy, Clone, Default, Debug, Eq, PartialEq)]
+pub struct AttackPower {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl AttackPower {
+    pub fn new(min: u32, max: u32) -> Self {
+        AttackPower { min, max: max.max(min) }
+    }
+
+    fn roll<R: Rng>(&self, rng: &mut R) -> u32 {
+        rng.gen_range(self.min..=self.max)
+    }
+}
+
+/// Everything about the attacking entity that influences the damage it deals.
+#[derive(Copy, Clone, Default)]
+pub struct Offense {
+    pub level: u8,
+    pub phys: AttackPower,
+    pub mag: AttackPower,
+    /// Chance in percent to land a critical hit.
+    pub critical: u8,
+    pub hit_rate: u32,
+}
+
+impl Offense {
+    /// Monsters don't have any attack power on their own, all their damage comes from the skill they use.
+    pub fn for_monster(data: &RefCharacterData) -> Self {
+        Offense {
+            level: data.level,
+            critical: as_chance(data.critical),
+            hit_rate: data.hit_ratio as u32,
+            ..Default::default()
+        }
+    }
+}
+
+/// Everything about the attacked entity that influences the damage it receives.
+#[derive(Copy, Clone, Default)]
+pub struct Defense {
+    pub level: u8,
+    pub phys: u32,
+    pub mag: u32,
+    /// Chance in percent to block a hit entirely.
+    pub block: u8,
+    pub parry_rate: u32,
+}
+
+impl Defense {
+    pub fn for_monster(data: &RefCharacterData) -> Self {
+        Defense {
+            level: data.level,
+            phys: data.phys_defense as u32,
+            mag: data.mag_defense as u32,
+            block: as_chance(data.block_ratio),
+            parry_rate: data.parry_ratio as u32,
+        }
+    }
+}
+
+/// The damage part of a skill, as given by its [SkillParam::Attack].
+#[derive(Copy, Clone)]
+pub struct SkillDamage {
+    /// Percentage of the physical attack power of the attacker that is applied.
+    pub phys_percent: u32,
+    /// Percentage of the magical attack power of the attacker that is applied.
+    pub mag_percent: u32,
+    /// Flat damage added on top of the attack power of the attacker.
+    pub power: AttackPower,
+}
+
+impl SkillDamage {
+    pub fn from_skill(skill: &RefSkillData) -> Option<Self> {
+        skill.params.iter().find_map(|param| match param {
+            SkillParam::Attack {
+                phys, min, max, mag, ..
+            } => Some(SkillDamage {
+                phys_percent: *phys,
+                mag_percent: *mag,
+                power: AttackPower::new(*min, *max),
+            }),
+            _ => None,
+        })
+    }
+
+    fn is_magical(&self) -> bool {
+        self.phys_percent == 0 && self.mag_percent > 0
+    }
+}
+
+/// Provides how many times the skill hits its targets. Every hit deals damage on its own. A skill continuing into the
+/// next skill of its chain only hits once, as the further hits are dealt by the following skills.
+pub fn hit_count(skill: &RefSkillData) -> u32 {
+    if skill.next_in_chain.is_some() {
+        return 1;
+    }
+    skill
+        .params
+        .iter()
+        .find_map(|param| match param {
+            SkillParam::MultiHit { count, .. } => Some(*count),
+            _ => None,
+        })
+        .unwrap_or(1)
+        .max(1)
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DamageOutcome {
+    Normal(u32),
+    Critical(u32),
+    Blocked,
+    Missed,
+}
+
+impl DamageOutcome {
+    pub fn amount(&self) -> u32 {
+        match self {
+            DamageOutcome::Normal(amount) | DamageOutcome::Critical(amount) => *amount,
+            DamageOutcome::Blocked | DamageOutcome::Missed => 0,
+        }
+    }
+
+    /// Checks if the hit didn't reach the target at all, either because it missed or because it was blocked.
+    pub fn is_avoided(&self) -> bool {
+        matches!(self, DamageOutcome::Blocked | DamageOutcome::Missed)
+    }
+}
+
+/// The chance in percent for a hit to land. Hits always land as long as the hit rate of the attacker is at least as
+/// high as the parry rate of the target, otherwise the chance drops with the ratio between the two.
+fn hit_chance(hit_rate: u32, parry_rate: u32) -> u32 {
+    if parry_rate <= hit_rate {
+        return 100;
+    }
+    (hit_rate.saturating_mul(100) / parry_rate).max(MIN_HIT_CHANCE)
+}
+
+/// Rolls the damage of a single hit of the given skill.
+///
+/// The hit may first miss the target, depending on the hit rate of the attacker and the parry rate of the target.
+/// If it lands, the target gets the chance to block the hit, in which case no damage is dealt either. Otherwise, the
+/// physical and magical parts of the damage are each reduced by the respective defense of the target. A hit that
+/// isn't avoided always deals at least one point of damage.
+pub fn calculate_damage<R: Rng>(
+    rng: &mut R,
+    skill: &SkillDamage,
+    attacker: &Offense,
+    target: &Defense,
+) -> DamageOutcome {
+    if rng.gen_range(0..100) >= hit_chance(attacker.hit_rate, target.parry_rate) {
+        return DamageOutcome::Missed;
+    }
+
+    if rng.gen_range(0..100) < target.block {
+        return DamageOutcome::Blocked;
+    }
+
+    let damage = base_damage(
+        skill,
+        attacker,
+        target,
+        skill.power.roll(rng),
+        attacker.phys.roll(rng),
+        attacker.mag.roll(rng),
+    );
+
+    if rng.gen_range(0..100) < attacker.critical {
+        DamageOutcome::Critical(damage * CRITICAL_MULTIPLIER)
+    } else {
+        DamageOutcome::Normal(damage)
+    }
+}
+
+fn base_damage(skill: &SkillDamage, attacker: &Offense, target: &Defense, flat: u32, phys: u32, mag: u32) -> u32 {
+    let (phys_flat, mag_flat) = if skill.is_magical() { (0, flat) } else { (flat, 0) };
+    let phys = (phys * skill.phys_percent / 100 + phys_flat).saturating_sub(target.phys);
+    let mag = (mag * skill.mag_percent / 100 + mag_flat).saturating_sub(target.mag);
+
+    let level_difference = target.level.saturating_sub(attacker.level);
+    let level_factor = (1.0 - level_difference as f32 * LEVEL_PENALTY).max(MIN_LEVEL_FACTOR);
+
+    (((phys + mag) as f32 * level_factor) as u32).max(1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::num::NonZeroU32;
+
+    const PHYSICAL_SKILL: SkillDamage = SkillDamage {
+        phys_percent: 100,
+        mag_percent: 0,
+        power: AttackPower { min: 10, max: 10 },
+    };
+
+    fn attacker(level: u8, critical: u8) -> Offense {
+        Offense {
+            level,
+            phys: AttackPower::new(50, 50),
+            mag: AttackPower::new(20, 20),
+            critical,
+            hit_rate: 10,
+        }
+    }
+
+    fn target(level: u8, phys: u32, block: u8) -> Defense {
+        Defense {
+            level,
+            phys,
+            mag: 0,
+            block,
+            parry_rate: 10,
+        }
+    }
+
+    #[test]
+    fn test_base_damage() {
+        let mut rng = rand::thread_rng();
+        assert_eq!(
+            DamageOutcome::Normal(40),
+            calculate_damage(&mut rng, &PHYSICAL_SKILL, &attacker(1, 0), &target(1, 20, 0))
+        );
+
+        let magical = SkillDamage {
+            phys_percent: 0,
+            mag_percent: 150,
+            power: AttackPower::new(10, 10),
+        };
+        assert_eq!(
+            DamageOutcome::Normal(40),
+            calculate_damage(&mut rng, &magical, &attacker(1, 0), &target(1, 20, 0))
+        );
+    }
+
+    #[test]
+    fn test_defense_and_level() {
+        let mut rng = rand::thread_rng();
+        assert_eq!(
+            DamageOutcome::Normal(1),
+            calculate_damage(&mut rng, &PHYSICAL_SKILL, &attacker(1, 0), &target(1, 100, 0))
+        );
+        assert_eq!(
+            DamageOutcome::Normal(30),
+            calculate_damage(&mut rng, &PHYSICAL_SKILL, &attacker(1, 0), &target(11, 0, 0))
+        );
+        assert_eq!(
+            DamageOutcome::Normal(12),
+            calculate_damage(&mut rng, &PHYSICAL_SKILL, &attacker(1, 0), &target(50, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_critical_and_block() {
+        let mut rng = rand::thread_rng();
+        assert_eq!(
+            DamageOutcome::Critical(120),
+            calculate_damage(&mut rng, &PHYSICAL_SKILL, &attacker(1, 100), &target(1, 0, 0))
+        );
+        assert_eq!(
+            DamageOutcome::Blocked,
+            calculate_damage(&mut rng, &PHYSICAL_SKILL, &attacker(1, 100), &target(1, 0, 100))
+        );
+    }
+
+    #[test]
+    fn test_miss() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            assert_ne!(
+                DamageOutcome::Missed,
+                calculate_damage(&mut rng, &PHYSICAL_SKILL, &attacker(1, 0), &target(1, 20, 0))
+            );
+        }
+
+        let evasive = Defense {
+            parry_rate: 1000,
+            ..target(1, 20, 0)
+        };
+        let misses = (0..100)
+            .filter(|_| calculate_damage(&mut rng, &PHYSICAL_SKILL, &attacker(1, 0), &evasive) == DamageOutcome::Missed)
+            .count();
+        assert!(misses > 0 && misses < 100);
+
+        assert_eq!(100, hit_chance(10, 10));
+        assert_eq!(50, hit_chance(10, 20));
+        assert_eq!(MIN_HIT_CHANCE, hit_chance(0, 20));
+    }
+
+    #[test]
+    fn test_hit_count() {
+        let mut skill = RefSkillData::test_skill(1);
+        skill.params = vec![SkillParam::Duration(0)];
+        assert_eq!(1, hit_count(&skill));
+
+        skill.params = vec![SkillParam::MultiHit { count: 3, unknown: 0 }];
+        assert_eq!(3, hit_count(&skill));
+        skill.params = vec![SkillParam::MultiHit { count: 0, unknown: 0 }];
+        assert_eq!(1, hit_count(&skill));
+    }
+
+    #[test]
+    fn test_chained_skill_hits_once() {
+        let mut skill = RefSkillData::test_skill(1);
+        skill.params = vec![SkillParam::MultiHit { count: 3, unknown: 0 }];
+        skill.next_in_chain = NonZeroU32::new(2);
+        assert_eq!(1, hit_count(&skill));
+    }
+}
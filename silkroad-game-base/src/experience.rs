@@ -0,0 +1,110 @@
+use silkroad_data::characterdata::RefCharacterData;
+use silkroad_definitions::rarity::{EntityRarity, EntityRarityType};
+
+/// Fraction of experience lost for each level the receiver is above the monster.
+const HIGHER_LEVEL_PENALTY: f32 = 0.1;
+/// The lowest fraction of experience that remains when killing monsters far below one's own level.
+const MIN_LEVEL_FACTOR: f32 = 0.05;
+/// Fraction of additional experience for each level the receiver is below the monster.
+const LOWER_LEVEL_BONUS: f32 = 0.05;
+const MAX_LEVEL_FACTOR: f32 = 1.5;
+/// Party monsters are meant to be fought by a group and thus give more experience.
+const PARTY_MULTIPLIER: f32 = 2.0;
+
+/// Multiplier for the base experience of a monster, depending on how strong it is.
+fn rarity_multiplier(rarity: EntityRarity) -> f32 {
+    let base = match rarity.kind() {
+        EntityRarityType::Normal | EntityRarityType::UnknownCos => 1.0,
+        EntityRarityType::Champion => 2.0,
+        EntityRarityType::Strong => 3.0,
+        EntityRarityType::Elite => 5.0,
+        EntityRarityType::Giant => 7.0,
+        EntityRarityType::Titan => 15.0,
+        // Uniques already come with a much higher experience value in their reference data.
+        EntityRarityType::Unique | EntityRarityType::Unique2 => 1.0,
+    };
+    if rarity.is_party() {
+        base * PARTY_MULTIPLIER
+    } else {
+        base
+    }
+}
+
+fn level_factor(receiver_level: u8, monster_level: u8) -> f32 {
+    let difference = receiver_level as f32 - monster_level as f32;
+    if difference > 0.0 {
+        (1.0 - difference * HIGHER_LEVEL_PENALTY).max(MIN_LEVEL_FACTOR)
+    } else {
+        (1.0 - difference * LOWER_LEVEL_BONUS).min(MAX_LEVEL_FACTOR)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ExperienceGain {
+    pub exp: u64,
+    pub sp: u64,
+}
+
+/// A killed entity that hands out experience to everyone that helped killing it.
+#[derive(Copy, Clone)]
+pub struct ExperienceSource {
+    pub exp: u32,
+    pub level: u8,
+    pub rarity: EntityRarity,
+}
+
+impl ExperienceSource {
+    pub fn new(data: &RefCharacterData, rarity: EntityRarity) -> Self {
+        ExperienceSource {
+            exp: data.exp,
+            level: data.level,
+            rarity,
+        }
+    }
+
+    /// Calculates the experience a receiver of the given level gets, if they dealt `share` (between `0.0` and
+    /// `1.0`) of the total damage. SP experience is derived from the same base value as the experience.
+    pub fn experience_for(&self, receiver_level: u8, share: f32) -> ExperienceGain {
+        let total = self.exp as f32
+            * rarity_multiplier(self.rarity)
+            * level_factor(receiver_level, self.level)
+            * share.clamp(0.0, 1.0);
+        ExperienceGain {
+            exp: total as u64,
+            sp: total as u64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn source(exp: u32, level: u8, rarity: EntityRarityType) -> ExperienceSource {
+        ExperienceSource {
+            exp,
+            level,
+            rarity: rarity.into(),
+        }
+    }
+
+    #[test]
+    fn test_experience_share() {
+        let monster = source(100, 10, EntityRarityType::Normal);
+        assert_eq!(ExperienceGain { exp: 100, sp: 100 }, monster.experience_for(10, 1.0));
+        assert_eq!(ExperienceGain { exp: 25, sp: 25 }, monster.experience_for(10, 0.25));
+
+        let champion = source(100, 10, EntityRarityType::Champion);
+        assert_eq!(200, champion.experience_for(10, 1.0).exp);
+    }
+
+    #[test]
+    fn test_level_difference() {
+        let monster = source(100, 10, EntityRarityType::Normal);
+        assert_eq!(50, monster.experience_for(15, 1.0).exp);
+        assert_eq!(5, monster.experience_for(40, 1.0).exp);
+
+        let stronger = source(100, 20, EntityRarityType::Normal);
+        assert_eq!(150, stronger.experience_for(1, 1.0).exp);
+    }
+}
@@ -339,6 +339,17 @@ impl Inventory {
         self.non_equipment_slots().find(|slot| !self.items.contains_key(slot))
     }
 
+    pub fn has_free_slot(&self) -> bool {
+        self.empty_slot().is_some()
+    }
+
+    /// Counts the slots outside the equipment that currently don't hold an item.
+    pub fn free_slots(&self) -> usize {
+        self.non_equipment_slots()
+            .filter(|slot| !self.items.contains_key(slot))
+            .count()
+    }
+
     pub fn move_item(&mut self, source: u8, target: u8, amount: u16) -> Result<u16, MoveError> {
         if let Some(mut source_item) = self.items.remove(&source) {
             if let Some(mut target_item) = self.items.remove(&target) {
@@ -490,6 +501,69 @@ impl Inventory {
         None
     }
 
+    /// Uses up a single item of the stack in the given slot, removing the item once the stack is empty. Returns the
+    /// amount that is left in the slot or `None` if there is no consumable item in that slot.
+    pub fn consume_at(&mut self, slot: u8) -> Option<u16> {
+        let item = self.items.get_mut(&slot)?;
+        let old_data = item.type_data;
+        item.change_stack_size(-1).ok()?;
+        let remaining = item.stack_size();
+        if remaining == 0 {
+            self.items.remove(&slot);
+            self.changes.push(InventoryChange::RemoveItem { slot });
+        } else {
+            self.changes.push(InventoryChange::ChangeTypeData {
+                slot,
+                old_item: old_data,
+                new_item: item.type_data,
+            });
+        }
+        Some(remaining)
+    }
+
+    /// Changes the upgrade level of the equipment in the given slot.
+    pub fn set_upgrade_level(&mut self, slot: u8, upgrade_level: u8) -> Result<(), MoveError> {
+        let item = self.items.get_mut(&slot).ok_or(MoveError::ItemDoesNotExist)?;
+        let ItemTypeData::Equipment { .. } = item.type_data else {
+            return Err(MoveError::Impossible);
+        };
+
+        let old_data = item.type_data;
+        item.type_data = ItemTypeData::Equipment { upgrade_level };
+        self.changes.push(InventoryChange::ChangeTypeData {
+            slot,
+            old_item: old_data,
+            new_item: item.type_data,
+        });
+        Ok(())
+    }
+
+    /// Takes the given amount of the item in the given slot out of the inventory, removing the item entirely if the
+    /// amount covers the whole stack. Returns the item that was taken with its stack size set to the taken amount.
+    pub fn take_from(&mut self, slot: u8, amount: u16) -> Result<Item, MoveError> {
+        let item = self.items.get_mut(&slot).ok_or(MoveError::ItemDoesNotExist)?;
+        if amount == 0 || amount >= item.stack_size() {
+            let item = self
+                .items
+                .remove(&slot)
+                .expect("Item should still exist just after checking");
+            self.changes.push(InventoryChange::RemoveItem { slot });
+            return Ok(item);
+        }
+
+        let old_data = item.type_data;
+        item.change_stack_size(-(amount as i16))?;
+        self.changes.push(InventoryChange::ChangeTypeData {
+            slot,
+            old_item: old_data,
+            new_item: item.type_data,
+        });
+        Ok(Item {
+            type_data: ItemTypeData::Consumable { amount },
+            ..*item
+        })
+    }
+
     pub fn remove_item(&mut self, item: Item) -> Result<u16, MoveError> {
         let mut to_remove = item.stack_size();
         let mut removed = 0;
@@ -565,11 +639,14 @@ mod test {
             despawn_time: Default::default(),
         },
         price: 100,
+        sell_price: 25,
         max_stack_size: 50,
         range: None,
         required_level: None,
         biological_type: RefBiologicalType::Both,
+        item_class: 1,
         params: [0, 0, 0, 0],
+        stats: Default::default(),
     });
 
     static SECOND_ITEM_DATA: Lazy<RefItemData> = Lazy::new(|| RefItemData {
@@ -584,11 +661,14 @@ mod test {
             despawn_time: Default::default(),
         },
         price: 100,
+        sell_price: 25,
         max_stack_size: 50,
         range: None,
         required_level: None,
         biological_type: RefBiologicalType::Both,
+        item_class: 1,
         params: [0, 0, 0, 0],
+        stats: Default::default(),
     });
 
     #[test]
@@ -670,4 +750,86 @@ mod test {
         assert_eq!(1, changes.len());
         assert!(matches!(changes.pop().unwrap(), InventoryChange::RemoveItem { slot }));
     }
+
+    #[test]
+    pub fn test_consume_item() {
+        let mut inv = Inventory::default();
+        let reference = FIRST_ITEM_DATA.deref();
+        let slot = inv
+            .add_item(Item {
+                reference,
+                variance: None,
+                type_data: ItemTypeData::Consumable { amount: 2 },
+            })
+            .unwrap();
+
+        assert_eq!(Some(1), inv.consume_at(slot));
+        assert_eq!(Some(0), inv.consume_at(slot));
+        assert!(inv.get_item_at(slot).is_none());
+        assert_eq!(None, inv.consume_at(slot));
+    }
+
+    #[test]
+    pub fn test_take_from() {
+        let mut inv = Inventory::default();
+        let reference = FIRST_ITEM_DATA.deref();
+        let slot = inv
+            .add_item(Item {
+                reference,
+                variance: None,
+                type_data: ItemTypeData::Consumable { amount: 5 },
+            })
+            .unwrap();
+
+        let taken = inv.take_from(slot, 2).unwrap();
+        assert_eq!(2, taken.stack_size());
+        assert_eq!(3, inv.get_item_at(slot).unwrap().stack_size());
+
+        let taken = inv.take_from(slot, 10).unwrap();
+        assert_eq!(3, taken.stack_size());
+        assert!(inv.get_item_at(slot).is_none());
+        assert!(matches!(inv.take_from(slot, 1), Err(MoveError::ItemDoesNotExist)));
+    }
+
+    #[test]
+    pub fn test_free_slots() {
+        let mut inv = Inventory::default();
+        let free = inv.free_slots();
+        assert_eq!(inv.size() - 13, free);
+
+        inv.add_item(Item {
+            reference: FIRST_ITEM_DATA.deref(),
+            variance: None,
+            type_data: ItemTypeData::Consumable { amount: 5 },
+        })
+        .unwrap();
+        assert_eq!(free - 1, inv.free_slots());
+    }
+
+    #[test]
+    pub fn test_set_upgrade_level() {
+        let mut inv = Inventory::default();
+        let reference = FIRST_ITEM_DATA.deref();
+        let equipment = inv
+            .add_item(Item {
+                reference,
+                variance: None,
+                type_data: ItemTypeData::Equipment { upgrade_level: 0 },
+            })
+            .unwrap();
+        let consumable = inv
+            .add_item(Item {
+                reference: SECOND_ITEM_DATA.deref(),
+                variance: None,
+                type_data: ItemTypeData::Consumable { amount: 1 },
+            })
+            .unwrap();
+
+        inv.set_upgrade_level(equipment, 3).unwrap();
+        assert_eq!(3, inv.get_item_at(equipment).unwrap().upgrade_level());
+        assert!(matches!(
+            inv.set_upgrade_level(consumable, 3),
+            Err(MoveError::Impossible)
+        ));
+    }
 }
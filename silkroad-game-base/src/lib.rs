@@ -1,19 +1,36 @@
+mod alchemy;
+mod area;
+mod buff;
 mod changes;
 mod character;
+mod character_stats;
+mod damage;
+mod experience;
 mod inventory;
+mod loot;
 mod movement;
 mod pos;
 mod skill;
 mod stats;
+mod status;
 mod vec;
 
+pub use alchemy::*;
+pub use area::*;
+pub use buff::*;
 pub use changes::*;
 pub use character::*;
+pub use character_stats::*;
+pub use damage::*;
+pub use experience::*;
 pub use inventory::*;
+pub use loot::*;
 pub use movement::*;
 pub use pos::*;
+use silkroad_definitions::type_id::ObjectRace;
 pub use skill::*;
 pub use stats::*;
+pub use status::*;
 pub use vec::*;
 
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -22,6 +39,15 @@ pub enum Race {
     Chinese,
 }
 
+impl From<ObjectRace> for Race {
+    fn from(value: ObjectRace) -> Self {
+        match value {
+            ObjectRace::Chinese => Race::Chinese,
+            ObjectRace::European => Race::European,
+        }
+    }
+}
+
 impl Race {
     pub fn as_skill_origin(self) -> u8 {
         match self {
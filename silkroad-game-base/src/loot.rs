@@ -0,0 +1,197 @@
+use rand::Rng;
+use silkroad_data::drop::{DropSource, DropTables, RefDropGroupItem};
+use silkroad_definitions::rarity::{EntityRarity, EntityRarityType};
+
+/// A single item that should be dropped, given by its reference id.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Loot {
+    pub ref_id: u32,
+    pub amount: u16,
+}
+
+/// How many times monsters get to roll their drops, depending on their rarity. The drop tables of the client don't
+/// contain these, so the defaults are only a rough guess and servers are expected to configure their own.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct DropRolls {
+    pub normal: usize,
+    pub champion: usize,
+    pub elite: usize,
+    pub giant: usize,
+    pub titan: usize,
+    pub unique: usize,
+    /// Multiplier for the rolls of monsters meant to be fought by a party.
+    pub party_multiplier: usize,
+}
+
+impl DropRolls {
+    pub fn for_rarity(&self, rarity: EntityRarity) -> usize {
+        let rolls = match rarity.kind() {
+            EntityRarityType::Normal | EntityRarityType::UnknownCos => self.normal,
+            EntityRarityType::Champion | EntityRarityType::Strong => self.champion,
+            EntityRarityType::Elite => self.elite,
+            EntityRarityType::Giant => self.giant,
+            EntityRarityType::Titan => self.titan,
+            EntityRarityType::Unique | EntityRarityType::Unique2 => self.unique,
+        };
+        if rarity.is_party() {
+            rolls * self.party_multiplier
+        } else {
+            rolls
+        }
+    }
+}
+
+impl Default for DropRolls {
+    fn default() -> Self {
+        DropRolls {
+            normal: 1,
+            champion: 2,
+            elite: 3,
+            giant: 5,
+            titan: 7,
+            unique: 10,
+            party_multiplier: 2,
+        }
+    }
+}
+
+fn select_from_group<R: Rng>(rng: &mut R, items: &[RefDropGroupItem]) -> Option<u32> {
+    let total: f32 = items.iter().map(|item| item.select_ratio).sum();
+    if total <= 0.0 {
+        return None;
+    }
+
+    let mut selection = rng.gen_range(0.0..total);
+    for item in items {
+        if selection < item.select_ratio {
+            return Some(item.item_id);
+        }
+        selection -= item.select_ratio;
+    }
+    items.last().map(|item| item.item_id)
+}
+
+/// Rolls all drops of the given monster. Each drop entry of the monster gets a chance to drop, multiplied by the
+/// `rate`, for every roll the monster gets based on its rarity.
+pub fn roll_loot<R: Rng>(
+    rng: &mut R,
+    tables: &DropTables,
+    rolls: &DropRolls,
+    monster: u32,
+    rarity: EntityRarity,
+    rate: f32,
+) -> Vec<Loot> {
+    let drops = tables.drops_for(monster);
+    let mut loot = Vec::new();
+    for _ in 0..rolls.for_rarity(rarity) {
+        for drop in drops {
+            if rng.gen::<f32>() >= drop.ratio * rate {
+                continue;
+            }
+
+            let item = match drop.source {
+                DropSource::Item(item) => Some(item),
+                DropSource::Group(group) => select_from_group(rng, tables.group(group)),
+            };
+            if let Some(ref_id) = item {
+                let amount = rng.gen_range(drop.min_amount..=drop.max_amount.max(drop.min_amount));
+                loot.push(Loot {
+                    ref_id,
+                    amount: amount.max(1),
+                });
+            }
+        }
+    }
+    loot
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use silkroad_data::drop::RefMonsterDrop;
+
+    const MONSTER: u32 = 1;
+
+    fn tables() -> DropTables {
+        let mut tables = DropTables::default();
+        tables.add_group_item(RefDropGroupItem {
+            service: true,
+            group_id: 10,
+            item_id: 100,
+            select_ratio: 1.0,
+        });
+        tables.add_monster_drop(
+            MONSTER,
+            RefMonsterDrop {
+                source: DropSource::Item(5),
+                min_amount: 2,
+                max_amount: 2,
+                ratio: 1.0,
+            },
+        );
+        tables.add_monster_drop(
+            MONSTER,
+            RefMonsterDrop {
+                source: DropSource::Group(10),
+                min_amount: 1,
+                max_amount: 1,
+                ratio: 1.0,
+            },
+        );
+        tables
+    }
+
+    #[test]
+    fn test_guaranteed_drops() {
+        let tables = tables();
+        let loot = roll_loot(
+            &mut rand::thread_rng(),
+            &tables,
+            &DropRolls::default(),
+            MONSTER,
+            EntityRarityType::Normal.into(),
+            1.0,
+        );
+        assert_eq!(
+            vec![Loot { ref_id: 5, amount: 2 }, Loot { ref_id: 100, amount: 1 }],
+            loot
+        );
+
+        let loot = roll_loot(
+            &mut rand::thread_rng(),
+            &tables,
+            &DropRolls::default(),
+            MONSTER,
+            EntityRarityType::Champion.into(),
+            1.0,
+        );
+        assert_eq!(4, loot.len());
+    }
+
+    #[test]
+    fn test_configured_rolls() {
+        let tables = tables();
+        let rolls = DropRolls {
+            champion: 3,
+            ..DropRolls::default()
+        };
+        let loot = roll_loot(
+            &mut rand::thread_rng(),
+            &tables,
+            &rolls,
+            MONSTER,
+            EntityRarityType::Champion.into(),
+            1.0,
+        );
+        assert_eq!(6, loot.len());
+    }
+
+    #[test]
+    fn test_no_drops() {
+        let tables = tables();
+        let mut rng = rand::thread_rng();
+        let rolls = DropRolls::default();
+        assert!(roll_loot(&mut rng, &tables, &rolls, MONSTER, EntityRarityType::Normal.into(), 0.0).is_empty());
+        assert!(roll_loot(&mut rng, &tables, &rolls, 2, EntityRarityType::Normal.into(), 1.0).is_empty());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::Item;
 use silkroad_data::itemdata::RefItemData;
-use silkroad_data::skilldata::RefSkillData;
+use silkroad_data::skilldata::{RefSkillData, SkillParam};
 use silkroad_data::DataMap;
 use silkroad_definitions::type_id::{ObjectEquippable, ObjectItem, ObjectType, ObjectWeaponType};
 use thiserror::Error;
@@ -56,6 +56,61 @@ impl AttackSkill {
     }
 }
 
+fn equippable_type(item: &RefItemData) -> Option<ObjectEquippable> {
+    match ObjectType::from_type_id(&item.common.type_id)? {
+        ObjectType::Item(ObjectItem::Equippable(equippable)) => Some(equippable),
+        _ => None,
+    }
+}
+
+impl AttackSkill {
+    /// Checks if the skill can be used with the given equipment. This considers the kinds of weapons the skill is
+    /// restricted to as well as other items, like shields, which need to be equipped for the skill.
+    pub fn check_requirements(
+        skill: &RefSkillData,
+        weapon: Option<&RefItemData>,
+        secondary: Option<&RefItemData>,
+    ) -> Result<(), AttackSkillError> {
+        let weapon_type = weapon
+            .and_then(equippable_type)
+            .and_then(|equippable| match equippable {
+                ObjectEquippable::Weapon(weapon_type) => Some(weapon_type),
+                _ => None,
+            });
+        let mut required_weapons = skill
+            .weapon_requirements
+            .iter()
+            .flatten()
+            .filter(|required| **required != ObjectWeaponType::None)
+            .peekable();
+        if required_weapons.peek().is_some() && !required_weapons.any(|required| Some(*required) == weapon_type) {
+            return Err(AttackSkillError::WeaponMismatch);
+        }
+
+        let equipped: Vec<(u8, u8)> = [weapon, secondary]
+            .into_iter()
+            .flatten()
+            .filter_map(equippable_type)
+            .map(ObjectEquippable::type_value)
+            .collect();
+        // Like the weapon requirements, multiple required items are alternatives, e.g. for skills that can be used
+        // with either kind of shield.
+        let mut required_items = skill
+            .params
+            .iter()
+            .filter_map(|param| match param {
+                SkillParam::RequiredItem(required) => Some(required.type_value()),
+                _ => None,
+            })
+            .peekable();
+        if required_items.peek().is_some() && !required_items.any(|required| equipped.contains(&required)) {
+            return Err(AttackSkillError::WeaponMismatch);
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum AttackSkillError {
     #[error("The item being held is not a weapon")]
@@ -64,4 +119,86 @@ pub enum AttackSkillError {
     SkillNotFound,
     #[error("The type of weapon was not known")]
     UnknownWeapon,
+    #[error("The equipped items do not fulfill the requirements of the skill")]
+    WeaponMismatch,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use silkroad_data::common::{RefCommon, RefOrigin};
+    use silkroad_data::itemdata::{RefBiologicalType, RefItemStats};
+    use silkroad_definitions::type_id::ObjectRace;
+
+    fn item(equippable: ObjectEquippable) -> RefItemData {
+        RefItemData {
+            common: RefCommon {
+                ref_id: 1,
+                id: "TestItem".to_string(),
+                type_id: ObjectType::Item(ObjectItem::Equippable(equippable)).type_id(),
+                country: RefOrigin::Chinese,
+                despawn_time: Default::default(),
+            },
+            price: 0,
+            sell_price: 0,
+            max_stack_size: 1,
+            range: None,
+            required_level: None,
+            biological_type: RefBiologicalType::Both,
+            item_class: 1,
+            params: [0, 0, 0, 0],
+            stats: RefItemStats::default(),
+        }
+    }
+
+    fn skill(weapon_requirements: [Option<ObjectWeaponType>; 2], params: Vec<SkillParam>) -> RefSkillData {
+        let mut skill = RefSkillData::test_skill(1);
+        skill.weapon_requirements = weapon_requirements;
+        skill.params = params;
+        skill
+    }
+
+    #[test]
+    fn test_weapon_requirements() {
+        let skill = skill(
+            [Some(ObjectWeaponType::Sword), Some(ObjectWeaponType::Blade)],
+            Vec::new(),
+        );
+        let sword = item(ObjectEquippable::Weapon(ObjectWeaponType::Sword));
+        let blade = item(ObjectEquippable::Weapon(ObjectWeaponType::Blade));
+        let spear = item(ObjectEquippable::Weapon(ObjectWeaponType::Spear));
+
+        assert!(AttackSkill::check_requirements(&skill, Some(&sword), None).is_ok());
+        assert!(AttackSkill::check_requirements(&skill, Some(&blade), None).is_ok());
+        assert!(AttackSkill::check_requirements(&skill, Some(&spear), None).is_err());
+        assert!(AttackSkill::check_requirements(&skill, None, None).is_err());
+    }
+
+    #[test]
+    fn test_no_weapon_requirements() {
+        let skill = skill([Some(ObjectWeaponType::None), None], Vec::new());
+        let spear = item(ObjectEquippable::Weapon(ObjectWeaponType::Spear));
+
+        assert!(AttackSkill::check_requirements(&skill, Some(&spear), None).is_ok());
+        assert!(AttackSkill::check_requirements(&skill, None, None).is_ok());
+    }
+
+    #[test]
+    fn test_required_items() {
+        let skill = skill(
+            [None, None],
+            vec![
+                SkillParam::RequiredItem(ObjectEquippable::Shield(ObjectRace::Chinese)),
+                SkillParam::RequiredItem(ObjectEquippable::Shield(ObjectRace::European)),
+            ],
+        );
+        let sword = item(ObjectEquippable::Weapon(ObjectWeaponType::Sword));
+        let chinese_shield = item(ObjectEquippable::Shield(ObjectRace::Chinese));
+        let european_shield = item(ObjectEquippable::Shield(ObjectRace::European));
+
+        assert!(AttackSkill::check_requirements(&skill, Some(&sword), Some(&chinese_shield)).is_ok());
+        assert!(AttackSkill::check_requirements(&skill, Some(&sword), Some(&european_shield)).is_ok());
+        assert!(AttackSkill::check_requirements(&skill, Some(&sword), None).is_err());
+        assert!(AttackSkill::check_requirements(&skill, None, None).is_err());
+    }
 }
@@ -1,4 +1,6 @@
 const SCALING: f32 = 1.02;
+/// The strength and intelligence every character starts with and which cannot be allocated away.
+const BASE_STAT: u16 = 20;
 
 pub enum StatType {
     STR,
@@ -44,11 +46,25 @@ impl Stats {
     pub fn increase_intelligence(&mut self, amount: u16) {
         self.int += amount
     }
+
+    /// The amount of stat points that have been allocated into strength and intelligence.
+    pub fn allocated(&self) -> u16 {
+        self.str.saturating_sub(BASE_STAT) + self.int.saturating_sub(BASE_STAT)
+    }
+
+    /// Resets strength and intelligence to their base values, returning the amount of stat points that had been
+    /// allocated into them.
+    pub fn reset(&mut self) -> u16 {
+        let allocated = self.allocated();
+        self.str = self.str.min(BASE_STAT);
+        self.int = self.int.min(BASE_STAT);
+        allocated
+    }
 }
 
 impl Default for Stats {
     fn default() -> Self {
-        Self::new(20, 20)
+        Self::new(BASE_STAT, BASE_STAT)
     }
 }
 
@@ -62,4 +78,13 @@ mod test {
         assert_eq!(200, default.max_health(1));
         assert_eq!(200, default.max_mana(1));
     }
+
+    #[test]
+    fn test_reset() {
+        let mut stats = Stats::new(25, 23);
+        assert_eq!(8, stats.reset());
+        assert_eq!(20, stats.strength());
+        assert_eq!(20, stats.intelligence());
+        assert_eq!(0, stats.reset());
+    }
 }
@@ -0,0 +1,257 @@
+use crate::AttackPower;
+use rand::Rng;
+use silkroad_data::skilldata::SkillParam;
+use std::time::Duration;
+
+/// How much the chance to apply a status effect drops for every level the target is above the effect.
+const LEVEL_RESISTANCE: u8 = 2;
+/// How long an abnormal state lasts if the skill causing it does not specify a duration.
+const DEFAULT_DURATION: Duration = Duration::from_secs(5);
+/// How long an entity stays on the ground after being knocked down.
+pub const KNOCKDOWN_DURATION: Duration = Duration::from_secs(3);
+/// Time between two ticks of an abnormal state that deals damage over time.
+pub const DAMAGE_TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Lasting states which negatively affect an entity. The values are the flags used to represent them towards the
+/// client.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AbnormalState {
+    Frozen = 0x01,
+    Frostbitten = 0x02,
+    Burnt = 0x08,
+    Poisoned = 0x10,
+    Sleeping = 0x40,
+    Dull = 0x100,
+    Bleeding = 0x800,
+    Stunned = 0x4000,
+}
+
+impl AbnormalState {
+    pub fn flag(self) -> u32 {
+        self as u32
+    }
+
+    /// Whether the entity can neither move nor act while in this state.
+    pub fn is_incapacitating(self) -> bool {
+        matches!(
+            self,
+            AbnormalState::Frozen | AbnormalState::Sleeping | AbnormalState::Stunned
+        )
+    }
+
+    /// Whether the client expects the level of the state alongside its flag.
+    pub fn has_level(self) -> bool {
+        self.flag() > 0x80
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum StatusEffect {
+    Abnormal {
+        state: AbnormalState,
+        level: u8,
+        duration: Duration,
+        /// The damage dealt every tick, if any.
+        damage: Option<AttackPower>,
+    },
+    Knockdown,
+    Knockback {
+        distance: u16,
+    },
+}
+
+impl StatusEffect {
+    fn abnormal(state: AbnormalState, level: u8, duration: u32) -> Self {
+        StatusEffect::Abnormal {
+            state,
+            level,
+            duration: as_duration(duration),
+            damage: None,
+        }
+    }
+
+    fn damaging(state: AbnormalState, level: u8, duration: u32, damage: AttackPower) -> Self {
+        StatusEffect::Abnormal {
+            state,
+            level,
+            duration: as_duration(duration),
+            damage: Some(damage),
+        }
+    }
+
+    pub fn level(&self) -> Option<u8> {
+        match self {
+            StatusEffect::Abnormal { level, .. } => Some(*level),
+            _ => None,
+        }
+    }
+}
+
+fn as_duration(millis: u32) -> Duration {
+    if millis == 0 {
+        DEFAULT_DURATION
+    } else {
+        Duration::from_millis(millis.into())
+    }
+}
+
+/// A status effect a skill may cause, together with the chance in percent that it does.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PossibleStatusEffect {
+    pub effect: StatusEffect,
+    pub chance: u8,
+}
+
+impl PossibleStatusEffect {
+    fn new(effect: StatusEffect, chance: u8) -> Self {
+        PossibleStatusEffect { effect, chance }
+    }
+}
+
+/// Collects the status effects the given skill parameters may cause. Effects that don't define their own level use
+/// the level of the caster instead.
+pub fn status_effects(params: &[SkillParam], caster_level: u8) -> Vec<PossibleStatusEffect> {
+    params
+        .iter()
+        .filter_map(|param| {
+            let (effect, chance) = match *param {
+                SkillParam::Stun {
+                    duration,
+                    chance,
+                    level,
+                } => (StatusEffect::abnormal(AbnormalState::Stunned, level, duration), chance),
+                SkillParam::Sleep {
+                    duration,
+                    chance,
+                    level,
+                } => (StatusEffect::abnormal(AbnormalState::Sleeping, level, duration), chance),
+                SkillParam::DullDebuff {
+                    duration,
+                    chance,
+                    level,
+                } => (StatusEffect::abnormal(AbnormalState::Dull, level, duration), chance),
+                SkillParam::Freeze { chance, .. } => {
+                    (StatusEffect::abnormal(AbnormalState::Frozen, caster_level, 0), chance)
+                },
+                SkillParam::Frostbite { chance, .. } => (
+                    StatusEffect::abnormal(AbnormalState::Frostbitten, caster_level, 0),
+                    chance,
+                ),
+                SkillParam::Burn { min, max, chance } => (
+                    StatusEffect::damaging(AbnormalState::Burnt, caster_level, 0, AttackPower::new(min, max)),
+                    chance,
+                ),
+                SkillParam::Poison { chance, damage, .. } => (
+                    StatusEffect::damaging(
+                        AbnormalState::Poisoned,
+                        caster_level,
+                        0,
+                        AttackPower::new(damage, damage),
+                    ),
+                    chance,
+                ),
+                SkillParam::BleedDebuff {
+                    duration,
+                    chance,
+                    level,
+                    damage,
+                    ..
+                } => (
+                    StatusEffect::damaging(
+                        AbnormalState::Bleeding,
+                        level,
+                        duration,
+                        AttackPower::new(damage, damage),
+                    ),
+                    chance,
+                ),
+                SkillParam::Knockdown { chance, .. } => (StatusEffect::Knockdown, chance),
+                SkillParam::Knockback { chance, distance } => (StatusEffect::Knockback { distance }, chance),
+                _ => return None,
+            };
+            Some(PossibleStatusEffect::new(effect, chance))
+        })
+        .collect()
+}
+
+/// Provides the chance in percent for an effect to be applied, which gets lower the higher the level of the target
+/// is compared to the level of the effect.
+pub fn effect_chance(chance: u8, effect_level: u8, target_level: u8) -> u8 {
+    let resistance = target_level
+        .saturating_sub(effect_level)
+        .saturating_mul(LEVEL_RESISTANCE);
+    chance.min(100).saturating_sub(resistance)
+}
+
+/// Rolls whether the effect gets applied to a target of the given level.
+pub fn roll_effect<R: Rng>(rng: &mut R, effect: &PossibleStatusEffect, caster_level: u8, target_level: u8) -> bool {
+    let effect_level = effect.effect.level().unwrap_or(caster_level);
+    rng.gen_range(0..100) < effect_chance(effect.chance, effect_level, target_level)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_effects_from_params() {
+        let effects = status_effects(
+            &[
+                SkillParam::Duration(10_000),
+                SkillParam::Stun {
+                    duration: 2000,
+                    chance: 50,
+                    level: 20,
+                },
+                SkillParam::Burn {
+                    max: 30,
+                    chance: 25,
+                    min: 10,
+                },
+                SkillParam::Knockdown { unknown: 0, chance: 10 },
+            ],
+            15,
+        );
+        assert_eq!(
+            vec![
+                PossibleStatusEffect::new(
+                    StatusEffect::Abnormal {
+                        state: AbnormalState::Stunned,
+                        level: 20,
+                        duration: Duration::from_secs(2),
+                        damage: None,
+                    },
+                    50
+                ),
+                PossibleStatusEffect::new(
+                    StatusEffect::Abnormal {
+                        state: AbnormalState::Burnt,
+                        level: 15,
+                        duration: DEFAULT_DURATION,
+                        damage: Some(AttackPower::new(10, 30)),
+                    },
+                    25
+                ),
+                PossibleStatusEffect::new(StatusEffect::Knockdown, 10),
+            ],
+            effects
+        );
+    }
+
+    #[test]
+    fn test_effect_chance() {
+        assert_eq!(50, effect_chance(50, 20, 10));
+        assert_eq!(50, effect_chance(50, 20, 20));
+        assert_eq!(30, effect_chance(50, 20, 30));
+        assert_eq!(0, effect_chance(50, 1, 100));
+        assert_eq!(100, effect_chance(200, 1, 1));
+    }
+
+    #[test]
+    fn test_roll_effect() {
+        let mut rng = rand::thread_rng();
+        let certain = PossibleStatusEffect::new(StatusEffect::Knockdown, 100);
+        assert!(roll_effect(&mut rng, &certain, 10, 10));
+        assert!(!roll_effect(&mut rng, &certain, 1, 100));
+    }
+}
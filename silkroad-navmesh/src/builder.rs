@@ -25,7 +25,7 @@ impl NavmeshBuilder {
                     Err(_) => return None,
                 };
 
-                let container = NavmeshContainer::new(region, new_mesh);
+                let container = NavmeshContainer::new(region, new_mesh, &objects);
                 let new_mesh = Arc::new(container);
                 Some((region, new_mesh))
             })
@@ -0,0 +1,215 @@
+use crate::pathfinding::{region_offset, CellGraph, NavmeshLocation, REGION_SIZE};
+use cgmath::{InnerSpace, Vector2};
+use silkroad_definitions::Region;
+
+/// The distance between two points we check along a ray. This needs to be small enough to not skip
+/// over thin obstacles, like walls.
+const RAY_STEP: f32 = 2.5;
+
+/// The footprint of a placed object on the ground, which cannot be walked or shot through.
+///
+/// The footprint is a rectangle given in the local space of the object, which is then rotated by
+/// `yaw` around the position of the object.
+pub(crate) struct Obstacle {
+    pub(crate) position: Vector2<f32>,
+    pub(crate) yaw: f32,
+    pub(crate) min: Vector2<f32>,
+    pub(crate) max: Vector2<f32>,
+}
+
+impl Obstacle {
+    pub(crate) fn contains(&self, point: Vector2<f32>) -> bool {
+        let relative = point - self.position;
+        let (sin, cos) = (-self.yaw).sin_cos();
+        let local = Vector2::new(relative.x * cos - relative.y * sin, relative.x * sin + relative.y * cos);
+        local.x >= self.min.x && local.x <= self.max.x && local.y >= self.min.y && local.y <= self.max.y
+    }
+}
+
+/// What should be considered an obstacle when casting a ray.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub(crate) enum RayKind {
+    /// Walking along the ray; both objects and the terrain can block.
+    Movement,
+    /// Looking or shooting along the ray; only objects can block.
+    Sight,
+}
+
+fn region_at(start_region: Region, global: Vector2<f32>) -> Region {
+    if start_region.is_dungeon() {
+        // Dungeons are not part of the world grid, so we can't leave the region.
+        return start_region;
+    }
+    Region::from_xy((global.x / REGION_SIZE) as u8, (global.y / REGION_SIZE) as u8)
+}
+
+/// Walks along the line from `from` to `to` and returns the last free location before the first obstacle
+/// that was hit. If nothing was hit, [None] is returned.
+///
+/// Regions without a navmesh are considered to be free. If the ray starts at a location that is not walkable
+/// itself, terrain is only considered once the ray entered a walkable area, such that entities standing at
+/// the border of the navmesh are not stuck.
+pub(crate) fn raycast<'a, F>(
+    graph_for: F,
+    from: NavmeshLocation,
+    to: NavmeshLocation,
+    kind: RayKind,
+) -> Option<NavmeshLocation>
+where
+    F: Fn(Region) -> Option<&'a CellGraph>,
+{
+    let start = region_offset(from.0) + from.1;
+    let end = region_offset(to.0) + to.1;
+    let distance = (end - start).magnitude();
+    let steps = (distance / RAY_STEP).ceil() as usize;
+
+    let mut last_free = from;
+    let mut previous_cell = graph_for(from.0)
+        .and_then(|graph| graph.cell_at(from.1))
+        .map(|cell| (from.0, cell));
+
+    for step in 1..=steps {
+        let progress = (step as f32 / steps as f32).min(1.0);
+        let point = start + (end - start) * progress;
+        let region = region_at(from.0, point);
+        let location = NavmeshLocation(region, point - region_offset(region));
+
+        let Some(graph) = graph_for(region) else {
+            previous_cell = None;
+            last_free = location;
+            continue;
+        };
+
+        let cell = graph.cell_at(location.1);
+        if graph.is_obstructed(cell, location.1) {
+            return Some(last_free);
+        }
+
+        if kind == RayKind::Movement {
+            match (previous_cell, cell) {
+                (Some(_), None) => return Some(last_free),
+                (Some((previous_region, previous)), Some(current))
+                    if (previous_region, previous) != (region, current) =>
+                {
+                    let connected = graph_for(previous_region)
+                        .map(|previous_graph| previous_graph.is_linked(previous, region, current))
+                        .unwrap_or(true);
+                    if !connected {
+                        return Some(last_free);
+                    }
+                },
+                _ => {},
+            }
+            if let Some(cell) = cell {
+                previous_cell = Some((region, cell));
+            }
+        }
+
+        last_free = location;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pathfinding::{CellLink, NavCell};
+
+    const REGION: Region = Region::new(0x6060);
+
+    fn location(x: f32, z: f32) -> NavmeshLocation {
+        NavmeshLocation(REGION, Vector2::new(x, z))
+    }
+
+    /// Two cells next to each other, where only the left one can be walked into from the right one.
+    /// The left cell also contains a wall in its middle, going from the bottom to a third of the height.
+    fn graph() -> CellGraph {
+        let mut left = NavCell::new(Vector2::new(0.0, 0.0), Vector2::new(100.0, 300.0));
+        let mut right = NavCell::new(Vector2::new(100.0, 0.0), Vector2::new(200.0, 300.0));
+        left.obstacles.push(0);
+        right.links.push(CellLink {
+            target_region: REGION,
+            target_cell: 0,
+            start: Vector2::new(100.0, 0.0),
+            end: Vector2::new(100.0, 300.0),
+        });
+        let wall = Obstacle {
+            position: Vector2::new(50.0, 0.0),
+            yaw: 0.0,
+            min: Vector2::new(-1.0, 0.0),
+            max: Vector2::new(1.0, 100.0),
+        };
+        CellGraph::new(vec![left, right], Vec::new(), 0, 1.0).with_obstacles(vec![wall])
+    }
+
+    #[test]
+    pub fn test_free_ray() {
+        let graph = graph();
+        assert_eq!(
+            None,
+            raycast(
+                |_| Some(&graph),
+                location(10.0, 200.0),
+                location(90.0, 200.0),
+                RayKind::Movement
+            )
+        );
+        assert_eq!(
+            None,
+            raycast(
+                |_| Some(&graph),
+                location(150.0, 50.0),
+                location(10.0, 200.0),
+                RayKind::Movement
+            )
+        );
+    }
+
+    #[test]
+    pub fn test_blocked_by_object() {
+        let graph = graph();
+        let hit = raycast(
+            |_| Some(&graph),
+            location(10.0, 50.0),
+            location(90.0, 50.0),
+            RayKind::Sight,
+        )
+        .unwrap();
+        assert!(hit.1.x < 49.0 && hit.1.x > 45.0);
+    }
+
+    #[test]
+    pub fn test_blocked_by_terrain() {
+        let graph = graph();
+        let hit = raycast(
+            |_| Some(&graph),
+            location(10.0, 200.0),
+            location(150.0, 200.0),
+            RayKind::Movement,
+        )
+        .unwrap();
+        assert!(hit.1.x <= 100.0 && hit.1.x > 95.0);
+        assert_eq!(
+            None,
+            raycast(
+                |_| Some(&graph),
+                location(10.0, 200.0),
+                location(150.0, 200.0),
+                RayKind::Sight
+            )
+        );
+    }
+
+    #[test]
+    pub fn test_rotated_obstacle() {
+        let obstacle = Obstacle {
+            position: Vector2::new(0.0, 0.0),
+            yaw: std::f32::consts::FRAC_PI_2,
+            min: Vector2::new(0.0, -1.0),
+            max: Vector2::new(10.0, 1.0),
+        };
+        assert!(!obstacle.contains(Vector2::new(5.0, 0.0)));
+        assert!(obstacle.contains(Vector2::new(0.0, 5.0)));
+    }
+}
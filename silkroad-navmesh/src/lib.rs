@@ -1,5 +1,7 @@
+use crate::collision::RayKind;
 use crate::navmesh::NavmeshContainer;
 use crate::object::Object;
+use crate::pathfinding::{NavmeshLocation, PathfindingError};
 use silkroad_definitions::Region;
 use std::collections::HashMap;
 use std::path::Path;
@@ -7,11 +9,13 @@ use std::sync::Arc;
 use std::{fs, io};
 
 pub mod builder;
+mod collision;
 pub mod heightmap;
 pub mod map_info_ext;
 pub mod navmesh;
 pub mod object;
 pub mod object_info;
+pub mod pathfinding;
 pub mod region;
 
 pub trait FileLoader {
@@ -39,7 +43,6 @@ const MAP_INFO_FILE: &str = "navmesh/mapinfo.mfo";
 
 pub struct GlobalNavmesh {
     loaded_meshes: HashMap<Region, Arc<NavmeshContainer>>,
-    #[allow(unused)] // We will eventually use objects for navigation
     loaded_objects: HashMap<u32, Arc<Object>>,
 }
 
@@ -51,4 +54,55 @@ impl GlobalNavmesh {
     pub fn mesh_ref_for(&self, region: Region) -> Option<&NavmeshContainer> {
         self.loaded_meshes.get(&region).map(|arc| arc.as_ref())
     }
+
+    pub fn object(&self, id: u32) -> Option<Arc<Object>> {
+        self.loaded_objects.get(&id).cloned()
+    }
+
+    /// Checks if the given location cannot be stood on, either because the terrain isn't walkable
+    /// or because there's an object in the way. Locations in regions without a navmesh are never blocked.
+    pub fn is_blocked(&self, location: NavmeshLocation) -> bool {
+        let Some(mesh) = self.mesh_ref_for(location.0) else {
+            return false;
+        };
+        match mesh.graph().cell_at(location.1) {
+            Some(cell) => mesh.graph().is_obstructed(Some(cell), location.1),
+            None => true,
+        }
+    }
+
+    /// Walks in a straight line from `from` to `to` and checks if anything is in the way. If something
+    /// is in the way, the last location that can be reached before hitting the obstacle is returned.
+    pub fn raycast(&self, from: NavmeshLocation, to: NavmeshLocation) -> Option<NavmeshLocation> {
+        collision::raycast(
+            |region| self.mesh_ref_for(region).map(|mesh| mesh.graph()),
+            from,
+            to,
+            RayKind::Movement,
+        )
+    }
+
+    /// Checks if there's no object blocking the view from `from` to `to`. Unlike [GlobalNavmesh::raycast],
+    /// this does not consider the terrain, as one can still look, or shoot, over areas that cannot be walked.
+    pub fn has_line_of_sight(&self, from: NavmeshLocation, to: NavmeshLocation) -> bool {
+        collision::raycast(
+            |region| self.mesh_ref_for(region).map(|mesh| mesh.graph()),
+            from,
+            to,
+            RayKind::Sight,
+        )
+        .is_none()
+    }
+
+    /// Finds a walkable path from `start` to `goal`, which may span multiple regions.
+    ///
+    /// The path consists of the waypoints to walk to in order, excluding the starting point, and always
+    /// ends at the `goal`. Heights are not considered and need to be looked up separately.
+    pub fn find_path(
+        &self,
+        start: NavmeshLocation,
+        goal: NavmeshLocation,
+    ) -> Result<Vec<NavmeshLocation>, PathfindingError> {
+        pathfinding::find_path(|region| self.mesh_ref_for(region).map(|mesh| mesh.graph()), start, goal)
+    }
 }
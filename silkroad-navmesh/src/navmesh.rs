@@ -1,14 +1,28 @@
+use crate::collision::Obstacle;
 use crate::heightmap::Heightmap;
+use crate::object::Object;
+use crate::pathfinding::{CellGraph, CellLink, NavCell, NavTile};
 use crate::Region;
+use cgmath::Vector2;
 use sr_formats::jmxvnvm::JmxNvm;
+use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
 
 const MESH_SIZE: usize = 96;
 const MESH_TILE_SIZE: usize = 20;
 
+const NO_CELL: u16 = 0xFFFF;
+const EDGE_BLOCK_DST_TO_SRC: u8 = 0x1;
+const EDGE_BLOCK_SRC_TO_DST: u8 = 0x2;
+const TILE_BLOCKED: u16 = 0x1;
+/// The height above the ground, below which an object is considered to be in the way of walking.
+const WALKABLE_CLEARANCE: f32 = 20.0;
+
 pub struct NavmeshContainer {
     region: Region,
     mesh: JmxNvm,
+    graph: CellGraph,
 }
 
 impl Debug for NavmeshContainer {
@@ -18,8 +32,131 @@ impl Debug for NavmeshContainer {
 }
 
 impl NavmeshContainer {
-    pub fn new(region: Region, jmx: JmxNvm) -> Self {
-        Self { region, mesh: jmx }
+    pub fn new(region: Region, jmx: JmxNvm, objects: &HashMap<u32, Arc<Object>>) -> Self {
+        let heightmap = Heightmap::new(&jmx.height_map, MESH_SIZE, MESH_TILE_SIZE);
+        let mut graph_cells = Self::build_cells(region, &jmx);
+        let obstacles = Self::build_obstacles(&jmx, &heightmap, objects, &mut graph_cells);
+        let tiles = jmx
+            .tile_map
+            .iter()
+            .map(|tile| NavTile {
+                cell: tile.cell_index,
+                blocked: tile.flag & TILE_BLOCKED != 0,
+            })
+            .collect();
+        let graph = CellGraph::new(graph_cells, tiles, MESH_SIZE, MESH_TILE_SIZE as f32).with_obstacles(obstacles);
+        Self {
+            region,
+            mesh: jmx,
+            graph,
+        }
+    }
+
+    pub(crate) fn graph(&self) -> &CellGraph {
+        &self.graph
+    }
+
+    fn build_cells(region: Region, mesh: &JmxNvm) -> Vec<NavCell> {
+        let mut cells = mesh
+            .cells
+            .iter()
+            .map(|cell| {
+                NavCell::new(
+                    Vector2::new(cell.min.x, cell.min.y),
+                    Vector2::new(cell.max.x, cell.max.y),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut add_link = |source: u16, target_region: Region, target_cell: u16, start, end| {
+            if source == NO_CELL || target_cell == NO_CELL {
+                return;
+            }
+            if let Some(cell) = cells.get_mut(source as usize) {
+                cell.links.push(CellLink {
+                    target_region,
+                    target_cell,
+                    start,
+                    end,
+                });
+            }
+        };
+
+        for edge in mesh.region_edges.iter() {
+            let start = Vector2::new(edge.min.x, edge.min.y);
+            let end = Vector2::new(edge.max.x, edge.max.y);
+            let [source, destination] = edge.assoc_cell;
+            if edge.flag & EDGE_BLOCK_SRC_TO_DST == 0 {
+                add_link(source, region, destination, start, end);
+            }
+            if edge.flag & EDGE_BLOCK_DST_TO_SRC == 0 {
+                add_link(destination, region, source, start, end);
+            }
+        }
+
+        // Global edges are contained in the navmesh of both regions, so we only need to add the direction
+        // going out of this region.
+        for edge in mesh.global_edges.iter() {
+            let start = Vector2::new(edge.min.x, edge.min.y);
+            let end = Vector2::new(edge.max.x, edge.max.y);
+            let [source, destination] = edge.assoc_cell;
+            let [source_region, destination_region] = edge.assoc_region.map(Region::from);
+            if source_region == region && edge.flag & EDGE_BLOCK_SRC_TO_DST == 0 {
+                add_link(source, destination_region, destination, start, end);
+            }
+            if destination_region == region && edge.flag & EDGE_BLOCK_DST_TO_SRC == 0 {
+                add_link(destination, source_region, source, start, end);
+            }
+        }
+
+        cells
+    }
+
+    /// Creates the obstacles for all objects placed in this region and registers them with the cells
+    /// they're overlapping. Objects that are floating high enough above the ground to walk underneath
+    /// are not considered to be obstacles. Neither are structures, like gates, arches or bridges, which
+    /// come with their own walkable navmesh; blocking their whole bounds would also block the parts
+    /// that are meant to be walked on or through.
+    fn build_obstacles(
+        mesh: &JmxNvm,
+        heightmap: &Heightmap,
+        objects: &HashMap<u32, Arc<Object>>,
+        cells: &mut [NavCell],
+    ) -> Vec<Obstacle> {
+        let mut obstacles = Vec::new();
+        let mut obstacle_of_entry = Vec::with_capacity(mesh.entries.len());
+        for entry in mesh.entries.iter() {
+            let obstacle = Some(entry)
+                .filter(|entry| !entry.is_struct)
+                .and_then(|entry| objects.get(&entry.id))
+                .and_then(|object| object.collision_bounds())
+                .filter(|(min, _)| {
+                    let ground = heightmap
+                        .height_at_position(entry.position.x, entry.position.z)
+                        .unwrap_or(entry.position.y);
+                    entry.position.y + min.y <= ground + WALKABLE_CLEARANCE
+                })
+                .map(|(min, max)| Obstacle {
+                    position: Vector2::new(entry.position.x, entry.position.z),
+                    yaw: entry.yaw,
+                    min: Vector2::new(min.x, min.z),
+                    max: Vector2::new(max.x, max.z),
+                });
+            obstacle_of_entry.push(obstacle.map(|obstacle| {
+                obstacles.push(obstacle);
+                obstacles.len() - 1
+            }));
+        }
+
+        for (cell, mesh_cell) in cells.iter_mut().zip(mesh.cells.iter()) {
+            cell.obstacles = mesh_cell
+                .entries
+                .iter()
+                .filter_map(|entry| obstacle_of_entry.get(*entry as usize).copied().flatten())
+                .collect();
+        }
+
+        obstacles
     }
 
     pub fn heightmap(&self) -> Heightmap {
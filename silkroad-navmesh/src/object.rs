@@ -1,5 +1,6 @@
 use crate::object_info::ObjectInfo;
 use crate::FileLoader;
+use cgmath::Vector3;
 use log::debug;
 use sr_formats::jmxvbms::JmxBMesh;
 use sr_formats::jmxvbsr::JmxRes;
@@ -93,6 +94,28 @@ impl Object {
         Ok(res)
     }
 
+    /// The box enclosing the collision geometry of this object, in the local space of the object.
+    /// Returns [None] if the object has nothing to collide with.
+    pub fn collision_bounds(&self) -> Option<(Vector3<f32>, Vector3<f32>)> {
+        let (min, max) = match &self {
+            Object::Compound { collision_resource, .. } => {
+                collision_resource.as_ref().map(|resource| resource.bounding_box0)?
+            },
+            Object::Resource(resource) => resource.bounding_box0,
+            Object::Mesh(mesh) => {
+                let mut vertices = mesh.vertices.iter().map(|vertex| vertex.position);
+                let first = vertices.next()?;
+                vertices.fold((first, first), |(min, max), vertex| {
+                    (
+                        mint::Vector3::from([min.x.min(vertex.x), min.y.min(vertex.y), min.z.min(vertex.z)]),
+                        mint::Vector3::from([max.x.max(vertex.x), max.y.max(vertex.y), max.z.max(vertex.z)]),
+                    )
+                })
+            },
+        };
+        Some((Vector3::new(min.x, min.y, min.z), Vector3::new(max.x, max.y, max.z)))
+    }
+
     pub fn name(&self) -> &str {
         match &self {
             Object::Compound { header, .. } => header.name.borrow(),
@@ -0,0 +1,412 @@
+use crate::collision::Obstacle;
+use cgmath::{InnerSpace, MetricSpace, Vector2};
+use silkroad_definitions::Region;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use thiserror::Error;
+
+pub(crate) const REGION_SIZE: f32 = 1920.0;
+/// The maximum amount of cells we look at before giving up. This avoids searching through the whole world in case
+/// the target is unreachable.
+const MAX_VISITED_CELLS: usize = 4096;
+
+/// A location inside a region, where the vector contains the `x` and `z` coordinates local to the region.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NavmeshLocation(pub Region, pub Vector2<f32>);
+
+impl NavmeshLocation {
+    fn to_global(self) -> Vector2<f32> {
+        region_offset(self.0) + self.1
+    }
+
+    fn from_global(region: Region, global: Vector2<f32>) -> Self {
+        NavmeshLocation(region, global - region_offset(region))
+    }
+}
+
+pub(crate) fn region_offset(region: Region) -> Vector2<f32> {
+    Vector2::new(region.x() as f32 * REGION_SIZE, region.y() as f32 * REGION_SIZE)
+}
+
+#[derive(Debug, Error)]
+pub enum PathfindingError {
+    #[error("There is no navmesh loaded for region {0}")]
+    MissingRegion(Region),
+    #[error("The location {0:?} is not walkable")]
+    NotWalkable(NavmeshLocation),
+    #[error("There is no walkable path to the destination")]
+    Unreachable,
+}
+
+/// A connection from one cell into another, which may also be located in a different region.
+/// The connection can be crossed anywhere along the line from `start` to `end`.
+pub(crate) struct CellLink {
+    pub(crate) target_region: Region,
+    pub(crate) target_cell: u16,
+    pub(crate) start: Vector2<f32>,
+    pub(crate) end: Vector2<f32>,
+}
+
+impl CellLink {
+    fn closest_point_to(&self, point: Vector2<f32>) -> Vector2<f32> {
+        let line = self.end - self.start;
+        let length = line.magnitude2();
+        if length == 0.0 {
+            return self.start;
+        }
+        let progress = ((point - self.start).dot(line) / length).clamp(0.0, 1.0);
+        self.start + line * progress
+    }
+}
+
+/// An axis aligned, walkable area of a region, from which we can move into other cells using its links.
+pub(crate) struct NavCell {
+    pub(crate) min: Vector2<f32>,
+    pub(crate) max: Vector2<f32>,
+    pub(crate) links: Vec<CellLink>,
+    /// Indices of the obstacles of the region that overlap this cell.
+    pub(crate) obstacles: Vec<usize>,
+}
+
+impl NavCell {
+    pub(crate) fn new(min: Vector2<f32>, max: Vector2<f32>) -> Self {
+        NavCell {
+            min,
+            max,
+            links: Vec::new(),
+            obstacles: Vec::new(),
+        }
+    }
+
+    fn contains(&self, point: Vector2<f32>) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x && point.y >= self.min.y && point.y <= self.max.y
+    }
+}
+
+#[derive(Copy, Clone)]
+pub(crate) struct NavTile {
+    pub(crate) cell: u32,
+    pub(crate) blocked: bool,
+}
+
+/// The cells of a single region and how they are connected, to be used for finding paths.
+pub(crate) struct CellGraph {
+    cells: Vec<NavCell>,
+    obstacles: Vec<Obstacle>,
+    tiles: Vec<NavTile>,
+    tiles_per_side: usize,
+    tile_size: f32,
+}
+
+impl CellGraph {
+    /// Creates a new graph from the given cells. `tiles` is expected to be either empty, or contain
+    /// `tiles_per_side * tiles_per_side` entries, with each tile covering `tile_size` units on each side.
+    pub(crate) fn new(cells: Vec<NavCell>, tiles: Vec<NavTile>, tiles_per_side: usize, tile_size: f32) -> Self {
+        CellGraph {
+            cells,
+            obstacles: Vec::new(),
+            tiles,
+            tiles_per_side,
+            tile_size,
+        }
+    }
+
+    pub(crate) fn with_obstacles(mut self, obstacles: Vec<Obstacle>) -> Self {
+        self.obstacles = obstacles;
+        self
+    }
+
+    fn tile_at(&self, point: Vector2<f32>) -> Option<&NavTile> {
+        if point.x < 0.0 || point.y < 0.0 {
+            return None;
+        }
+        let x = (point.x / self.tile_size) as usize;
+        let z = (point.y / self.tile_size) as usize;
+        if x >= self.tiles_per_side || z >= self.tiles_per_side {
+            return None;
+        }
+        self.tiles.get(z * self.tiles_per_side + x)
+    }
+
+    /// Finds the cell the given point is in. If the point is on a tile that is blocked, we consider it to not be
+    /// inside any cell.
+    pub(crate) fn cell_at(&self, point: Vector2<f32>) -> Option<u16> {
+        if let Some(tile) = self.tile_at(point) {
+            if tile.blocked {
+                return None;
+            }
+            let cell = tile.cell as usize;
+            if self.cells.get(cell).map(|cell| cell.contains(point)).unwrap_or(false) {
+                return Some(cell as u16);
+            }
+        }
+
+        self.cells
+            .iter()
+            .position(|cell| cell.contains(point))
+            .map(|index| index as u16)
+    }
+
+    /// Checks if the point is inside any of the obstacles placed in the given cell. If no cell is
+    /// given, all obstacles of the region are checked.
+    pub(crate) fn is_obstructed(&self, cell: Option<u16>, point: Vector2<f32>) -> bool {
+        match cell.and_then(|cell| self.cells.get(cell as usize)) {
+            Some(cell) => cell
+                .obstacles
+                .iter()
+                .filter_map(|index| self.obstacles.get(*index))
+                .any(|obstacle| obstacle.contains(point)),
+            None => self.obstacles.iter().any(|obstacle| obstacle.contains(point)),
+        }
+    }
+
+    pub(crate) fn is_linked(&self, cell: u16, target_region: Region, target_cell: u16) -> bool {
+        self.links_of(cell)
+            .iter()
+            .any(|link| link.target_region == target_region && link.target_cell == target_cell)
+    }
+
+    fn links_of(&self, cell: u16) -> &[CellLink] {
+        self.cells
+            .get(cell as usize)
+            .map(|cell| cell.links.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+type CellKey = (Region, u16);
+
+struct OpenCell {
+    estimate: f32,
+    key: CellKey,
+}
+
+impl PartialEq for OpenCell {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OpenCell {}
+
+impl PartialOrd for OpenCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenCell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed, such that the binary heap pops the cell with the lowest estimate first.
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+/// Finds a path from `start` to `goal` using A* over the cells of the regions provided by `graph_for`.
+///
+/// Each cell is entered at the point closest to where the previous cell was entered, which is also used as the
+/// waypoint for that cell. The resulting path does not contain the starting point, but always ends with the goal.
+pub(crate) fn find_path<'a, F>(
+    graph_for: F,
+    start: NavmeshLocation,
+    goal: NavmeshLocation,
+) -> Result<Vec<NavmeshLocation>, PathfindingError>
+where
+    F: Fn(Region) -> Option<&'a CellGraph>,
+{
+    let start_cell = graph_for(start.0)
+        .ok_or(PathfindingError::MissingRegion(start.0))?
+        .cell_at(start.1)
+        .ok_or(PathfindingError::NotWalkable(start))?;
+    let goal_cell = graph_for(goal.0)
+        .ok_or(PathfindingError::MissingRegion(goal.0))?
+        .cell_at(goal.1)
+        .ok_or(PathfindingError::NotWalkable(goal))?;
+
+    let start_key = (start.0, start_cell);
+    let goal_key = (goal.0, goal_cell);
+    if start_key == goal_key {
+        return Ok(vec![goal]);
+    }
+
+    let start_global = start.to_global();
+    let goal_global = goal.to_global();
+
+    let mut open = BinaryHeap::new();
+    let mut closed = HashSet::new();
+    let mut costs: HashMap<CellKey, f32> = HashMap::new();
+    let mut entry_points: HashMap<CellKey, Vector2<f32>> = HashMap::new();
+    let mut came_from: HashMap<CellKey, CellKey> = HashMap::new();
+
+    costs.insert(start_key, 0.0);
+    entry_points.insert(start_key, start_global);
+    open.push(OpenCell {
+        estimate: start_global.distance(goal_global),
+        key: start_key,
+    });
+
+    while let Some(current) = open.pop() {
+        if current.key == goal_key {
+            return Ok(reconstruct_path(&came_from, &entry_points, goal_key, goal));
+        }
+
+        if !closed.insert(current.key) {
+            continue;
+        }
+
+        if closed.len() > MAX_VISITED_CELLS {
+            break;
+        }
+
+        let Some(graph) = graph_for(current.key.0) else {
+            continue;
+        };
+
+        let cost = costs[&current.key];
+        let entry = entry_points[&current.key];
+        let offset = region_offset(current.key.0);
+
+        for link in graph.links_of(current.key.1) {
+            let next = (link.target_region, link.target_cell);
+            if closed.contains(&next) {
+                continue;
+            }
+
+            let crossing = offset + link.closest_point_to(entry - offset);
+            let next_cost = cost + entry.distance(crossing);
+            if costs.get(&next).map(|existing| next_cost < *existing).unwrap_or(true) {
+                costs.insert(next, next_cost);
+                entry_points.insert(next, crossing);
+                came_from.insert(next, current.key);
+                open.push(OpenCell {
+                    estimate: next_cost + crossing.distance(goal_global),
+                    key: next,
+                });
+            }
+        }
+    }
+
+    Err(PathfindingError::Unreachable)
+}
+
+fn reconstruct_path(
+    came_from: &HashMap<CellKey, CellKey>,
+    entry_points: &HashMap<CellKey, Vector2<f32>>,
+    goal_key: CellKey,
+    goal: NavmeshLocation,
+) -> Vec<NavmeshLocation> {
+    let mut path = vec![goal];
+    let mut current = goal_key;
+    while let Some(previous) = came_from.get(&current) {
+        path.push(NavmeshLocation::from_global(current.0, entry_points[&current]));
+        current = *previous;
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const REGION: Region = Region::new(0x6060);
+
+    fn link(target_cell: u16, start: (f32, f32), end: (f32, f32)) -> CellLink {
+        CellLink {
+            target_region: REGION,
+            target_cell,
+            start: Vector2::new(start.0, start.1),
+            end: Vector2::new(end.0, end.1),
+        }
+    }
+
+    /// Creates a graph of three cells in an L shape, where the first cell is in the bottom left,
+    /// the second one in the top left, and the third one in the top right. The bottom right
+    /// quadrant is not walkable.
+    fn l_shaped_graph() -> CellGraph {
+        let mut bottom_left = NavCell::new(Vector2::new(0.0, 0.0), Vector2::new(100.0, 100.0));
+        let mut top_left = NavCell::new(Vector2::new(0.0, 100.0), Vector2::new(100.0, 200.0));
+        let mut top_right = NavCell::new(Vector2::new(100.0, 100.0), Vector2::new(200.0, 200.0));
+        bottom_left.links.push(link(1, (0.0, 100.0), (100.0, 100.0)));
+        top_left.links.push(link(0, (0.0, 100.0), (100.0, 100.0)));
+        top_left.links.push(link(2, (100.0, 100.0), (100.0, 200.0)));
+        top_right.links.push(link(1, (100.0, 100.0), (100.0, 200.0)));
+        CellGraph::new(vec![bottom_left, top_left, top_right], Vec::new(), 0, 1.0)
+    }
+
+    #[test]
+    pub fn test_same_cell() {
+        let graph = l_shaped_graph();
+        let goal = NavmeshLocation(REGION, Vector2::new(80.0, 80.0));
+        let path = find_path(
+            |_| Some(&graph),
+            NavmeshLocation(REGION, Vector2::new(10.0, 10.0)),
+            goal,
+        )
+        .unwrap();
+        assert_eq!(vec![goal], path);
+    }
+
+    #[test]
+    pub fn test_path_around_corner() {
+        let graph = l_shaped_graph();
+        let goal = NavmeshLocation(REGION, Vector2::new(150.0, 150.0));
+        let path = find_path(
+            |_| Some(&graph),
+            NavmeshLocation(REGION, Vector2::new(50.0, 50.0)),
+            goal,
+        )
+        .unwrap();
+        assert_eq!(3, path.len());
+        assert_eq!(Vector2::new(50.0, 100.0), path[0].1);
+        assert_eq!(Vector2::new(100.0, 100.0), path[1].1);
+        assert_eq!(goal, path[2]);
+    }
+
+    #[test]
+    pub fn test_not_walkable() {
+        let graph = l_shaped_graph();
+        let result = find_path(
+            |_| Some(&graph),
+            NavmeshLocation(REGION, Vector2::new(50.0, 50.0)),
+            NavmeshLocation(REGION, Vector2::new(150.0, 50.0)),
+        );
+        assert!(matches!(result, Err(PathfindingError::NotWalkable(_))));
+    }
+
+    #[test]
+    pub fn test_unreachable() {
+        let mut graph = l_shaped_graph();
+        graph.cells[1].links.retain(|link| link.target_cell != 2);
+        let result = find_path(
+            |_| Some(&graph),
+            NavmeshLocation(REGION, Vector2::new(50.0, 50.0)),
+            NavmeshLocation(REGION, Vector2::new(150.0, 150.0)),
+        );
+        assert!(matches!(result, Err(PathfindingError::Unreachable)));
+    }
+
+    #[test]
+    pub fn test_across_regions() {
+        let other_region = Region::from_xy(REGION.x() + 1, REGION.y());
+        let mut left = NavCell::new(Vector2::new(0.0, 0.0), Vector2::new(REGION_SIZE, REGION_SIZE));
+        left.links.push(CellLink {
+            target_region: other_region,
+            target_cell: 0,
+            start: Vector2::new(REGION_SIZE, 0.0),
+            end: Vector2::new(REGION_SIZE, REGION_SIZE),
+        });
+        let right = NavCell::new(Vector2::new(0.0, 0.0), Vector2::new(REGION_SIZE, REGION_SIZE));
+        let left = CellGraph::new(vec![left], Vec::new(), 0, 1.0);
+        let right = CellGraph::new(vec![right], Vec::new(), 0, 1.0);
+
+        let goal = NavmeshLocation(other_region, Vector2::new(100.0, 50.0));
+        let path = find_path(
+            |region| if region == REGION { Some(&left) } else { Some(&right) },
+            NavmeshLocation(REGION, Vector2::new(1800.0, 50.0)),
+            goal,
+        )
+        .unwrap();
+        assert_eq!(vec![NavmeshLocation(other_region, Vector2::new(0.0, 50.0)), goal], path);
+    }
+}
@@ -0,0 +1,38 @@
+use silkroad_serde::*;
+
+#[derive(Deserialize, Clone)]
+pub enum AlchemyReinforceRequest {
+    /// Reinforces the item in the first slot with the elixir in the second slot, optionally using the lucky powder in
+    /// the third slot.
+    #[silkroad(value = 1)]
+    Fuse { kind: u8, slots: Vec<u8> },
+    #[silkroad(value = 2)]
+    Cancel { kind: u8 },
+}
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+#[silkroad(size = 2)]
+pub enum AlchemyError {
+    #[silkroad(value = 0x5401)]
+    InvalidItem,
+    #[silkroad(value = 0x5402)]
+    InvalidElixir,
+    #[silkroad(value = 0x5403)]
+    InvalidLuckyPowder,
+    #[silkroad(value = 0x5404)]
+    MaxLevelReached,
+}
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub enum AlchemyReinforceResponse {
+    #[silkroad(value = 1)]
+    Fused {
+        succeeded: bool,
+        slot: u8,
+        upgrade_level: u8,
+    },
+    #[silkroad(value = 2)]
+    Cancelled,
+    #[silkroad(value = 3)]
+    Error(AlchemyError),
+}
@@ -113,6 +113,8 @@ pub enum SkillPartDamage {
     Default(DamageValue),
     #[silkroad(value = 0x80)]
     KillingBlow(DamageValue),
+    #[silkroad(value = 0x02)]
+    Blocked,
     #[silkroad(value = 0x08)]
     Abort,
 }
@@ -188,3 +190,22 @@ impl PerformActionUpdate {
         }
     }
 }
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct BuffAdded {
+    pub target: u32,
+    pub skill: u32,
+    pub token: u32,
+}
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct BuffRemoved {
+    pub unknown: u8, // always 1
+    pub token: u32,
+}
+
+impl BuffRemoved {
+    pub fn new(token: u32) -> Self {
+        BuffRemoved { unknown: 1, token }
+    }
+}
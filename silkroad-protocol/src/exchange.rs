@@ -0,0 +1,101 @@
+use crate::inventory::InventoryItemData;
+use silkroad_serde::*;
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct ExchangeRequest {
+    pub target: u32,
+}
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct ExchangeInvitationReply {
+    pub accepted: bool,
+}
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct ExchangeConfirmRequest;
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct ExchangeApproveRequest;
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct ExchangeCancelRequest;
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+#[silkroad(size = 2)]
+pub enum ExchangeError {
+    #[silkroad(value = 0x3C01)]
+    InvalidTarget,
+    #[silkroad(value = 0x3C02)]
+    AlreadyExchanging,
+    #[silkroad(value = 0x3C03)]
+    TooFarAway,
+    #[silkroad(value = 0x3C04)]
+    InvitationDeclined,
+    #[silkroad(value = 0x3C05)]
+    InvitationPending,
+    #[silkroad(value = 0x3C06)]
+    NotExchanging,
+    #[silkroad(value = 0x3C07)]
+    NotConfirmed,
+    #[silkroad(value = 0x3C08)]
+    InventoryFull,
+    #[silkroad(value = 0x3C09)]
+    OfferChanged,
+}
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub enum ExchangeResponse {
+    #[silkroad(value = 1)]
+    Success,
+    #[silkroad(value = 2)]
+    Error(ExchangeError),
+}
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct ExchangeRequestResponse(pub ExchangeResponse);
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct ExchangeConfirmResponse(pub ExchangeResponse);
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct ExchangeApproveResponse(pub ExchangeResponse);
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct ExchangeCancelResponse(pub ExchangeResponse);
+
+/// Asks the receiver if they want to trade with the requesting player.
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct ExchangeInvitation {
+    pub requester: u32,
+}
+
+/// Opens the exchange window with the given partner.
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct ExchangeStarted {
+    pub partner: u32,
+}
+
+/// Informs the player that their partner has confirmed their offer.
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct ExchangeConfirmed;
+
+/// Informs the player that their partner has approved the exchange.
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct ExchangeApproved;
+
+/// Closes the exchange window after the items and gold have been swapped.
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct ExchangeCompleted;
+
+/// Closes the exchange window without swapping anything.
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct ExchangeCancelled;
+
+/// The current offer of one of the players taking part in the exchange. The slots of the items are their positions in
+/// the exchange window, not in the inventory.
+#[derive(Serialize, ByteSize, Clone)]
+pub struct ExchangeOfferUpdate {
+    pub owner: u32,
+    pub gold: u64,
+    pub items: Vec<InventoryItemData>,
+}
@@ -0,0 +1,164 @@
+use silkroad_serde::*;
+
+#[derive(Deserialize, Clone)]
+pub struct GuildCreateRequest {
+    pub name: String,
+}
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct GuildDisbandRequest;
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct GuildLeaveRequest;
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct GuildInviteRequest {
+    pub target: u32,
+}
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct GuildKickRequest {
+    pub character_id: u32,
+}
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct GuildInvitationReply {
+    pub accepted: bool,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct GuildNoticeRequest {
+    pub title: String,
+    pub notice: String,
+}
+
+#[derive(Deserialize, Serialize, ByteSize, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum GuildRank {
+    #[silkroad(value = 0)]
+    Master,
+    #[silkroad(value = 1)]
+    Officer,
+    #[silkroad(value = 2)]
+    Member,
+}
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct GuildRankRequest {
+    pub character_id: u32,
+    pub rank: GuildRank,
+}
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+#[silkroad(size = 2)]
+pub enum GuildError {
+    #[silkroad(value = 0x4C01)]
+    InvalidTarget,
+    #[silkroad(value = 0x4C02)]
+    AlreadyInGuild,
+    #[silkroad(value = 0x4C03)]
+    NotInGuild,
+    #[silkroad(value = 0x4C04)]
+    InsufficientRank,
+    #[silkroad(value = 0x4C05)]
+    GuildFull,
+    #[silkroad(value = 0x4C06)]
+    NameTaken,
+    #[silkroad(value = 0x4C07)]
+    InvalidName,
+    #[silkroad(value = 0x4C08)]
+    InvitationDeclined,
+    #[silkroad(value = 0x4C09)]
+    InvitationPending,
+    #[silkroad(value = 0x4C0A)]
+    MasterCannotLeave,
+}
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub enum GuildResponse {
+    #[silkroad(value = 1)]
+    Success,
+    #[silkroad(value = 2)]
+    Error(GuildError),
+}
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct GuildCreateResponse(pub GuildResponse);
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct GuildDisbandResponse(pub GuildResponse);
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct GuildLeaveResponse(pub GuildResponse);
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct GuildInviteResponse(pub GuildResponse);
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct GuildKickResponse(pub GuildResponse);
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct GuildNoticeResponse(pub GuildResponse);
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct GuildRankResponse(pub GuildResponse);
+
+/// Asks the receiver if they want to join the guild of the inviting player.
+#[derive(Serialize, ByteSize, Clone)]
+pub struct GuildInvitation {
+    pub inviter: u32,
+    pub guild_name: String,
+}
+
+#[derive(Serialize, ByteSize, Clone)]
+pub struct GuildMemberData {
+    pub character_id: u32,
+    pub name: String,
+    pub rank: GuildRank,
+    pub level: u8,
+    pub online: bool,
+}
+
+#[derive(Serialize, ByteSize, Clone)]
+pub struct GuildInfo {
+    pub id: u32,
+    pub name: String,
+    pub notice_title: String,
+    pub notice: String,
+    pub members: Vec<GuildMemberData>,
+}
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub enum GuildLeaveReason {
+    #[silkroad(value = 1)]
+    Left,
+    #[silkroad(value = 2)]
+    Kicked,
+}
+
+#[derive(Serialize, ByteSize, Clone)]
+pub enum GuildUpdate {
+    #[silkroad(value = 1)]
+    Disbanded,
+    #[silkroad(value = 2)]
+    MemberJoined(GuildMemberData),
+    #[silkroad(value = 3)]
+    MemberLeft {
+        character_id: u32,
+        reason: GuildLeaveReason,
+    },
+    #[silkroad(value = 4)]
+    MemberStatus { character_id: u32, online: bool },
+    #[silkroad(value = 6)]
+    RankChanged { character_id: u32, rank: GuildRank },
+    #[silkroad(value = 9)]
+    NoticeChanged { title: String, notice: String },
+}
+
+/// Informs surrounding players about the guild of a player changing, e.g. when joining or leaving a guild. An empty
+/// name means the player is no longer in a guild.
+#[derive(Serialize, ByteSize, Clone)]
+pub struct GuildNameUpdate {
+    pub unique_id: u32,
+    pub guild_id: u32,
+    pub name: String,
+}
@@ -10,12 +10,34 @@ pub enum InventoryOperationRequest {
     PickupItem { unique_id: u32 },
     #[silkroad(value = 0x07)]
     DropItem { slot: u8 },
+    #[silkroad(value = 0x08)]
+    BuyItem { tab: u8, slot: u8, amount: u16, npc: u32 },
+    #[silkroad(value = 0x09)]
+    SellItem { slot: u8, amount: u16, npc: u32 },
+    #[silkroad(value = 0x22)]
+    BuybackItem { npc: u32, slot: u8, amount: u16 },
+    #[silkroad(value = 0x04)]
+    OfferExchangeItem { slot: u8 },
+    #[silkroad(value = 0x05)]
+    RetractExchangeItem { slot: u8 },
+    #[silkroad(value = 0x0D)]
+    OfferExchangeGold { amount: u64 },
 }
 
 impl InventoryOperationRequest {
     pub fn dropgold(amount: u64) -> Self {
         InventoryOperationRequest::DropGold { amount }
     }
+
+    /// Whether this operation changes the offer of an ongoing exchange rather than the inventory itself.
+    pub fn is_exchange(&self) -> bool {
+        matches!(
+            self,
+            InventoryOperationRequest::OfferExchangeItem { .. }
+                | InventoryOperationRequest::RetractExchangeItem { .. }
+                | InventoryOperationRequest::OfferExchangeGold { .. }
+        )
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, ByteSize)]
@@ -102,6 +124,32 @@ pub enum InventoryOperationResponseData {
     DropGold { amount: u64 },
     #[silkroad(value = 0x06)]
     PickupItem { slot: u8, item: ItemPickupData },
+    #[silkroad(value = 0x08)]
+    BuyItem {
+        tab: u8,
+        slot: u8,
+        target_slots: Vec<u8>,
+        amount: u16,
+    },
+    #[silkroad(value = 0x09)]
+    SellItem {
+        slot: u8,
+        amount: u16,
+        npc: u32,
+        buyback_slot: u8,
+    },
+    #[silkroad(value = 0x22)]
+    BuybackItem {
+        target_slot: u8,
+        buyback_slot: u8,
+        amount: u16,
+    },
+    #[silkroad(value = 0x04)]
+    OfferExchangeItem { slot: u8 },
+    #[silkroad(value = 0x05)]
+    RetractExchangeItem { slot: u8 },
+    #[silkroad(value = 0x0D)]
+    OfferExchangeGold { amount: u64 },
     #[silkroad(value = 0x0e)]
     AddedByServer {
         slot: u8,
@@ -400,6 +448,22 @@ pub struct InventoryOperation {
     pub data: InventoryOperationRequest,
 }
 
+/// Uses the consumable item in the given inventory slot. The type is the packed type id of the item, which has to match
+/// the item that is actually in that slot.
+#[derive(Clone, Deserialize, ByteSize)]
+pub struct UseItemRequest {
+    pub slot: u8,
+    pub item_type: u16,
+}
+
+#[derive(Clone, Serialize, ByteSize)]
+pub enum UseItemResponse {
+    #[silkroad(value = 1)]
+    Success { slot: u8, remaining: u16, item_type: u16 },
+    #[silkroad(value = 2)]
+    Error(InventoryOperationError),
+}
+
 #[derive(Clone, Deserialize, ByteSize)]
 pub struct OpenItemMall;
 
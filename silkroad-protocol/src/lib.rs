@@ -1,24 +1,32 @@
+use crate::alchemy::*;
 use crate::auth::*;
 use crate::character::*;
 use crate::chat::*;
 use crate::combat::*;
 use crate::error::ProtocolError;
+use crate::exchange::*;
 use crate::general::*;
 use crate::gm::*;
+use crate::guild::*;
 use crate::login::*;
+use crate::party::*;
 use crate::skill::*;
 use crate::world::*;
 use bytes::Bytes;
 
+pub mod alchemy;
 pub mod auth;
 pub mod character;
 pub mod chat;
 pub mod combat;
 pub mod error;
+pub mod exchange;
 pub mod general;
 pub mod gm;
+pub mod guild;
 pub mod inventory;
 pub mod login;
+pub mod party;
 pub mod skill;
 pub mod world;
 
@@ -59,10 +67,14 @@ client_packets! {
     0x7302 => AddFriend,
     0x7310 => CreateFriendGroup,
     0x7304 => DeleteFriend,
+    0x3303 => FriendRequestReply,
     0x7024 => Rotation,
     0x7045 => TargetEntity,
     0x704B => UnTargetEntity,
+    0x7046 => OpenNpcTalk,
     0x7034 => InventoryOperation,
+    0x704C => UseItemRequest,
+    0x7150 => AlchemyReinforceRequest,
     0x7025 => ChatMessage,
     0x6100 => PatchRequest,
     0x610A => LoginRequest,
@@ -83,7 +95,27 @@ client_packets! {
     0x70A2 => LevelUpMastery,
     0x70A1 => LearnSkill,
     0x7050 => IncreaseStr,
-    0x7051 => IncreaseInt
+    0x7051 => IncreaseInt,
+    0x3053 => ResurrectRequest,
+    0x7060 => PartyCreateRequest,
+    0x7061 => PartyLeaveRequest,
+    0x7062 => PartyInviteRequest,
+    0x7063 => PartyKickRequest,
+    0x7064 => PartyLeaderTransferRequest,
+    0x3080 => PartyInvitationReply,
+    0x7081 => ExchangeRequest,
+    0x3081 => ExchangeInvitationReply,
+    0x7082 => ExchangeConfirmRequest,
+    0x7083 => ExchangeApproveRequest,
+    0x7084 => ExchangeCancelRequest,
+    0x70F0 => GuildCreateRequest,
+    0x70F1 => GuildDisbandRequest,
+    0x70F2 => GuildLeaveRequest,
+    0x70F3 => GuildInviteRequest,
+    0x70F4 => GuildKickRequest,
+    0x70F9 => GuildNoticeRequest,
+    0x70FA => GuildRankRequest,
+    0x30F3 => GuildInvitationReply
 }
 
 macro_rules! server_packets {
@@ -133,11 +165,17 @@ server_packets! {
     0xB50E => ConsignmentResponse,
     0x3809 => WeatherUpdate,
     0x3305 => FriendListInfo,
+    0x3302 => FriendRequest,
+    0x3306 => FriendUpdate,
+    0xB302 => AddFriendResponse,
+    0xB310 => CreateFriendGroupResponse,
+    0xB304 => DeleteFriendResponse,
     0x300C => GameNotification,
     0xB021 => PlayerMovementResponse,
     0x30BF => EntityUpdateState,
     0xB045 => TargetEntityResponse,
     0xB04B => UnTargetEntityResponse,
+    0xB046 => OpenNpcTalkResponse,
     0x3535 => TextCharacterInitialization,
     0x3555 => MacroStatus,
     0x3026 => ChatUpdate,
@@ -159,21 +197,56 @@ server_packets! {
     0x2212 => Disconnect,
     0x3057 => EntityBarsUpdate,
     0xB034 => InventoryOperationResult,
+    0xB04C => UseItemResponse,
+    0xB150 => AlchemyReinforceResponse,
     0xB010 => GmResponse,
     0xB55D => OpenItemMallResponse,
     0xB074 => PerformActionResponse,
     0xB070 => PerformActionUpdate,
+    0xB0BD => BuffAdded,
+    0xB072 => BuffRemoved,
     0x304E => CharacterPointsUpdate,
     0xB0EA => GameGuideResponse,
     0xB023 => EntityMovementInterrupt,
     0x3036 => PlayerPickupAnimation,
     0x30D0 => ChangeSpeed,
     0x3054 => LevelUpEffect,
+    0x3011 => CharacterDied,
     0x3056 => ReceiveExperience,
     0xB0A2 => LevelUpMasteryResponse,
     0xB0A1 => LearnSkillResponse,
     0xB050 => IncreaseStrResponse,
-    0xB051 => IncreaseIntResponse
+    0xB051 => IncreaseIntResponse,
+    0xB060 => PartyCreateResponse,
+    0xB061 => PartyLeaveResponse,
+    0xB062 => PartyInviteResponse,
+    0xB063 => PartyKickResponse,
+    0xB064 => PartyLeaderTransferResponse,
+    0x3080 => PartyInvitation,
+    0xB081 => ExchangeRequestResponse,
+    0xB082 => ExchangeConfirmResponse,
+    0xB083 => ExchangeApproveResponse,
+    0xB084 => ExchangeCancelResponse,
+    0x3081 => ExchangeInvitation,
+    0x3085 => ExchangeStarted,
+    0x3086 => ExchangeConfirmed,
+    0x3087 => ExchangeApproved,
+    0x3088 => ExchangeCompleted,
+    0x3089 => ExchangeCancelled,
+    0x308C => ExchangeOfferUpdate,
+    0x3065 => PartyData,
+    0x3864 => PartyUpdate,
+    0xB0F0 => GuildCreateResponse,
+    0xB0F1 => GuildDisbandResponse,
+    0xB0F2 => GuildLeaveResponse,
+    0xB0F3 => GuildInviteResponse,
+    0xB0F4 => GuildKickResponse,
+    0xB0F9 => GuildNoticeResponse,
+    0xB0FA => GuildRankResponse,
+    0x30F3 => GuildInvitation,
+    0x3101 => GuildInfo,
+    0x38F5 => GuildUpdate,
+    0x30FF => GuildNameUpdate
 }
 
 impl ServerPacket {
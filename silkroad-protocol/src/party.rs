@@ -0,0 +1,123 @@
+use crate::world::Position;
+use silkroad_serde::*;
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct PartyCreateRequest {
+    pub target: u32,
+    pub settings: u8,
+}
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct PartyInviteRequest {
+    pub target: u32,
+}
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct PartyLeaveRequest;
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct PartyKickRequest {
+    pub member: u32,
+}
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct PartyLeaderTransferRequest {
+    pub member: u32,
+}
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct PartyInvitationReply {
+    pub accepted: bool,
+}
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+#[silkroad(size = 2)]
+pub enum PartyError {
+    #[silkroad(value = 0x2C01)]
+    InvalidTarget,
+    #[silkroad(value = 0x2C02)]
+    AlreadyInParty,
+    #[silkroad(value = 0x2C03)]
+    NotInParty,
+    #[silkroad(value = 0x2C04)]
+    NotLeader,
+    #[silkroad(value = 0x2C05)]
+    PartyFull,
+    #[silkroad(value = 0x2C06)]
+    InvitationDeclined,
+    #[silkroad(value = 0x2C07)]
+    InvitationPending,
+}
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub enum PartyResponse {
+    #[silkroad(value = 1)]
+    Success,
+    #[silkroad(value = 2)]
+    Error(PartyError),
+}
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct PartyCreateResponse(pub PartyResponse);
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct PartyInviteResponse(pub PartyResponse);
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct PartyLeaveResponse(pub PartyResponse);
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct PartyKickResponse(pub PartyResponse);
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct PartyLeaderTransferResponse(pub PartyResponse);
+
+/// Asks the receiver if they want to join the party of the inviting player.
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct PartyInvitation {
+    pub inviter: u32,
+    pub settings: u8,
+}
+
+#[derive(Serialize, ByteSize, Clone)]
+pub struct PartyMemberData {
+    pub unique_id: u32,
+    pub name: String,
+    pub ref_id: u32,
+    pub level: u8,
+    /// The health in the upper four bits and the mana in the lower four bits, each in tenths of the maximum.
+    pub hp_mp: u8,
+    pub position: Position,
+}
+
+#[derive(Serialize, ByteSize, Clone)]
+pub struct PartyData {
+    pub party_id: u32,
+    pub leader: u32,
+    pub settings: u8,
+    pub members: Vec<PartyMemberData>,
+}
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub enum PartyLeaveReason {
+    #[silkroad(value = 1)]
+    Left,
+    #[silkroad(value = 2)]
+    Kicked,
+    #[silkroad(value = 3)]
+    Disconnected,
+}
+
+#[derive(Serialize, ByteSize, Clone)]
+pub enum PartyUpdate {
+    #[silkroad(value = 1)]
+    Dismissed,
+    #[silkroad(value = 2)]
+    MemberJoined(PartyMemberData),
+    #[silkroad(value = 3)]
+    MemberLeft { unique_id: u32, reason: PartyLeaveReason },
+    #[silkroad(value = 6)]
+    MemberBars { unique_id: u32, hp_mp: u8 },
+    #[silkroad(value = 9)]
+    LeaderChanged { unique_id: u32 },
+}
@@ -442,12 +442,12 @@ impl TargetEntityResult {
         }
     }
 
-    pub fn success_npc(unique_id: u32) -> Self {
+    pub fn success_npc(unique_id: u32, talk_options: Vec<u8>) -> Self {
         TargetEntityResult::Success {
             unique_id,
             health: None,
             entity_data: TargetEntityData::NPC {
-                talk_options: Some(InteractOptions::talk(vec![])),
+                talk_options: Some(InteractOptions::talk(talk_options)),
             },
         }
     }
@@ -1132,6 +1132,72 @@ pub struct DeleteFriend {
     pub friend_character_id: u32,
 }
 
+/// The answer of a player to a friend request, identifying the requesting player by their unique id.
+#[derive(Clone, Deserialize, ByteSize)]
+pub struct FriendRequestReply {
+    pub requester: u32,
+    pub accepted: bool,
+}
+
+#[derive(Clone, Serialize, ByteSize)]
+pub struct FriendRequest {
+    pub requester: u32,
+    pub name: String,
+}
+
+#[derive(Clone, Serialize, ByteSize)]
+#[silkroad(size = 2)]
+pub enum FriendError {
+    #[silkroad(value = 0x6401)]
+    InvalidTarget,
+    #[silkroad(value = 0x6402)]
+    AlreadyFriends,
+    #[silkroad(value = 0x6403)]
+    RequestDeclined,
+    #[silkroad(value = 0x6404)]
+    RequestPending,
+    #[silkroad(value = 0x6405)]
+    NotFriends,
+    #[silkroad(value = 0x6406)]
+    GroupExists,
+}
+
+#[derive(Clone, Serialize, ByteSize)]
+pub enum AddFriendResponse {
+    #[silkroad(value = 1)]
+    Success(FriendListEntry),
+    #[silkroad(value = 2)]
+    Error(FriendError),
+}
+
+#[derive(Clone, Serialize, ByteSize)]
+pub enum CreateFriendGroupResponse {
+    #[silkroad(value = 1)]
+    Success(FriendListGroup),
+    #[silkroad(value = 2)]
+    Error(FriendError),
+}
+
+#[derive(Clone, Serialize, ByteSize)]
+pub enum DeleteFriendResponse {
+    #[silkroad(value = 1)]
+    Success { friend_character_id: u32 },
+    #[silkroad(value = 2)]
+    Error(FriendError),
+}
+
+#[derive(Clone, Serialize, ByteSize)]
+pub enum FriendUpdate {
+    #[silkroad(value = 2)]
+    Added(FriendListEntry),
+    #[silkroad(value = 3)]
+    Removed { char_id: u32 },
+    #[silkroad(value = 9)]
+    Online { char_id: u32 },
+    #[silkroad(value = 10)]
+    Offline { char_id: u32 },
+}
+
 #[derive(Clone, Serialize, Deserialize, ByteSize)]
 pub struct Rotation {
     pub heading: u16,
@@ -1203,6 +1269,35 @@ pub struct UnTargetEntity {
     pub unique_id: u32,
 }
 
+#[derive(Serialize, Deserialize, ByteSize, Copy, Clone, Eq, PartialEq)]
+pub enum TalkOption {
+    #[silkroad(value = 1)]
+    Store,
+}
+
+impl From<TalkOption> for u8 {
+    fn from(option: TalkOption) -> Self {
+        match option {
+            TalkOption::Store => 1,
+        }
+    }
+}
+
+/// Opens one of the talk options of the currently selected NPC, e.g. its shop.
+#[derive(Deserialize, Copy, Clone)]
+pub struct OpenNpcTalk {
+    pub unique_id: u32,
+    pub option: TalkOption,
+}
+
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub enum OpenNpcTalkResponse {
+    #[silkroad(value = 1)]
+    Success(TalkOption),
+    #[silkroad(value = 2)]
+    Error,
+}
+
 #[derive(Serialize, ByteSize, Copy, Clone)]
 pub struct UnTargetEntityResponse {
     pub success: bool,
@@ -1315,6 +1410,29 @@ pub struct ReceiveExperience {
     pub new_level: Option<u16>,
 }
 
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub enum DeathKind {
+    #[silkroad(value = 4)]
+    Killed,
+}
+
+/// Informs the player that their character died, prompting them to choose where they want to be resurrected.
+#[derive(Serialize, ByteSize, Copy, Clone)]
+pub struct CharacterDied(pub DeathKind);
+
+#[derive(Deserialize, Copy, Clone)]
+pub enum ResurrectKind {
+    /// Resurrects the character at the return point of the closest town.
+    #[silkroad(value = 1)]
+    ReturnPoint,
+    /// Resurrects the character where they died.
+    #[silkroad(value = 2)]
+    PresentPoint,
+}
+
+#[derive(Deserialize, Copy, Clone)]
+pub struct ResurrectRequest(pub ResurrectKind);
+
 #[derive(Serialize, ByteSize, Copy, Clone)]
 pub struct LevelUpEffect {
     /// Unique ID of the entity that levelled up